@@ -2,25 +2,157 @@
 
 import { spawn } from 'node:child_process'
 import fs from 'node:fs'
+import os from 'node:os'
 import path from 'node:path'
 import { Command } from 'commander'
+import {
+	ALLOW_DIRECTIVE_RULE_ID,
+	applyAllowDirectives,
+	collectAllowDirectives,
+} from '../scripts/allows.ts'
+import {
+	applyBaseline,
+	type BaselineEntry,
+	type BaselineFile,
+	createBaseline,
+	DEFAULT_BASELINE_FILE,
+	formatBaseline,
+	loadBaseline,
+} from '../scripts/baseline.ts'
+import { checkBudgets, failsRun, formatBudget } from '../scripts/budgets.ts'
+import {
+	CACHE_DIR,
+	computeRuleSetHash,
+	hashContent,
+	type LintCache,
+	loadCache,
+	partitionByCache,
+	saveCache,
+	updateCache,
+} from '../scripts/cache.ts'
+import {
+	applyCrateTargets,
+	CARGO_MANIFEST,
+	type CargoCrate,
+	classifyTargetFiles,
+	findWorkspaceCrates,
+	type TargetKind,
+} from '../scripts/cargo.ts'
+import { applyAsCasts } from '../scripts/casts.ts'
+import {
+	AS_CAST_RULE_ID,
+	applyMessageTemplates,
+	applyRuleLevels,
+	applyRuleLevelsToMetadata,
+	enableTestRules,
+	FUNCTION_LENGTH_RULE_ID,
+	IMPL_TRAIT_RULE_ID,
+	LARGE_ENUM_VARIANT_RULE_ID,
+	LINE_LENGTH_RULE_ID,
+	type LintConfig,
+	loadConfig,
+	MUST_USE_RULE_ID,
+	RULE_LEVEL,
+	resolveRuleId,
+	SHADOWING_RULE_ID,
+	selectRules,
+	TEST_RULE_IDS,
+	WILDCARD_IMPORT_RULE_ID,
+} from '../scripts/config.ts'
+import { type ChangedLines, filterChangedLines, parseUnifiedDiff } from '../scripts/diff.ts'
+import { applyLargeEnumVariants } from '../scripts/enum-variants.ts'
+import {
+	buildGlobArgs,
+	buildIgnoreArgs,
+	dedupeScanPaths,
+	discoverFiles,
+	type FileFilter,
+	getLanguageExtensions,
+	parseFileList,
+	splitExcludes,
+} from '../scripts/files.ts'
+import { applyFixes } from '../scripts/fix.ts'
+import { applyFunctionLength } from '../scripts/function-length.ts'
+import {
+	buildSummary,
+	COLOR_MODE,
+	getFormatter,
+	isValidColorMode,
+	isValidOutputFormat,
+	OUTPUT_FORMAT,
+	type OutputFormat,
+	resolveColor,
+	VALID_COLOR_MODES,
+	VALID_OUTPUT_FORMATS,
+} from '../scripts/formatters.ts'
+import type { IgnorePattern } from '../scripts/globs.ts'
+import { applyImplTraitSignatures } from '../scripts/impl-trait.ts'
+import { applyLineLength } from '../scripts/line-length.ts'
+import { applyMustUseResults } from '../scripts/must-use.ts'
+import { applySeverityOverrides } from '../scripts/overrides.ts'
+import { describeParseError, filesFailingToParse, isParseError } from '../scripts/parse-errors.ts'
+import { loadPathRules } from '../scripts/path-rules.ts'
+import {
+	applyRules,
+	getLanguageRules,
+	loadPlugins,
+	type Rule,
+	toRuleMetadata,
+} from '../scripts/plugins.ts'
+import {
+	applyAllowedPaths,
+	buildRuleOverrides,
+	buildVendorOverrides,
+	dropLibraryIgnores,
+} from '../scripts/rule-options.ts'
+import { applyShadowing } from '../scripts/shadowing.ts'
 import {
 	countBySeverity,
 	createTempConfig,
+	EXIT_CODE,
 	filterExcludedRules,
+	findRepoRoot,
 	generateConfigContent,
+	getDirectiveDirs,
 	getRuleDirs,
 	getRuleDirsRelative,
+	getRuleMetadata,
 	getValidRuleIds,
+	groupByFile,
+	isQuiet,
 	isValidLanguage,
 	LANG,
 	type Language,
 	type LintIssue,
 	PACKAGE_ROOT,
+	PARSE_ERROR_RULE_ID,
 	parseLintIssues,
+	pluralize,
+	relativizePath,
+	reportError,
+	setQuiet,
+	sortIssues,
 	VALID_LANGUAGES,
 	warn,
 } from '../scripts/shared.ts'
+import {
+	createStats,
+	formatStats,
+	type RunStats,
+	recordRuleTime,
+	startPhase,
+	timePhase,
+} from '../scripts/stats.ts'
+import {
+	CLEAR_SCREEN,
+	debounce,
+	isWatchedChange,
+	WATCH_DEBOUNCE_MS,
+	watchPath,
+} from '../scripts/watch.ts'
+import { applyWildcardImports } from '../scripts/wildcard-imports.ts'
+
+type ExitCode = (typeof EXIT_CODE)[keyof typeof EXIT_CODE]
 
 function getVersion(): string {
 	const pkgPath = path.join(PACKAGE_ROOT, 'package.json')
@@ -29,175 +161,1239 @@ function getVersion(): string {
 }
 
 interface ScanOptions {
+	config?: string
 	exclude?: string
+	excludePath?: string
+	include?: string
+	ignore?: boolean
+	cache?: boolean
+	summary?: boolean
+	threads?: string
+	format?: string
 	json?: boolean
 	fix?: boolean
 	githubAction?: boolean
+	warnUnusedAllows?: boolean
+	baseline?: string
+	writeBaseline?: boolean
+	stdinFilename?: string
+	quiet?: boolean
+	rules?: string
+	skipRules?: string
+	watch?: boolean
+	relativeTo?: string
+	workspace?: boolean
+	testsOnly?: boolean
+	filesFrom?: string
+	diff?: string
+	color?: string
+	snippets?: boolean
+	context?: string
+	stats?: boolean
+	slowest?: string
+	maxErrors?: string
 }
 
-function runScan(language: string, scanPath: string, options: ScanOptions): void {
+// Above this many changed files, rescanning the whole path is cheaper than passing each file
+const MAX_CACHED_SCAN_TARGETS = 200
+
+async function runScan(language: string, paths: string[], options: ScanOptions): Promise<void> {
+	setQuiet(options.quiet ?? false)
+	// Started first, so the total also covers loading the config and rules
+	const stats = resolveStats(options)
+
 	if (!isValidLanguage(language)) {
-		console.error(
-			`Error: Invalid language '${language}'. Must be one of: ${VALID_LANGUAGES.join(', ')}`,
-		)
-		process.exit(1)
+		fail(`Invalid language '${language}'. Must be one of: ${VALID_LANGUAGES.join(', ')}`)
+	}
+	const stdinFilename = resolveStdinFilename(language, paths, options)
+	const fileList = options.filesFrom === undefined ? null : readFileList(language, paths, options)
+	const workspace = options.workspace ? loadWorkspace(language, paths) : null
+	const diff = readDiff(paths, options)
+	const scanPaths =
+		fileList ?? workspace?.scanPaths ?? dedupeScanPaths(paths.length > 0 ? paths : ['.'], '.')
+	for (const scanPath of stdinFilename === null ? scanPaths : []) {
+		if (!fs.existsSync(scanPath)) {
+			fail(`Path '${scanPath}' does not exist`)
+		}
+	}
+	if (options.watch && (options.fix || options.writeBaseline)) {
+		fail(`${options.fix ? '--fix' : '--write-baseline'} cannot be used with --watch`)
 	}
 
-	const ruleDirs = getRuleDirs(language)
-	const { configPath, cleanup } = createTempConfig(ruleDirs)
+	const format = resolveOutputFormat(options)
+	const color = resolveColorOption(options.color)
+	const threads = parseThreads(options.threads)
+	const contextLines = parseContextLines(options.context)
+	const maxErrors = parseMaxErrors(options.maxErrors)
+	const { config: projectConfig, plugins } = await loadProjectConfig(options.config)
+	const rules = [...loadLanguagePathRules(language), ...getLanguageRules(plugins, language)]
+	const validRuleIds = new Set([...getValidRuleIds(language), ...rules.map((rule) => rule.id)])
+	const selection = resolveRuleSelection(language, validRuleIds, options)
+	const config = enableTestRules(projectConfig, selection.testRules)
+	checkOptInSelection(language, selection.only, config)
 
-	const excludeRules = options.exclude?.split(',').map((r) => r.trim()) ?? []
+	const ruleDirs = [...getRuleDirs(language), ...getDirectiveDirs(language)]
+	let ruleOverrides: Map<string, string>
+	try {
+		ruleOverrides = buildRuleOverrides(ruleDirs, config)
+	} catch (err) {
+		fail((err as Error).message)
+	}
+	// With --workspace the crates' targets, not file paths, decide what's library code
+	const crateTargets = workspace && {
+		kinds: workspace.kinds,
+		fallback: dropLibraryIgnores(ruleOverrides, ruleDirs),
+	}
+	const excludes = splitExcludes(splitList(options.exclude), validRuleIds)
+	const excludePaths = [...excludes.paths, ...splitList(options.excludePath)]
 
-	runAstGrep(language, configPath, scanPath, {
-		json: options.json ?? false,
+	// Globs given on the command line replace the ones from the config file
+	const include = splitList(options.include)
+	const fileFilter: FileFilter = {
+		include: include.length > 0 ? include : (config.include ?? []),
+		exclude: excludePaths.length > 0 ? excludePaths : (config.exclude ?? []),
+	}
+	// commander sets `ignore: false` for --no-ignore
+	const noIgnore = options.ignore === false
+
+	const reportOptions: Omit<ReportOptions, 'scannedFiles'> = {
+		format,
 		fix: options.fix ?? false,
-		githubAction: options.githubAction ?? false,
-		excludeRules,
-		cleanup,
+		warnUnusedAllows: options.warnUnusedAllows ?? false,
+		excludeRules: excludes.rules.map((id) => resolveRuleId(id, '--exclude')),
+		config: selectRules(config, validRuleIds, selection.only, selection.skip),
+		scanPaths,
+		baseline: resolveBaseline(options),
+		// commander sets `summary: false` for --no-summary
+		summary: options.summary !== false,
+		relativeTo: resolveRelativeTo(options.relativeTo),
+		crateTargets,
+		rules,
+		diff,
+		color,
+		// commander sets `snippets: false` for --no-snippets
+		snippets: options.snippets === false ? null : { context: contextLines },
+		stats,
+		maxErrors,
+	}
+	const scanOptions = { fileFilter, noIgnore, threads, ruleOverrides, maxErrors }
+
+	// ast-grep scans the cwd when given no paths, so a list with nothing to lint stops here
+	if (scanPaths.length === 0) {
+		process.exitCode = reportIssues(language, [], { ...reportOptions, scannedFiles: [] })
+		return
+	}
+
+	if (stdinFilename !== null) {
+		runStdinScan(language, ruleDirs, stdinFilename, scanOptions, {
+			...reportOptions,
+			scanPaths: [stdinFilename],
+			scannedFiles: [stdinFilename],
+		})
+		return
+	}
+
+	const scan: FileScan = { language, ruleDirs, scanPaths, scanOptions, stats }
+	// commander sets `cache: false` for --no-cache
+	const cache =
+		options.cache === false ? null : loadLanguageCache(language, ruleDirs, config, ruleOverrides)
+
+	if (options.watch) {
+		runWatch(scan, reportOptions, cache)
+		return
+	}
+
+	if (cache === null) {
+		const files = findFiles(scan)
+		const stopScan = startPhase(stats, 'scan')
+		runAstGrep(language, ruleDirs, scanPaths, scanOptions, (scanned) => {
+			stopScan()
+			process.exitCode = reportIssues(language, scanned, {
+				...reportOptions,
+				scannedFiles: files,
+			})
+		})
+		return
+	}
+
+	scanFiles(scan, cache, ({ issues, files }) => {
+		writeCache(cache)
+		process.exitCode = reportIssues(language, issues, { ...reportOptions, scannedFiles: files })
 	})
 }
 
+interface FileScan {
+	language: Language
+	ruleDirs: string[]
+	scanPaths: string[]
+	scanOptions: AstGrepOptions & { fileFilter: FileFilter }
+	stats: RunStats | null
+}
+
+interface FileScanResult {
+	issues: LintIssue[]
+	files: string[]
+	// Files rescanned because they're new or their contents changed, plus removed files
+	changed: string[]
+}
+
+// The same files ast-grep will scan; the summary reports how many there were
+function findFiles(scan: FileScan): string[] {
+	const options = {
+		extensions: getLanguageExtensions(scan.language),
+		filter: scan.scanOptions.fileFilter,
+		noIgnore: scan.scanOptions.noIgnore,
+		cwd: process.cwd(),
+	}
+	try {
+		// Scan paths don't overlap, so no file is found twice
+		return timePhase(scan.stats, 'discovery', () =>
+			scan.scanPaths.flatMap((scanPath) => discoverFiles(scanPath, options)).sort(),
+		)
+	} catch (err) {
+		fail((err as Error).message)
+	}
+}
+
+function loadLanguageCache(
+	language: Language,
+	ruleDirs: string[],
+	config: LintConfig,
+	ruleOverrides: Map<string, string>,
+): LintCache {
+	return loadCache(
+		path.join(findRepoRoot(process.cwd()), CACHE_DIR, `${language}.json`),
+		computeRuleSetHash(ruleDirs, config, getVersion(), ruleOverrides),
+	)
+}
+
+/**
+ * --files-from reads the files to lint from a newline-separated list, or from stdin with `-`,
+ * e.g. the output of `git diff --cached --name-only`. Entries that can't be linted are skipped
+ * with a warning, so the list may hold deleted files and other languages.
+ */
+function readFileList(language: Language, paths: string[], options: ScanOptions): string[] {
+	if (paths.length > 0) {
+		fail('--files-from takes the place of paths to scan')
+	}
+	if (options.stdinFilename !== undefined) {
+		fail('--files-from cannot be used with --stdin-filename')
+	}
+	if (options.workspace) {
+		fail('--files-from cannot be used with --workspace')
+	}
+	if (options.watch) {
+		fail('--files-from cannot be used with --watch')
+	}
+
+	const source = options.filesFrom === '-' ? 'stdin' : options.filesFrom!
+	let content: string
+	try {
+		content = fs.readFileSync(options.filesFrom === '-' ? 0 : options.filesFrom!, 'utf8')
+	} catch (err) {
+		fail(`Could not read the file list from ${source}: ${(err as Error).message}`)
+	}
+	const { files, skipped } = parseFileList(content, getLanguageExtensions(language), '.')
+	for (const entry of skipped) {
+		warn(`Skipping '${entry.file}' from --files-from: ${entry.reason}`)
+	}
+	return files
+}
+
+/**
+ * --diff reads a unified diff from a file, or from stdin with `-`, and limits the report to
+ * findings on the lines it adds or changes. Its paths are relative to the repository root, as
+ * `git diff` prints them.
+ */
+function readDiff(paths: string[], options: ScanOptions): ReportOptions['diff'] {
+	if (options.diff === undefined) {
+		return null
+	}
+	const stdinTaken = paths.includes('-') || options.stdinFilename !== undefined
+	if (options.diff === '-' && (stdinTaken || options.filesFrom === '-')) {
+		fail(`--diff can't read stdin when ${stdinTaken ? 'linting stdin' : '--files-from reads it'}`)
+	}
+	if (options.writeBaseline) {
+		fail('--diff cannot be used with --write-baseline')
+	}
+	if (options.watch) {
+		fail('--diff cannot be used with --watch')
+	}
+
+	const source = options.diff === '-' ? 'stdin' : options.diff
+	let content: string
+	try {
+		content = fs.readFileSync(options.diff === '-' ? 0 : options.diff, 'utf8')
+	} catch (err) {
+		fail(`Could not read the diff from ${source}: ${(err as Error).message}`)
+	}
+	try {
+		return { changed: parseUnifiedDiff(content), baseDir: findRepoRoot(process.cwd()) }
+	} catch (err) {
+		fail(`Could not parse the diff from ${source}: ${(err as Error).message}`)
+	}
+}
+
+function loadLanguagePathRules(language: Language): Rule[] {
+	try {
+		return loadPathRules(language)
+	} catch (err) {
+		fail((err as Error).message)
+	}
+}
+
+interface Workspace {
+	// Directories of the member crates, relative to the cwd
+	scanPaths: string[]
+	kinds: Map<string, TargetKind>
+}
+
+// --workspace takes the directory with the workspace's Cargo.toml in place of paths to scan
+function loadWorkspace(language: Language, paths: string[]): Workspace {
+	if (language !== LANG.RUST) {
+		fail(`--workspace only works with language '${LANG.RUST}'`)
+	}
+	if (paths.length > 1) {
+		fail(`--workspace takes the directory containing ${CARGO_MANIFEST}, not paths to scan`)
+	}
+	let crates: CargoCrate[]
+	try {
+		crates = findWorkspaceCrates(paths[0] ?? '.')
+	} catch (err) {
+		fail((err as Error).message)
+	}
+	const dirs = crates.map((crate) => path.relative(process.cwd(), crate.dir) || '.')
+	return { scanPaths: dedupeScanPaths(dirs, '.'), kinds: classifyTargetFiles(crates) }
+}
+
+/**
+ * Discover the files to lint and rescan only those whose cached results are out of date.
+ * `onResult` gets the findings for every file; `cache` is updated but not written.
+ */
+function scanFiles(
+	scan: FileScan,
+	cache: LintCache,
+	onResult: (result: FileScanResult) => void,
+): void {
+	const files = findFiles(scan)
+	let hashes: Map<string, string>
+	try {
+		hashes = timePhase(
+			scan.stats,
+			'discovery',
+			() => new Map(files.map((file) => [file, hashContent(fs.readFileSync(file))])),
+		)
+	} catch (err) {
+		fail((err as Error).message)
+	}
+	const removed = Object.keys(cache.entries).filter((file) => !hashes.has(file))
+	const { cached, stale } = partitionByCache(cache, hashes)
+	const fullScan = stale.length > MAX_CACHED_SCAN_TARGETS
+	if (scan.stats) {
+		scan.stats.filesCached = fullScan ? 0 : files.length - stale.length
+	}
+
+	if (stale.length === 0) {
+		updateCache(cache, hashes, [], [])
+		onResult({ issues: cached, files, changed: removed })
+		return
+	}
+
+	// Changed files were already filtered by discoverFiles, so they're passed as-is
+	const targets = fullScan ? scan.scanPaths : stale
+	const targetOptions = fullScan ? scan.scanOptions : { ...scan.scanOptions, fileFilter: null }
+
+	const stopScan = startPhase(scan.stats, 'scan')
+	runAstGrep(scan.language, scan.ruleDirs, targets, targetOptions, (fresh, stopped) => {
+		stopScan()
+		// Key issues the same way as discovered files so they line up with cache entries
+		const scanned = fresh.map((issue) => ({ ...issue, file: path.normalize(issue.file) }))
+		// A scan stopped by --max-errors didn't reach every file, so none of it is cached
+		if (!stopped) {
+			updateCache(cache, hashes, fullScan ? files : stale, scanned)
+		}
+		onResult({
+			issues: fullScan ? scanned : [...cached, ...scanned],
+			files,
+			changed: [...stale, ...removed],
+		})
+	})
+}
+
+/**
+ * Lint once, then again whenever something under the scan path changes, until interrupted.
+ * Every run discovers the files anew, so new files matching the include globs are picked up,
+ * and the cache limits rescanning to files whose contents changed. Rule files and the config
+ * are only read at startup. With `cache` null (--no-cache) results are kept in memory only.
+ */
+function runWatch(
+	scan: FileScan,
+	reportOptions: Omit<ReportOptions, 'scannedFiles'>,
+	cache: LintCache | null,
+): void {
+	const results = cache ?? { path: '', ruleSetHash: '', entries: {} }
+	const extensions = getLanguageExtensions(scan.language)
+	let running = false
+	let pending = false
+	let firstRun = true
+
+	const lint = () => {
+		running = true
+		scanFiles(scan, results, ({ issues, files, changed }) => {
+			if (cache && changed.length > 0) writeCache(cache)
+			// Saving a file without changing it, or touching an unrelated one, leaves the report as is
+			if (firstRun || changed.length > 0) {
+				if (!isQuiet() && process.stdout.isTTY) {
+					process.stdout.write(CLEAR_SCREEN)
+				}
+				reportIssues(scan.language, issues, { ...reportOptions, scannedFiles: files })
+				if (!isQuiet()) {
+					const relinted = firstRun
+						? ''
+						: `Re-linted ${changed.length} changed ${pluralize(changed.length, 'file')}. `
+					const watched = scan.scanPaths.join(', ')
+					console.error(`${relinted}Watching ${watched} for changes. Press Ctrl-C to stop.`)
+				}
+				firstRun = false
+			}
+
+			running = false
+			if (pending) {
+				pending = false
+				lint()
+			}
+		})
+	}
+
+	// A change during a run is picked up by one more run right after it
+	const scheduled = debounce(WATCH_DEBOUNCE_MS, () => {
+		if (running) {
+			pending = true
+		} else {
+			lint()
+		}
+	})
+
+	let stopWatching: Array<() => void>
+	try {
+		stopWatching = scan.scanPaths.map((scanPath) =>
+			watchPath(scanPath, (file) => {
+				if (isWatchedChange(file, extensions)) scheduled.trigger()
+			}),
+		)
+	} catch (err) {
+		fail((err as Error).message)
+	}
+
+	const stop = () => {
+		scheduled.cancel()
+		for (const stopPath of stopWatching) stopPath()
+		process.exit(EXIT_CODE.SUCCESS)
+	}
+	process.on('SIGINT', stop)
+	process.on('SIGTERM', stop)
+
+	lint()
+}
+
+/**
+ * `-` as the path, or --stdin-filename, lints source read from stdin. The filename decides
+ * which rules apply (e.g. library-only rules skip `main.rs`) and is what findings report.
+ * Returns null when linting files on disk.
+ */
+function resolveStdinFilename(
+	language: Language,
+	paths: string[],
+	options: ScanOptions,
+): string | null {
+	if (!paths.includes('-') && options.stdinFilename === undefined) {
+		return null
+	}
+	const other = paths.find((scanPath) => scanPath !== '-')
+	if (other !== undefined || paths.length > 1) {
+		const extra = other ?? '-'
+		fail(`Can't lint '${extra}' and stdin at once. Pass '-' as the only path with --stdin-filename`)
+	}
+	if (options.workspace) {
+		fail('--workspace cannot be used when reading from stdin')
+	}
+	if (options.fix) {
+		fail('--fix cannot be used when reading from stdin')
+	}
+	if (options.watch) {
+		fail('--watch cannot be used when reading from stdin')
+	}
+
+	const extensions = getLanguageExtensions(language)
+	if (options.stdinFilename === undefined) {
+		if (language === LANG.ALL) {
+			fail("--stdin-filename is required to lint stdin with language 'all'")
+		}
+		return `stdin${extensions[0]}`
+	}
+	if (!extensions.includes(path.extname(options.stdinFilename))) {
+		fail(`--stdin-filename '${options.stdinFilename}' is not a ${language} file`)
+	}
+	return options.stdinFilename
+}
+
+/**
+ * Lint source from stdin as if it were `filename`. The buffer is written to the same path
+ * relative to the cwd inside a temp dir, so rules scoped with `files`/`ignores` globs see it
+ * the way they'd see the file on disk. The cache is not used.
+ */
+function runStdinScan(
+	language: Language,
+	ruleDirs: string[],
+	filename: string,
+	scanOptions: AstGrepOptions,
+	reportOptions: ReportOptions,
+): void {
+	let source: string
+	try {
+		source = fs.readFileSync(0, 'utf8')
+	} catch (err) {
+		fail(`Could not read stdin: ${(err as Error).message}`)
+	}
+
+	const tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-stdin-'))
+	process.on('exit', () => fs.rmSync(tmpDir, { recursive: true, force: true }))
+
+	const relative = path.relative(process.cwd(), path.resolve(filename))
+	const virtualPath = relative
+		.split(path.sep)
+		.filter((segment) => segment !== '..')
+		.join(path.sep)
+	fs.mkdirSync(path.dirname(path.join(tmpDir, virtualPath)), { recursive: true })
+	fs.writeFileSync(path.join(tmpDir, virtualPath), source)
+
+	const options: AstGrepOptions = { ...scanOptions, fileFilter: null, cwd: tmpDir }
+	const stopScan = startPhase(reportOptions.stats, 'scan')
+	runAstGrep(language, ruleDirs, [virtualPath], options, (scanned) => {
+		stopScan()
+		const issues = scanned.map((issue) => ({ ...issue, file: filename }))
+		// An editor integration reads the report as soon as it's printed, one line per run
+		const report = { ...reportOptions, readSource: () => source, compact: true }
+		process.exitCode = reportIssues(language, issues, report)
+	})
+}
+
+// Unlike --exclude, these flags only take rule ids, and a typo is an error rather than a warning
+function resolveRuleSelection(
+	language: Language,
+	validRuleIds: Set<string>,
+	options: ScanOptions,
+): { only: string[] | null; skip: string[]; testRules: readonly string[] } {
+	const only =
+		options.rules === undefined
+			? null
+			: splitList(options.rules).map((id) => resolveRuleId(id, '--rules'))
+	const skip = splitList(options.skipRules).map((id) => resolveRuleId(id, '--skip-rules'))
+	if (only?.length === 0) {
+		fail('--rules needs at least one rule id')
+	}
+
+	for (const [flag, ids] of [
+		['--rules', only ?? []],
+		['--skip-rules', skip],
+	] as const) {
+		const unknown = ids.filter((id) => !validRuleIds.has(id))
+		if (unknown.length > 0) {
+			fail(
+				`Unknown ${pluralize(unknown.length, 'rule')} in ${flag}: ${unknown.join(', ')}. Known rules: ${[...validRuleIds].sort().join(', ')}`,
+			)
+		}
+	}
+
+	const testsOnly = options.testsOnly ?? false
+	if (testsOnly) {
+		if (language !== LANG.RUST) {
+			fail(`--tests-only only works with language '${LANG.RUST}'`)
+		}
+		const others = (only ?? []).filter((id) => !TEST_RULE_IDS.includes(id))
+		if (others.length > 0) {
+			fail(
+				`--tests-only only runs the test rules (${TEST_RULE_IDS.join(', ')}), not: ${others.join(', ')}`,
+			)
+		}
+	}
+	// The test rules and the others check opposite policies, e.g. `.unwrap()` is fine in a test,
+	// so --tests-only turns the others off. The test rules are off in their rule files and run
+	// with --tests-only, when --rules names them, or at a level the config file gives them
+	if (!testsOnly) {
+		return { only, skip, testRules: TEST_RULE_IDS.filter((id) => only?.includes(id)) }
+	}
+	const libraryRules = [...validRuleIds].filter((id) => !TEST_RULE_IDS.includes(id))
+	return { only, skip: [...skip, ...libraryRules], testRules: TEST_RULE_IDS }
+}
+
+// Opt-in rules have no level of their own to run at, so --rules can't turn them on by itself
+function checkOptInSelection(language: Language, only: string[] | null, config: LintConfig): void {
+	if (!only) return
+	const ids = getRuleMetadata(language)
+		.filter((rule) => rule.severity === RULE_LEVEL.OFF && only.includes(rule.id))
+		.map((rule) => rule.id)
+		.filter((id) => (config.rules[id] ?? RULE_LEVEL.OFF) === RULE_LEVEL.OFF)
+	if (ids.length > 0) {
+		fail(
+			`--rules can't turn on opt-in ${pluralize(ids.length, 'rule')} ${ids.join(', ')}; give ${ids.length === 1 ? 'it' : 'them'} a level in tempo-lints.toml first`,
+		)
+	}
+}
+
+function resolveRelativeTo(value: string | undefined): string {
+	const dir = path.resolve(value ?? '.')
+	if (!fs.existsSync(dir) || !fs.statSync(dir).isDirectory()) {
+		fail(`--relative-to '${value}' is not a directory`)
+	}
+	return dir
+}
+
+// --write-baseline without --baseline writes the default file; reading requires an explicit path
+function resolveBaseline(options: ScanOptions): BaselineOptions | null {
+	if (options.writeBaseline) {
+		return { path: options.baseline ?? DEFAULT_BASELINE_FILE, write: true, file: null }
+	}
+	if (options.baseline === undefined) {
+		return null
+	}
+	try {
+		return { path: options.baseline, write: false, file: loadBaseline(options.baseline) }
+	} catch (err) {
+		fail((err as Error).message)
+	}
+}
+
+// The cache only saves time, so failing to write it doesn't fail the run
+function writeCache(cache: LintCache): void {
+	try {
+		saveCache(cache)
+	} catch (err) {
+		warn(`Could not write cache ${cache.path}: ${(err as Error).message}`)
+	}
+}
+
+// Usage and IO errors exit with their own code so CI can tell them apart from findings
+function fail(message: string): never {
+	if (!isQuiet()) {
+		console.error(`Error: ${message}`)
+	}
+	process.exit(EXIT_CODE.TOOL_ERROR)
+}
+
+// `--threads 0` (the default) lets ast-grep pick based on the available cores
+function parseThreads(value: string | undefined): number | undefined {
+	if (value === undefined) return undefined
+
+	const threads = Number(value)
+	if (!Number.isInteger(threads) || threads < 0) {
+		fail(`Invalid --threads '${value}'. Must be a non-negative integer`)
+	}
+	return threads
+}
+
+function resolveStats(options: ScanOptions): RunStats | null {
+	if (!options.stats) {
+		if (options.slowest !== undefined) {
+			fail('--slowest needs --stats')
+		}
+		return null
+	}
+	if (options.watch) {
+		fail('--stats cannot be used with --watch')
+	}
+	const slowest = options.slowest === undefined ? 0 : Number(options.slowest)
+	if (!Number.isInteger(slowest) || slowest < 0) {
+		fail(`Invalid --slowest '${options.slowest}'. Must be a non-negative integer`)
+	}
+	return createStats(slowest)
+}
+
+function parseMaxErrors(value: string | undefined): number | null {
+	if (value === undefined) return null
+	const maxErrors = Number(value)
+	if (!Number.isInteger(maxErrors) || maxErrors < 0) {
+		fail(`Invalid --max-errors '${value}'. Must be a non-negative integer`)
+	}
+	return maxErrors
+}
+
+function parseContextLines(value: string | undefined): number {
+	if (value === undefined) return 0
+
+	const lines = Number(value)
+	if (!Number.isInteger(lines) || lines < 0) {
+		fail(`Invalid --context '${value}'. Must be a non-negative integer`)
+	}
+	return lines
+}
+
+function splitList(value: string | undefined): string[] {
+	return (
+		value
+			?.split(',')
+			.map((item) => item.trim())
+			.filter((item) => item !== '') ?? []
+	)
+}
+
+/**
+ * Precedence, lowest to highest: built-in defaults, then the config file, then CLI flags
+ * such as `--exclude`. Also loads the plugins the config file lists, for every language.
+ */
+async function loadProjectConfig(
+	configPath: string | undefined,
+): Promise<{ config: LintConfig; plugins: Rule[] }> {
+	// Validate against every language so one config can serve both `rust` and `typescript` runs
+	const builtInRuleIds = getValidRuleIds(LANG.ALL)
+	try {
+		// Plugins add rule ids, so the config is only checked once they're loaded
+		const unchecked = loadConfig({ startDir: process.cwd(), configPath })
+		if (unchecked.path === null) {
+			return { config: unchecked.config, plugins: [] }
+		}
+		const plugins = await loadPlugins(
+			unchecked.config.plugins ?? [],
+			path.dirname(unchecked.path),
+			builtInRuleIds,
+		)
+		const { config } = loadConfig({
+			startDir: process.cwd(),
+			configPath: unchecked.path,
+			knownRuleIds: new Set([...builtInRuleIds, ...plugins.map((rule) => rule.id)]),
+		})
+		return { config, plugins }
+	} catch (err) {
+		fail((err as Error).message)
+	}
+}
+
+/**
+ * `--json` and `--github-action` are kept as shorthands for `--format json-array`
+ * and `--format github`. An explicit `--format` always wins.
+ */
+function resolveOutputFormat(options: ScanOptions): OutputFormat {
+	if (options.format !== undefined) {
+		if (!isValidOutputFormat(options.format)) {
+			fail(
+				`Invalid format '${options.format}'. Must be one of: ${VALID_OUTPUT_FORMATS.join(', ')}`,
+			)
+		}
+		return options.format
+	}
+	if (options.githubAction) return OUTPUT_FORMAT.GITHUB
+	if (options.json) return OUTPUT_FORMAT.JSON_ARRAY
+	return OUTPUT_FORMAT.TEXT
+}
+
+// Findings go to stdout, so --color auto looks at whether that's a terminal
+function resolveColorOption(value: string | undefined): boolean {
+	const mode = value ?? COLOR_MODE.AUTO
+	if (!isValidColorMode(mode)) {
+		fail(`Invalid color mode '${mode}'. Must be one of: ${VALID_COLOR_MODES.join(', ')}`)
+	}
+	return resolveColor(mode, process.stdout.isTTY === true, process.env)
+}
+
 interface AstGrepOptions {
-	json: boolean
-	fix: boolean
-	githubAction: boolean
-	excludeRules: string[]
-	cleanup: () => void
+	// null when the targets are explicit files that were already filtered
+	fileFilter: FileFilter | null
+	noIgnore: boolean
+	threads: number | undefined
+	// Rule files rewritten for the config, see buildRuleOverrides
+	ruleOverrides: Map<string, string>
+	// Directory to run ast-grep in; targets are relative to it
+	cwd?: string
+	// From --max-errors: the scan stops once more files than this fail to parse, or null
+	maxErrors: number | null
 }
 
+// The file of a streamed ast-grep match that reports a parse error, or null for other matches
+function parseErrorFile(line: string): string | null {
+	try {
+		const match = JSON.parse(line) as { ruleId?: unknown; file?: unknown }
+		return match.ruleId === PARSE_ERROR_RULE_ID && typeof match.file === 'string'
+			? match.file
+			: null
+	} catch {
+		// Left for parseLintIssues to report
+		return null
+	}
+}
+
+/**
+ * Run ast-grep over `targets` and pass the parsed issues to `onIssues`, sorted.
+ * Matches are read as ast-grep prints them, so once more files than `maxErrors` fail to
+ * parse, ast-grep is stopped and `onIssues` gets the matches so far with `stopped` set.
+ * Exits the process when ast-grep can't be run or its output can't be parsed.
+ */
 function runAstGrep(
 	language: Language,
-	configPath: string,
-	scanPath: string,
+	ruleDirs: string[],
+	targets: string[],
 	options: AstGrepOptions,
+	onIssues: (issues: LintIssue[], stopped: boolean) => void,
 ): void {
-	const { cleanup } = options
+	const { configPath, cleanup } = createTempConfig(ruleDirs, options.ruleOverrides)
 
-	const handleSignal = (signal: 'SIGINT' | 'SIGTERM') => {
+	const handleSignal = (signal: NodeJS.Signals) => {
 		cleanup()
 		process.exit(128 + (signal === 'SIGINT' ? 2 : 15))
 	}
 
-	process.on('SIGINT', () => handleSignal('SIGINT'))
-	process.on('SIGTERM', () => handleSignal('SIGTERM'))
-
-	const args = ['scan', '--config', configPath]
+	process.on('SIGINT', handleSignal)
+	process.on('SIGTERM', handleSignal)
+	// Other exits, e.g. watch mode stopping on Ctrl-C, still remove the temp config
+	process.on('exit', cleanup)
 
-	const useJson = options.json || options.githubAction
-	if (useJson) {
-		args.push('--json')
-	}
-
-	if (options.fix) {
-		args.push('--update-all')
+	// Watch mode scans many times in one process, so the handlers go away with each scan
+	const finish = () => {
+		cleanup()
+		process.off('SIGINT', handleSignal)
+		process.off('SIGTERM', handleSignal)
+		process.off('exit', cleanup)
 	}
 
-	args.push(scanPath)
+	// Always collect ast-grep's JSON output, one match per line; the selected formatter renders
+	// it and `--fix` applies the replacements it reports
+	const args = [
+		'scan',
+		'--config',
+		configPath,
+		'--json=stream',
+		...(options.fileFilter === null
+			? []
+			: [...buildGlobArgs(options.fileFilter), ...buildIgnoreArgs(options.noIgnore)]),
+		...(options.threads === undefined ? [] : ['--threads', String(options.threads)]),
+		...targets,
+	]
 
 	const localAstGrep = path.join(PACKAGE_ROOT, 'node_modules', '.bin', 'sg')
 	const astGrepPath = fs.existsSync(localAstGrep) ? localAstGrep : 'sg'
 
 	const proc = spawn(astGrepPath, args, {
-		stdio: useJson ? ['inherit', 'pipe', 'inherit'] : 'inherit',
+		cwd: options.cwd,
+		stdio: ['inherit', 'pipe', isQuiet() ? 'ignore' : 'inherit'],
 		shell: process.platform === 'win32',
 	})
 
-	let output = ''
+	const lines: string[] = []
+	const failedFiles = new Set<string>()
+	let pending = ''
+	let stopped = false
 
-	if (useJson && proc.stdout) {
-		proc.stdout.on('data', (data: Buffer) => {
-			output += data.toString()
-		})
+	const readLine = (line: string) => {
+		if (line.trim() === '') return
+		lines.push(line)
+		if (options.maxErrors === null) return
+		const file = parseErrorFile(line)
+		if (file !== null) failedFiles.add(file)
+		if (failedFiles.size > options.maxErrors) {
+			stopped = true
+			proc.kill()
+		}
 	}
 
-	proc.on('close', (code) => {
-		cleanup()
+	// Decoded as a stream, so a character split across chunks comes out whole
+	proc.stdout?.setEncoding('utf8')
+	proc.stdout?.on('data', (data: string) => {
+		if (stopped) return
+		const complete = (pending + data).split('\n')
+		pending = complete.pop() ?? ''
+		for (const line of complete) {
+			if (stopped) break
+			readLine(line)
+		}
+	})
 
-		if (useJson && output) {
-			// Get valid rule IDs to filter out non-tempo lint entries
-			const validRuleIds = getValidRuleIds(language)
-			const { issues, error } = parseLintIssues(output, validRuleIds)
+	proc.on('close', (code) => {
+		finish()
+		if (!stopped) readLine(pending)
 
-			if (error) {
-				warn(error)
-				console.log(output)
-				process.exit(code ?? 1)
-				return
-			}
+		// Get valid rule IDs to filter out non-tempo lint entries
+		const validRuleIds = getValidRuleIds(language)
+		validRuleIds.add(ALLOW_DIRECTIVE_RULE_ID)
+		validRuleIds.add(PARSE_ERROR_RULE_ID)
 
-			const { filtered, warnings } = filterExcludedRules(issues, options.excludeRules)
-			for (const w of warnings) warn(w)
+		// ast-grep exits with 1 when it reports error findings, but then there's output to parse
+		if (code !== 0 && !stopped && lines.length === 0) {
+			fail(`ast-grep exited with ${code === null ? 'a signal' : `code ${code}`}`)
+		}
 
-			if (options.githubAction) {
-				outputForGitHubAction(filtered)
-			} else {
-				console.log(JSON.stringify(filtered, null, 2))
-			}
+		const { issues, error } = parseLintIssues(`[${lines.join(',')}]`, validRuleIds, {
+			oneBased: true,
+		})
 
-			const counts = countBySeverity(filtered)
-			if (counts.error > 0) {
-				process.exit(1)
+		if (error) {
+			warn(error)
+			if (!isQuiet()) {
+				console.log(lines.join('\n'))
 			}
-		} else {
-			process.exit(code ?? 0)
+			process.exit(EXIT_CODE.TOOL_ERROR)
 		}
+
+		onIssues(sortIssues(issues), stopped)
 	})
 
 	proc.on('error', (err) => {
-		cleanup()
-		console.error(`Error running ast-grep: ${err.message}`)
-		console.error('Make sure ast-grep is installed: npm install -g @ast-grep/cli')
-		process.exit(1)
+		finish()
+		fail(
+			`Could not run ast-grep: ${err.message}\nMake sure ast-grep is installed: npm install -g @ast-grep/cli`,
+		)
 	})
 }
 
-function outputForGitHubAction(issues: LintIssue[]): void {
-	const counts = countBySeverity(issues)
-	const total = issues.length
-	const hasErrors = counts.error > 0
+interface BaselineOptions {
+	path: string
+	// --write-baseline: record the findings instead of filtering them
+	write: boolean
+	// The loaded baseline when filtering
+	file: BaselineFile | null
+}
+
+interface ReportOptions {
+	format: OutputFormat
+	fix: boolean
+	warnUnusedAllows: boolean
+	excludeRules: string[]
+	config: LintConfig
+	scanPaths: string[]
+	baseline: BaselineOptions | null
+	// Reads a file's source for allow annotations; defaults to reading from disk
+	readSource?: (file: string) => string
+	compact?: boolean
+	summary: boolean
+	// The files that were linted, which path and plugin rules are checked against
+	scannedFiles: string[]
+	// Rules the CLI runs itself: path rules and plugin rules
+	rules: Rule[]
+	// Absolute directory that reported paths are relative to
+	relativeTo: string
+	// With --workspace, what each crate file is compiled as, and the path globs of
+	// library-scoped rules for files no target reaches
+	crateTargets: { kinds: Map<string, TargetKind>; fallback: Map<string, IgnorePattern[]> } | null
+	// With --diff, the lines it changes and the directory its paths are relative to
+	diff: { changed: ChangedLines; baseDir: string } | null
+	// Highlight the text output, from --color
+	color: boolean
+	// Source lines shown around each text finding, or null with --no-snippets
+	snippets: { context: number } | null
+	// Timings printed after the report with --stats
+	stats: RunStats | null
+	// From --max-errors: how many files may fail to parse before the run stops, or null for any
+	maxErrors: number | null
+}
+
+/**
+ * Check and report the raw scan results, then with --stats print how long the run took.
+ * With more files failing to parse than --max-errors allows, nothing else is checked.
+ * Returns the exit code for the run.
+ */
+function reportIssues(language: Language, raw: LintIssue[], options: ReportOptions): ExitCode {
+	const { stats } = options
+	const failed = filesFailingToParse(raw)
+	let exitCode: ExitCode
+	if (options.maxErrors !== null && failed.length > options.maxErrors) {
+		exitCode = stopOnParseErrors(raw, failed, options)
+	} else {
+		const checked = timePhase(stats, 'rules', () => checkIssues(raw, options))
+		exitCode = timePhase(stats, 'report', () => reportFindings(language, checked, options))
+	}
+	if (stats && !isQuiet()) {
+		const displayPath = (file: string) => relativizePath(file, options.relativeTo)
+		console.error(formatStats(stats, options.scannedFiles.length, displayPath))
+	}
+	return exitCode
+}
+
+// Scan results with the CLI's own rules added, and parse errors kept apart from the findings
+interface CheckedIssues {
+	issues: LintIssue[]
+	parseErrors: LintIssue[]
+}
+
+/**
+ * Run the path and plugin rules over the scanned files and finish the YAML rules that only
+ * match candidates.
+ */
+function checkIssues(raw: LintIssue[], options: ReportOptions): CheckedIssues {
+	const { stats } = options
+	let checked: LintIssue[]
+	try {
+		checked = applyRules(options.scannedFiles, options.rules, options.config, {
+			readSource: options.readSource,
+			onTiming: stats ? (ruleId, file, ms) => recordRuleTime(stats, ruleId, file, ms) : undefined,
+		})
+	} catch (err) {
+		fail((err as Error).message)
+	}
+	const sorted = sortIssues([...raw, ...checked])
+	const parseErrors = sorted.filter(isParseError)
+	// Rules like max-function-length only match candidates; finish them before anything counts them
+	const candidates = sorted.filter((issue) => !isParseError(issue))
+	return { issues: finishCandidates(candidates, options.config, stats), parseErrors }
+}
+
+// Passes finishing the YAML rules that only match candidates, keyed by the rule they finish
+const FINISHERS: [string, (issues: LintIssue[], config: LintConfig) => LintIssue[]][] = [
+	[AS_CAST_RULE_ID, applyAsCasts],
+	[FUNCTION_LENGTH_RULE_ID, applyFunctionLength],
+	[IMPL_TRAIT_RULE_ID, applyImplTraitSignatures],
+	[LARGE_ENUM_VARIANT_RULE_ID, applyLargeEnumVariants],
+	[LINE_LENGTH_RULE_ID, applyLineLength],
+	[MUST_USE_RULE_ID, applyMustUseResults],
+	[SHADOWING_RULE_ID, applyShadowing],
+	[WILDCARD_IMPORT_RULE_ID, applyWildcardImports],
+]
+
+/**
+ * Run every finishing pass over `issues`. With --stats, files are finished one at a time so
+ * each pass is timed by rule and by file, like the path and plugin rules. Issues stay sorted
+ * either way, since sorted issues group by file.
+ */
+function finishCandidates(
+	issues: LintIssue[],
+	config: LintConfig,
+	stats: RunStats | null,
+): LintIssue[] {
+	if (stats === null) {
+		return FINISHERS.reduce((result, [, finish]) => finish(result, config), issues)
+	}
+	return Object.entries(groupByFile(issues)).flatMap(([file, fileIssues]) =>
+		FINISHERS.reduce((result, [ruleId, finish]) => {
+			if (!result.some((issue) => issue.ruleId === ruleId)) return result
+			const started = performance.now()
+			const finished = finish(result, config)
+			recordRuleTime(stats, ruleId, file, performance.now() - started)
+			return finished
+		}, fileIssues),
+	)
+}
+
+/**
+ * Filter the checked results (including directive matches), apply fixes and print the report.
+ * Returns the exit code for the run.
+ */
+function reportFindings(
+	language: Language,
+	checked: CheckedIssues,
+	options: ReportOptions,
+): ExitCode {
+	const { issues: finished, parseErrors } = checked
+	const scanned = options.crateTargets
+		? applyCrateTargets(finished, options.crateTargets.kinds, options.crateTargets.fallback)
+		: finished
 
-	// Output GitHub Actions annotations (these show up in the PR "Files changed" view)
-	for (const issue of issues) {
-		const annotationType = issue.severity === 'error' ? 'error' : 'warning'
-		// Format: ::warning file={name},line={line},col={col}::{message}
-		console.log(
-			`::${annotationType} file=${issue.file},line=${issue.line},col=${issue.column}::${issue.ruleId}: ${issue.message}`,
+	// Drop findings suppressed by inline allow annotations. This runs before --exclude and
+	// configured levels so an allow only counts as unused if its rule really didn't fire.
+	const directives = collectAllowDirectives(
+		scanned.filter((issue) => issue.ruleId === ALLOW_DIRECTIVE_RULE_ID),
+		options.readSource,
+	)
+	const { issues, unused } = applyAllowDirectives(
+		scanned.filter((issue) => issue.ruleId !== ALLOW_DIRECTIVE_RULE_ID),
+		directives,
+	)
+
+	const { filtered: included, warnings } = filterExcludedRules(issues, options.excludeRules)
+	for (const w of warnings) warn(w)
+	const permitted = applyAllowedPaths(included, options.config)
+
+	// Configured levels decide the reported severity, and with it the exit code. Per-path
+	// overrides adjust the project-wide levels
+	const leveled = applySeverityOverrides(
+		applyRuleLevels(permitted, options.config.rules),
+		options.config,
+	)
+
+	const { baseline } = options
+	let matched = leveled
+	if (baseline?.write) {
+		writeBaseline(baseline.path, leveled)
+		return reportParseErrors(parseErrors, options.relativeTo)
+			? EXIT_CODE.TOOL_ERROR
+			: EXIT_CODE.SUCCESS
+	}
+	if (baseline?.file) {
+		const baseDir = path.dirname(path.resolve(baseline.path))
+		const { issues: fresh, stale } = applyBaseline(leveled, baseline.file, baseDir)
+		matched = fresh
+		warnStaleBaselineEntries(
+			stale.filter((entry) => isInScanScope(entry, baseDir, language, options)),
+			baseline.path,
 		)
 	}
 
-	// Output summary
-	console.log('')
-	console.log('========================================')
-	console.log('Tempo Lint Results')
-	console.log('========================================')
-	console.log(`Total issues: ${total}`)
-	console.log(`Errors: ${counts.error}`)
-	console.log(`Warnings: ${counts.warning}`)
-	console.log(`Hints: ${counts.hint}`)
-	console.log('')
+	// Baseline entries are matched first, so ones outside the diff aren't reported as stale.
+	// With --diff, only findings on changed lines are fixed
+	let filtered = keepChangedLines(matched, options)
+	if (options.fix) {
+		const { remaining, applied, filesChanged } = applyFixes(filtered)
+		if (!isQuiet()) {
+			console.error(
+				`Applied ${applied} ${pluralize(applied, 'fix', 'fixes')} in ${filesChanged} ${pluralize(filesChanged, 'file')}`,
+			)
+		}
+		filtered = remaining
+	}
 
-	if (total === 0) {
-		console.log('No lint issues found!')
-	} else {
-		// Detailed output with file:line
-		for (const issue of issues) {
-			const prefix =
-				issue.severity === 'error' ? '[ERROR]' : issue.severity === 'warning' ? '[WARN]' : '[HINT]'
-			console.log(`${prefix} ${issue.file}:${issue.line}:${issue.column}`)
-			console.log(`  ${issue.ruleId}: ${issue.message}`)
-			if (issue.code) {
-				console.log(`  > ${issue.code}`)
-			}
-			console.log('')
+	if (options.warnUnusedAllows) {
+		filtered = [...filtered, ...keepChangedLines(unused, options)]
+	}
+
+	// Every format reports the same paths and messages, so output is stable across checkouts.
+	// Finishing rules, path rules and unused allows add findings out of place, so the report is
+	// sorted once more by the paths it shows.
+	const reported = sortIssues(
+		applyMessageTemplates(
+			filtered.map((issue) => ({ ...issue, file: relativizePath(issue.file, options.relativeTo) })),
+			options.config,
+		),
+	)
+
+	if (!isQuiet()) {
+		// Path rules are among the built-in rules already, plugin rules aren't
+		const builtIn = getRuleMetadata(language)
+		const builtInIds = new Set(builtIn.map((rule) => rule.id))
+		const ruleMetadata = [
+			...builtIn,
+			...options.rules.filter((rule) => !builtInIds.has(rule.id)).map(toRuleMetadata),
+		]
+		const rendered = getFormatter(options.format).format(reported, {
+			rules: applyRuleLevelsToMetadata(ruleMetadata, options.config.rules),
+			rootDir: options.relativeTo,
+			toolVersion: getVersion(),
+			compact: options.compact,
+			color: options.color,
+			snippets: options.snippets && {
+				context: options.snippets.context,
+				readSource: (file) =>
+					readReportedSource(path.resolve(options.relativeTo, file), options.readSource),
+			},
+			summary: options.summary ? buildSummary(reported, options.scannedFiles.length) : undefined,
+		})
+		if (rendered) {
+			console.log(rendered)
 		}
 	}
 
-	// Write outputs to GITHUB_OUTPUT file (modern syntax)
-	const githubOutput = process.env.GITHUB_OUTPUT
-	if (githubOutput) {
-		fs.appendFileSync(githubOutput, `total_issues=${total}\n`)
-		fs.appendFileSync(githubOutput, `has_errors=${hasErrors}\n`)
+	if (options.format === OUTPUT_FORMAT.GITHUB) {
+		writeGitHubOutputs(filtered)
 	}
+
+	// Rules with a `max` budget fail the run by their count instead of their severity
+	const budgets = checkBudgets(filtered, options.config)
+	if (!isQuiet()) {
+		for (const result of budgets) {
+			console.error(formatBudget(result))
+		}
+	}
+
+	if (reportParseErrors(parseErrors, options.relativeTo)) {
+		return EXIT_CODE.TOOL_ERROR
+	}
+	return failsRun(filtered, budgets) ? EXIT_CODE.LINT_ERRORS : EXIT_CODE.SUCCESS
+}
+
+// A file removed since it was linted has no snippet to show
+function readReportedSource(
+	file: string,
+	readSource: (file: string) => string = (file) => fs.readFileSync(file, 'utf8'),
+): string | null {
+	try {
+		return readSource(file)
+	} catch {
+		return null
+	}
+}
+
+// Rules may miss or misreport code around a syntax error, so the run can't be trusted.
+// Returns whether there were any.
+function reportParseErrors(parseErrors: LintIssue[], relativeTo: string): boolean {
+	for (const issue of parseErrors) {
+		reportError(describeParseError(issue, relativizePath(issue.file, relativeTo)))
+	}
+	return parseErrors.length > 0
+}
+
+/**
+ * Give up on a run where more files failed to parse than --max-errors allows, e.g. because the
+ * wrong language or a generated directory was scanned: print the errors of the files up to and
+ * including the one over the limit, and report no findings.
+ */
+function stopOnParseErrors(raw: LintIssue[], failed: string[], options: ReportOptions): ExitCode {
+	const maxErrors = options.maxErrors ?? 0
+	const shown = new Set(failed.slice(0, maxErrors + 1))
+	reportParseErrors(
+		sortIssues(raw.filter((issue) => isParseError(issue) && shown.has(issue.file))),
+		options.relativeTo,
+	)
+	reportError(
+		`Stopped: ${failed.length} ${pluralize(failed.length, 'file')} failed to parse, more than --max-errors ${maxErrors}`,
+	)
+	return EXIT_CODE.TOOL_ERROR
+}
+
+function writeBaseline(baselinePath: string, issues: LintIssue[]): void {
+	const baseline = createBaseline(issues, path.dirname(path.resolve(baselinePath)))
+	try {
+		fs.writeFileSync(baselinePath, formatBaseline(baseline))
+	} catch (err) {
+		fail(`Could not write baseline file ${baselinePath}: ${(err as Error).message}`)
+	}
+	if (!isQuiet()) {
+		console.error(
+			`Wrote ${issues.length} ${pluralize(issues.length, 'finding')} to ${baselinePath}`,
+		)
+	}
+}
+
+function keepChangedLines(issues: LintIssue[], options: ReportOptions): LintIssue[] {
+	const { diff } = options
+	return diff === null ? issues : filterChangedLines(issues, diff.changed, diff.baseDir)
+}
+
+/**
+ * Whether a baseline entry could have matched in this run: its file is under a scanned
+ * path and its rule ran. Entries outside the scan aren't reported as stale.
+ */
+function isInScanScope(
+	entry: BaselineEntry,
+	baseDir: string,
+	language: Language,
+	options: ReportOptions,
+): boolean {
+	const ran = options.rules.some((rule) => rule.id === entry.rule_id)
+	if (!ran && !getValidRuleIds(language).has(entry.rule_id)) return false
+	if (options.excludeRules.includes(entry.rule_id)) return false
+	if (options.config.rules[entry.rule_id] === RULE_LEVEL.OFF) return false
+
+	const file = path.resolve(baseDir, entry.file)
+	return options.scanPaths.some((scanPath) => {
+		const relative = path.relative(path.resolve(scanPath), file)
+		return relative === '' || (!relative.startsWith('..') && !path.isAbsolute(relative))
+	})
+}
+
+function warnStaleBaselineEntries(stale: BaselineEntry[], baselinePath: string): void {
+	if (stale.length === 0) return
+
+	warn(
+		`${stale.length} ${pluralize(stale.length, 'entry', 'entries')} in ${baselinePath} no longer match any finding. Run with --write-baseline to remove them.`,
+	)
+	for (const entry of stale) {
+		warn(`Stale baseline entry: ${entry.rule_id} in ${entry.file}`)
+	}
+}
+
+// Write outputs to GITHUB_OUTPUT file (modern syntax)
+function writeGitHubOutputs(issues: LintIssue[]): void {
+	const githubOutput = process.env.GITHUB_OUTPUT
+	if (!githubOutput) return
+
+	const hasErrors = countBySeverity(issues).error > 0
+	fs.appendFileSync(githubOutput, `total_issues=${issues.length}\n`)
+	fs.appendFileSync(githubOutput, `has_errors=${hasErrors}\n`)
 }
 
 interface VendorOptions {
@@ -209,10 +1405,7 @@ function runVendor(options: VendorOptions): void {
 	const { lang, dest } = options
 
 	if (!isValidLanguage(lang)) {
-		console.error(
-			`Error: Invalid language '${lang}'. Must be one of: ${VALID_LANGUAGES.join(', ')}`,
-		)
-		process.exit(1)
+		fail(`Invalid language '${lang}'. Must be one of: ${VALID_LANGUAGES.join(', ')}`)
 	}
 
 	const language = lang as Language
@@ -220,6 +1413,7 @@ function runVendor(options: VendorOptions): void {
 	fs.mkdirSync(astGrepDir, { recursive: true })
 
 	console.log(`Vendoring Tempo lints to ${astGrepDir}...`)
+	const overrides = buildVendorOverrides(getRuleDirs(language))
 
 	// Copy shared rules
 	if (language === LANG.RUST || language === LANG.ALL) {
@@ -243,6 +1437,7 @@ function runVendor(options: VendorOptions): void {
 		copyDir(
 			path.join(PACKAGE_ROOT, 'src', LANG.RUST, 'rules'),
 			path.join(astGrepDir, 'rules', LANG.RUST),
+			overrides,
 		)
 		console.log(`Copied src/${LANG.RUST}/rules/`)
 	}
@@ -260,18 +1455,26 @@ function runVendor(options: VendorOptions): void {
 	fs.writeFileSync(path.join(dest, 'sgconfig.yml'), generateConfigContent(ruleDirs))
 
 	console.log('Created sgconfig.yml')
+	if (overrides.size > 0) {
+		const ruleIds = [...overrides.keys()].map((file) => path.basename(file, '.yml'))
+		console.log(`Turned off rules that only the tempo-lints CLI can check: ${ruleIds.join(', ')}`)
+	}
 	console.log('')
 	console.log('Done! Run: ast-grep scan --config sgconfig.yml')
 }
 
-function copyDir(src: string, destDir: string): void {
+// Files with an entry in `overrides` are written with its content instead of copied
+function copyDir(src: string, destDir: string, overrides?: Map<string, string>): void {
 	fs.mkdirSync(destDir, { recursive: true })
 	const entries = fs.readdirSync(src, { withFileTypes: true })
 	for (const entry of entries) {
 		const srcPath = path.join(src, entry.name)
 		const destPath = path.join(destDir, entry.name)
+		const override = overrides?.get(srcPath)
 		if (entry.isDirectory()) {
-			copyDir(srcPath, destPath)
+			copyDir(srcPath, destPath, overrides)
+		} else if (override !== undefined) {
+			fs.writeFileSync(destPath, override)
 		} else {
 			fs.copyFileSync(srcPath, destPath)
 		}
@@ -287,13 +1490,70 @@ program
 
 program
 	.argument('<language>', `Language to lint: ${VALID_LANGUAGES.join(', ')}`)
-	.argument('[path]', 'Path to scan', '.')
-	.option('--exclude <rules>', 'Comma-separated list of rules to exclude')
-	.option('--json', 'Output results as JSON')
+	.argument('[paths...]', "Paths to scan (default: the cwd), or '-' to read from stdin")
+	.option('--config <path>', 'Path to a tempo-lints.toml (default: nearest one above the cwd)')
+	.option(
+		'--exclude <rules-or-globs>',
+		'Comma-separated rules to exclude, or path globs (e.g. target/,**/generated/*.rs) to skip',
+	)
+	.option('--exclude-path <globs>', 'Comma-separated path globs to skip, even if named like a rule')
+	.option('--include <globs>', 'Comma-separated path globs; only matching files are scanned')
+	.option(
+		'--rules <ids>',
+		'Comma-separated rule ids; only these rules run, even if the config turns them off. Opt-in rules still need a level there',
+	)
+	.option('--skip-rules <ids>', 'Comma-separated rule ids to turn off for this run')
+	.option('-j, --threads <n>', 'Number of threads used to scan files (default: 0, all cores)')
+	.option('--no-ignore', 'Also scan files ignored by .gitignore, .ignore and other ignore files')
+	.option('--no-cache', `Rescan every file instead of reusing results cached in ${CACHE_DIR}/`)
+	.option('--format <format>', `Output format: ${VALID_OUTPUT_FORMATS.join(', ')} (default: text)`)
+	.option('--json', 'Output findings as a bare JSON array (same as --format json-array)')
+	.option(
+		'--color <when>',
+		`Color the text output: ${VALID_COLOR_MODES.join(', ')} (default: auto, only on a terminal)`,
+	)
+	.option('--no-snippets', 'Leave out the source line and underline below each text finding')
+	.option('--context <n>', 'Lines of source shown around each text finding (default: 0)')
 	.option('--fix', 'Apply auto-fixes where available')
-	.option('--github-action', 'Output in GitHub Actions format with annotations')
-	.action((language: string, scanPath: string, options: ScanOptions) => {
-		runScan(language, scanPath, options)
+	.option('--no-summary', 'Leave out the per-rule and per-severity totals after the findings')
+	.option('--relative-to <dir>', 'Report paths relative to this directory (default: the cwd)')
+	.option('--warn-unused-allows', 'Report inline allow annotations that suppressed nothing')
+	.option('--baseline <path>', 'Only report findings that are not recorded in this baseline file')
+	.option(
+		'--write-baseline',
+		`Record all current findings in the baseline file (default: ${DEFAULT_BASELINE_FILE})`,
+	)
+	.option(
+		'--github-action',
+		'Output in GitHub Actions format with annotations (same as --format github)',
+	)
+	.option(
+		'--stdin-filename <path>',
+		"Lint source from stdin, reported as this file (also enabled by passing '-' as the path)",
+	)
+	.option('-q, --quiet', 'Print nothing; only the exit code reports the result')
+	.option('-w, --watch', 'Lint again whenever a file changes, until interrupted with Ctrl-C')
+	.option('--stats', 'Print how long the run took, by phase and by CLI rule, to stderr')
+	.option('--slowest <n>', 'With --stats, also list the n files the CLI rules took longest on')
+	.option('--max-errors <n>', 'Stop without reporting findings when over n files fail to parse')
+	.option(
+		'--files-from <path>',
+		"Lint the files listed in this file, one per line, or read the list from stdin with '-'",
+	)
+	.option(
+		'--diff <path>',
+		"Only report findings on lines this unified diff adds or changes; '-' reads it from stdin",
+	)
+	.option(
+		'--workspace',
+		`Lint every crate of the Cargo workspace, using ${CARGO_MANIFEST} targets to find library code`,
+	)
+	.option(
+		'--tests-only',
+		`Only run the test rules (${TEST_RULE_IDS.join(', ')}), which check #[test] and #[cfg(test)] code`,
+	)
+	.action(async (language: string, paths: string[], options: ScanOptions) => {
+		await runScan(language, paths, options)
 	})
 
 program
@@ -305,4 +1565,9 @@ program
 		runVendor(options)
 	})
 
-program.parse()
+// Commander's own usage errors (unknown options, missing arguments) are tool errors too
+program.exitOverride((err) => {
+	process.exit(err.exitCode === 0 ? EXIT_CODE.SUCCESS : EXIT_CODE.TOOL_ERROR)
+})
+
+await program.parseAsync()
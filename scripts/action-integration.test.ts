@@ -3,23 +3,53 @@
  * Tests the full flow: lint → JSON output → PR comment generation
  */
 
-import { execSync } from 'node:child_process'
+import { execSync, spawnSync } from 'node:child_process'
+import fs from 'node:fs'
+import os from 'node:os'
 import path from 'node:path'
 import { describe, expect, it } from 'vitest'
-import { countBySeverity, type LintIssue, PACKAGE_ROOT, parseLintIssues } from './shared.ts'
+import {
+	JSON_SCHEMA_VERSION,
+	type JsonFinding,
+	parseJsonReport,
+	VALID_OUTPUT_FORMATS,
+} from './formatters.ts'
+import {
+	countBySeverity,
+	EXIT_CODE,
+	type LintIssue,
+	PACKAGE_ROOT,
+	sortIssues,
+} from './shared.ts'
 
 const FIXTURES_DIR = path.join(PACKAGE_ROOT, 'test-fixtures/rust')
+const PARSE_ERROR_FIXTURES_DIR = path.join(PACKAGE_ROOT, 'test-fixtures/rust-parse-error')
+const WORKSPACE_FIXTURES_DIR = path.join(PACKAGE_ROOT, 'test-fixtures/rust-workspace')
+const TESTS_FIXTURES_DIR = path.join(PACKAGE_ROOT, 'test-fixtures/rust-tests')
 const CLI_PATH = path.join(PACKAGE_ROOT, 'bin/tempo-lints.ts')
 
-function runLintsOnFixtures(): LintIssue[] {
+function runCli(
+	args: string[],
+	input?: string,
+): { status: number | null; stdout: string; stderr: string } {
+	const result = spawnSync('pnpm', ['exec', 'tsx', CLI_PATH, ...args], {
+		encoding: 'utf8',
+		cwd: PACKAGE_ROOT,
+		input,
+	})
+	return { status: result.status, stdout: result.stdout, stderr: result.stderr }
+}
+
+function runLintsOnFixtures(extraArgs = ''): LintIssue[] {
 	try {
-		const output = execSync(`pnpm exec tsx "${CLI_PATH}" rust "${FIXTURES_DIR}" --json`, {
+		const command = `pnpm exec tsx "${CLI_PATH}" rust "${FIXTURES_DIR}" --format json ${extraArgs}`
+		const output = execSync(command, {
 			encoding: 'utf8',
 			cwd: PACKAGE_ROOT,
 			stdio: ['pipe', 'pipe', 'pipe'],
 		})
 
-		const { issues, error } = parseLintIssues(output)
+		const { issues, error } = parseJsonReport(output)
 		if (error) {
 			throw new Error(`Failed to parse lint output: ${error}`)
 		}
@@ -28,7 +58,7 @@ function runLintsOnFixtures(): LintIssue[] {
 		// execSync throws on non-zero exit, but we still want the output
 		const error = err as { stdout?: string }
 		if (error.stdout) {
-			const { issues, error: parseError } = parseLintIssues(error.stdout)
+			const { issues, error: parseError } = parseJsonReport(error.stdout)
 			if (!parseError) {
 				return issues
 			}
@@ -65,6 +95,61 @@ describe('Action Integration Tests', () => {
 		expect(unwrapIssues.length).toBeGreaterThan(0)
 	})
 
+	it('should lint code behind any cfg, whichever features and platforms are enabled', () => {
+		const issues = runLintsOnFixtures().filter((issue) => issue.file.endsWith('with-cfg.rs'))
+
+		expect(issues.map((issue) => [issue.ruleId, issue.line])).toEqual([
+			['no-unwrap-in-lib', 4],
+			['no-dbg-macro', 10],
+			['no-dbg-macro', 16],
+		])
+	})
+
+	it('should report findings added after the scan in line order', () => {
+		const issues = runLintsOnFixtures().filter((issue) => issue.file.endsWith('long-lines.rs'))
+
+		expect(issues.map((issue) => [issue.ruleId, issue.line])).toEqual([
+			['no-unwrap-in-lib', 3],
+			['max-line-length', 4],
+			['no-dbg-macro', 5],
+		])
+	})
+
+	it('should print byte-identical, sorted output on every run in every format', () => {
+		for (const format of VALID_OUTPUT_FORMATS) {
+			const run = (...args: string[]) =>
+				runCli(['rust', FIXTURES_DIR, '--format', format, ...args]).stdout
+			const first = run('--no-cache', '--threads', '1')
+
+			expect(run('--no-cache', '--threads', '4')).toBe(first)
+			// Once to fill the cache, once to read it
+			expect(run()).toBe(first)
+			expect(run()).toBe(first)
+		}
+
+		const { issues } = parseJsonReport(runCli(['rust', FIXTURES_DIR, '--format', 'json']).stdout)
+		expect(issues.length).toBeGreaterThan(1)
+		expect(issues).toEqual(sortIssues(issues))
+	})
+
+	it('should skip files listed in ignore files', () => {
+		const issues = runLintsOnFixtures()
+
+		expect(issues.some((issue) => issue.file.includes('ignored/'))).toBe(false)
+	})
+
+	it('should scan ignored files with --no-ignore', () => {
+		const issues = runLintsOnFixtures('--no-ignore')
+
+		expect(issues.some((issue) => issue.file.includes('ignored/'))).toBe(true)
+	})
+
+	it('should skip files matching exclude globs even with --no-ignore', () => {
+		const issues = runLintsOnFixtures('--no-ignore --exclude "**/ignored/**"')
+
+		expect(issues.some((issue) => issue.file.includes('ignored/'))).toBe(false)
+	})
+
 	it('should return issues with all required fields', () => {
 		const issues = runLintsOnFixtures()
 
@@ -108,4 +193,606 @@ describe('Action Integration Tests', () => {
 		const parsed = JSON.parse(output)
 		expect(Array.isArray(parsed)).toBe(true)
 	})
+
+	it('should print a versioned report with --format json, 1-based unlike --json', () => {
+		const array = JSON.parse(runCli(['rust', FIXTURES_DIR, '--json']).stdout)
+		const report = JSON.parse(runCli(['rust', FIXTURES_DIR, '--format', 'json']).stdout)
+
+		expect(report.schema_version).toBe(JSON_SCHEMA_VERSION)
+		expect(Array.isArray(report.findings)).toBe(true)
+		expect(report.findings.length).toBeGreaterThan(0)
+		expect(array.map((f: LintIssue) => [f.ruleId, f.file, f.line + 1, f.column + 1])).toEqual(
+			report.findings.map((f: JsonFinding) => [f.rule_id, f.file, f.line, f.column]),
+		)
+	})
+
+	it('should exit with 1 when error findings are present', () => {
+		expect(runCli(['rust', FIXTURES_DIR]).status).toBe(EXIT_CODE.LINT_ERRORS)
+	})
+
+	it('should exit with 0 when only warnings are left', () => {
+		const { status } = runCli(['rust', FIXTURES_DIR, '--exclude', 'no-dbg-macro,no-unwrap-in-lib'])
+
+		expect(status).toBe(EXIT_CODE.SUCCESS)
+	})
+
+	it('should only run the rules given with --rules', () => {
+		const issues = runLintsOnFixtures('--rules no-dbg-macro')
+
+		expect(issues.length).toBeGreaterThan(0)
+		expect(issues.every((issue) => issue.ruleId === 'no-dbg-macro')).toBe(true)
+	})
+
+	it('should not run rules given with --skip-rules', () => {
+		const issues = runLintsOnFixtures('--skip-rules no-dbg-macro')
+
+		expect(issues.some((issue) => issue.ruleId === 'no-dbg-macro')).toBe(false)
+		expect(issues.some((issue) => issue.ruleId === 'no-unwrap-in-lib')).toBe(true)
+	})
+
+	it('should reject unknown ids in --rules and --skip-rules', () => {
+		const { status, stderr } = runCli(['rust', FIXTURES_DIR, '--rules', 'no-dbg-macros'])
+
+		expect(status).toBe(EXIT_CODE.TOOL_ERROR)
+		expect(stderr).toContain('Unknown rule in --rules: no-dbg-macros')
+		expect(runCli(['rust', FIXTURES_DIR, '--skip-rules', 'no-await-in-loop']).status).toBe(
+			EXIT_CODE.TOOL_ERROR,
+		)
+	})
+
+	it('should exit with 2 on usage errors', () => {
+		expect(runCli(['cobol', FIXTURES_DIR]).status).toBe(EXIT_CODE.TOOL_ERROR)
+		expect(runCli(['rust', FIXTURES_DIR, '--format', 'xml']).status).toBe(EXIT_CODE.TOOL_ERROR)
+		expect(runCli(['rust', path.join(FIXTURES_DIR, 'missing')]).status).toBe(
+			EXIT_CODE.TOOL_ERROR,
+		)
+	})
+
+	it('should print nothing with --quiet', () => {
+		const { status, stdout, stderr } = runCli(['rust', FIXTURES_DIR, '--quiet'])
+
+		expect(status).toBe(EXIT_CODE.LINT_ERRORS)
+		expect(stdout).toBe('')
+		expect(stderr).toBe('')
+	})
+
+	it('should summarize the run in the JSON report', () => {
+		const { stdout } = runCli(['rust', FIXTURES_DIR, '--format', 'json', '--no-cache'])
+		const { summary, findings } = JSON.parse(stdout)
+		const files = new Set(findings.map((finding: { file: string }) => finding.file))
+
+		expect(summary.total).toBe(findings.length)
+		expect(summary.files_with_findings).toBe(files.size)
+		expect(summary.files_scanned).toBeGreaterThanOrEqual(files.size)
+		expect(Object.values(summary.by_rule).reduce((a, b) => Number(a) + Number(b), 0)).toBe(
+			findings.length,
+		)
+	})
+
+	it('should leave out the summary with --no-summary', () => {
+		const json = JSON.parse(
+			runCli(['rust', FIXTURES_DIR, '--format', 'json', '--no-summary']).stdout,
+		)
+		const text = runCli(['rust', FIXTURES_DIR, '--no-summary']).stdout
+
+		expect(json.summary).toBeUndefined()
+		expect(text).not.toContain('Summary:')
+	})
+
+	it('should report unparseable files as tool errors, not findings', () => {
+		const { status, stdout, stderr } = runCli([
+			'rust',
+			PARSE_ERROR_FIXTURES_DIR,
+			'--format',
+			'json',
+		])
+		const { issues } = parseJsonReport(stdout)
+
+		expect(status).toBe(EXIT_CODE.TOOL_ERROR)
+		expect(stderr).toContain('broken.rs:2:')
+		expect(stderr).toContain('generated.rs:4:')
+		expect(stderr).toContain('failed to parse at `')
+		expect(issues.some((issue) => issue.ruleId === 'tempo-parse-error')).toBe(false)
+	})
+
+	it('should lint the rest of the batch when some files fail to parse', () => {
+		const { stdout } = runCli([
+			'rust',
+			PARSE_ERROR_FIXTURES_DIR,
+			'--format',
+			'json',
+			'--max-errors',
+			'2',
+		])
+		const dbg = parseJsonReport(stdout).issues.filter((issue) => issue.ruleId === 'no-dbg-macro')
+
+		expect(dbg.map((issue) => path.basename(issue.file))).toEqual(['broken.rs', 'valid.rs'])
+	})
+
+	it('should stop when more files fail to parse than --max-errors allows', () => {
+		const { status, stdout, stderr } = runCli([
+			'rust',
+			PARSE_ERROR_FIXTURES_DIR,
+			'--format',
+			'json',
+			'--max-errors',
+			'1',
+		])
+
+		expect(status).toBe(EXIT_CODE.TOOL_ERROR)
+		expect(stdout).toBe('')
+		expect(stderr).toContain('broken.rs:2:')
+		expect(stderr).toContain('generated.rs:4:')
+		expect(stderr).toContain('Stopped: 2 files failed to parse, more than --max-errors 1')
+		// The scan stops at the first file over the limit, without parsing the rest
+		expect(runCli(['rust', PARSE_ERROR_FIXTURES_DIR, '--max-errors', '0']).stderr).toContain(
+			'Stopped: 1 file failed to parse, more than --max-errors 0',
+		)
+		expect(runCli(['rust', PARSE_ERROR_FIXTURES_DIR, '--max-errors', 'x']).status).toBe(
+			EXIT_CODE.TOOL_ERROR,
+		)
+	})
+
+	it('should lint stdin as the given file', () => {
+		const source = 'pub fn parse(s: &str) -> i32 {\n    s.parse().unwrap()\n}\n'
+		const { stdout } = runCli(
+			['rust', '-', '--stdin-filename', 'src/lib.rs', '--format', 'json'],
+			source,
+		)
+		const { issues } = parseJsonReport(stdout)
+
+		expect(stdout.trim().split('\n')).toHaveLength(1)
+		expect(issues.map((issue) => [issue.ruleId, issue.file, issue.line])).toEqual([
+			['no-unwrap-in-lib', 'src/lib.rs', 2],
+		])
+	})
+
+	it('should scan several paths together with one summary', () => {
+		const single = parseJsonReport(runCli(['rust', FIXTURES_DIR, '--format', 'json']).stdout).issues
+		const { stdout } = runCli([
+			'rust',
+			FIXTURES_DIR,
+			path.join(FIXTURES_DIR, 'with-dbg.rs'),
+			WORKSPACE_FIXTURES_DIR,
+			FIXTURES_DIR,
+			'--format',
+			'json',
+		])
+		const { issues } = parseJsonReport(stdout)
+
+		expect(stdout.match(/"schema_version"/g)).toHaveLength(1)
+		expect(issues.filter((issue) => issue.file.startsWith('test-fixtures/rust/'))).toEqual(single)
+		expect(issues.some((issue) => issue.file.includes('rust-workspace'))).toBe(true)
+	})
+
+	it('should tell library code from binaries by the Cargo targets with --workspace', () => {
+		const byPath = parseJsonReport(
+			runCli(['rust', WORKSPACE_FIXTURES_DIR, '--format', 'json']).stdout,
+		)
+		const byTargets = parseJsonReport(
+			runCli(['rust', WORKSPACE_FIXTURES_DIR, '--workspace', '--format', 'json']).stdout,
+		)
+		const files = (issues: LintIssue[]) =>
+			[...new Set(issues.map((issue) => path.basename(issue.file)))].sort()
+
+		// By path, `cli/` looks like binary code and `launcher.rs` like library code
+		expect(files(byPath.issues)).toEqual(['launcher.rs'])
+		expect(files(byTargets.issues)).toEqual(['mod.rs'])
+	})
+
+	it('should only run the test rules on test code with --tests-only', () => {
+		const findings = (args: string[]) =>
+			parseJsonReport(
+				runCli(['rust', TESTS_FIXTURES_DIR, '--format', 'json', ...args]).stdout,
+			).issues.map((issue) => [issue.ruleId, path.basename(issue.file), issue.line])
+
+		expect(findings(['--tests-only'])).toEqual([
+			['no-noop-assert', 'lib.rs', 17],
+			['no-ignored-test', 'network.rs', 2],
+		])
+		expect(findings([])).toEqual([
+			['no-unwrap-in-lib', 'lib.rs', 2],
+			['no-dbg-macro', 'lib.rs', 11],
+		])
+		expect(findings(['--rules', 'no-ignored-test'])).toEqual([['no-ignored-test', 'network.rs', 2]])
+	})
+
+	it('should run test rules the config gives a level without --tests-only', () => {
+		const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-test-rules-'))
+		const configPath = path.join(dir, 'tempo-lints.toml')
+		fs.writeFileSync(configPath, '[rules]\nno-noop-assert = "warning"\nno-ignored-test = "off"\n')
+		try {
+			const lint = (...flags: string[]) => {
+				const args = ['rust', TESTS_FIXTURES_DIR, '--format', 'json', '--config', configPath]
+				return parseJsonReport(runCli([...args, ...flags]).stdout).issues.map((issue) => [
+					issue.ruleId,
+					issue.severity,
+					path.basename(issue.file),
+				])
+			}
+
+			expect(lint()).toEqual([
+				['no-unwrap-in-lib', 'warning', 'lib.rs'],
+				['no-dbg-macro', 'error', 'lib.rs'],
+				['no-noop-assert', 'warning', 'lib.rs'],
+			])
+			expect(lint('--tests-only')).toEqual([['no-noop-assert', 'warning', 'lib.rs']])
+		} finally {
+			fs.rmSync(dir, { recursive: true, force: true })
+		}
+	})
+
+	it('should reject other rules and languages with --tests-only', () => {
+		const withRule = runCli(['rust', TESTS_FIXTURES_DIR, '--tests-only', '--rules', 'no-dbg-macro'])
+
+		expect(withRule.status).toBe(EXIT_CODE.TOOL_ERROR)
+		expect(withRule.stderr).toContain('--tests-only only runs the test rules')
+		expect(runCli(['typescript', TESTS_FIXTURES_DIR, '--tests-only']).status).toBe(
+			EXIT_CODE.TOOL_ERROR,
+		)
+	})
+
+	it('should reject opt-in rules in --rules unless the config gives them a level', () => {
+		const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-opt-in-'))
+		const configPath = path.join(dir, 'tempo-lints.toml')
+		fs.writeFileSync(configPath, '[rules]\nno-shadowing = "warning"\n')
+		try {
+			const { status, stderr } = runCli(['rust', FIXTURES_DIR, '--rules', 'no-shadowing'])
+
+			expect(status).toBe(EXIT_CODE.TOOL_ERROR)
+			expect(stderr).toContain("--rules can't turn on opt-in rule no-shadowing")
+			const withLevel = ['--rules', 'no-shadowing', '--config', configPath]
+			expect(runCli(['rust', FIXTURES_DIR, ...withLevel]).status).not.toBe(
+				EXIT_CODE.TOOL_ERROR,
+			)
+		} finally {
+			fs.rmSync(dir, { recursive: true, force: true })
+		}
+	})
+
+	it('should reject --workspace without a Cargo.toml', () => {
+		const { status, stderr } = runCli(['rust', FIXTURES_DIR, '--workspace'])
+
+		expect(status).toBe(EXIT_CODE.TOOL_ERROR)
+		expect(stderr).toContain(`Could not read ${path.join(FIXTURES_DIR, 'Cargo.toml')}`)
+	})
+
+	it('should report paths relative to --relative-to', () => {
+		const fromCwd = parseJsonReport(
+			runCli(['rust', FIXTURES_DIR, '--format', 'json']).stdout,
+		).issues
+		const fromFixtures = parseJsonReport(
+			runCli(['rust', FIXTURES_DIR, '--format', 'json', '--relative-to', FIXTURES_DIR]).stdout,
+		).issues
+		const fromScripts = parseJsonReport(
+			runCli(['rust', FIXTURES_DIR, '--format', 'json', '--relative-to', 'scripts']).stdout,
+		).issues
+
+		expect(fromCwd.length).toBeGreaterThan(0)
+		expect(fromCwd.every((issue) => issue.file.startsWith('test-fixtures/rust/'))).toBe(true)
+		expect(fromFixtures.map((issue) => issue.file)).toEqual(
+			fromCwd.map((issue) => path.relative(FIXTURES_DIR, path.join(PACKAGE_ROOT, issue.file))),
+		)
+		expect(fromScripts.every((issue) => path.isAbsolute(issue.file))).toBe(true)
+	})
+
+	it('should reject a --relative-to that is not a directory', () => {
+		const { status, stderr } = runCli(['rust', FIXTURES_DIR, '--relative-to', 'package.json'])
+
+		expect(status).toBe(EXIT_CODE.TOOL_ERROR)
+		expect(stderr).toContain("--relative-to 'package.json' is not a directory")
+	})
+
+	it('should lint only the files listed with --files-from', () => {
+		const list = [
+			'test-fixtures/rust/with-dbg.rs',
+			'test-fixtures/rust/deleted.rs',
+			'README.md',
+			'',
+		].join('\n')
+		const { stdout, stderr } = runCli(['rust', '--files-from', '-', '--format', 'json'], list)
+
+		const files = parseJsonReport(stdout).issues.map((issue) => issue.file)
+		expect(files.length).toBeGreaterThan(0)
+		expect(new Set(files)).toEqual(new Set(['test-fixtures/rust/with-dbg.rs']))
+		expect(stderr).toContain(
+			"Skipping 'test-fixtures/rust/deleted.rs' from --files-from: no such file",
+		)
+		expect(stderr).toContain("Skipping 'README.md' from --files-from: not a .rs file")
+	})
+
+	it('should succeed without scanning when --files-from lists nothing to lint', () => {
+		const { status, stdout } = runCli(
+			['rust', '--files-from', '-', '--format', 'json'],
+			'README.md\n',
+		)
+
+		expect(status).toBe(EXIT_CODE.SUCCESS)
+		expect(parseJsonReport(stdout).issues).toEqual([])
+	})
+
+	it('should flag mod.rs files once no-mod-rs is enabled', () => {
+		const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-mod-rs-'))
+		const configPath = path.join(dir, 'tempo-lints.toml')
+		const lint = (config: string) => {
+			fs.writeFileSync(configPath, config)
+			const args = ['rust', WORKSPACE_FIXTURES_DIR, '--format', 'json', '--config', configPath]
+			return parseJsonReport(runCli(args).stdout).issues.filter(
+				(issue) => issue.ruleId === 'no-mod-rs',
+			)
+		}
+		try {
+			const [issue, ...rest] = lint('[rules]\nno-mod-rs = "error"\n')
+			expect(rest).toEqual([])
+			expect(issue?.file).toBe('test-fixtures/rust-workspace/crates/node/src/cli/mod.rs')
+			expect(issue?.message).toContain('`cli.rs`')
+
+			expect(lint('[rules.no-mod-rs]\nlevel = "error"\nallow = ["**/cli/"]\n')).toEqual([])
+		} finally {
+			fs.rmSync(dir, { recursive: true, force: true })
+		}
+	})
+
+	it('should fix redundant returns and delete bare ones with bare-returns', () => {
+		const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-returns-'))
+		const file = path.join(dir, 'returns.rs')
+		const configPath = path.join(dir, 'tempo-lints.toml')
+		const source = [
+			'fn answer() -> u32 {',
+			'    let x = 42;',
+			'    return x;',
+			'}',
+			'',
+			'fn stop() {',
+			'    cleanup();',
+			'    return;',
+			'}',
+			'',
+		].join('\n')
+		const fix = (config: string) => {
+			fs.writeFileSync(file, source)
+			fs.writeFileSync(configPath, config)
+			runCli(['rust', dir, '--fix', '--no-cache', '--config', configPath])
+			return fs.readFileSync(file, 'utf8')
+		}
+		try {
+			expect(fix('')).toBe(source.replace('return x;', 'x'))
+			expect(fix('[rules.no-redundant-return]\nbare-returns = true\n')).toBe(
+				source.replace('return x;', 'x').replace('    return;\n', ''),
+			)
+		} finally {
+			fs.rmSync(dir, { recursive: true, force: true })
+		}
+	})
+
+	it('should run rules from plugins listed in the config', () => {
+		const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-plugins-'))
+		const configPath = path.join(dir, 'tempo-lints.toml')
+		const plugin = path.join(PACKAGE_ROOT, 'test-fixtures/plugins/house-rules.ts')
+		fs.writeFileSync(
+			configPath,
+			`plugins = [${JSON.stringify(plugin)}]\n\n[rules]\nrequire-license-header = "warning"\n`,
+		)
+		const lint = (...args: string[]) => {
+			const cliArgs = ['rust', FIXTURES_DIR, '--format', 'json', '--config', configPath, ...args]
+			return parseJsonReport(runCli(cliArgs).stdout).issues
+		}
+		try {
+			const issues = lint()
+			const headers = issues.filter((issue) => issue.ruleId === 'require-license-header')
+
+			// None of the fixtures has a license header
+			expect(new Set(headers.map((issue) => issue.file))).toEqual(
+				new Set(issues.map((issue) => issue.file)),
+			)
+			for (const issue of headers) {
+				expect(issue).toMatchObject({ severity: 'warning', line: 1, column: 1 })
+			}
+			expect(lint('--rules', 'require-license-header')).toEqual(headers)
+			expect(lint('--skip-rules', 'require-license-header')).toEqual(
+				issues.filter((issue) => issue.ruleId !== 'require-license-header'),
+			)
+		} finally {
+			fs.rmSync(dir, { recursive: true, force: true })
+		}
+	})
+
+	it('should apply per-path severity overrides over the rule level', () => {
+		const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-overrides-'))
+		const configPath = path.join(dir, 'tempo-lints.toml')
+		fs.writeFileSync(
+			configPath,
+			[
+				'[rules]',
+				'no-dbg-macro = "error"',
+				'',
+				'[[overrides]]',
+				'path = "test-fixtures/rust/with-dbg.rs"',
+				'rule = "no-dbg-macro"',
+				'severity = "warning"',
+				'',
+			].join('\n'),
+		)
+		try {
+			const { issues } = parseJsonReport(
+				runCli(['rust', FIXTURES_DIR, '--format', 'json', '--config', configPath]).stdout,
+			)
+			const dbg = issues.filter((issue) => issue.ruleId === 'no-dbg-macro')
+
+			const overridden = dbg.filter((issue) => issue.file === 'test-fixtures/rust/with-dbg.rs')
+			const others = dbg.filter((issue) => !overridden.includes(issue))
+
+			expect(overridden.map((issue) => issue.severity)).toEqual(['warning'])
+			expect(others.length).toBeGreaterThan(0)
+			for (const issue of others) {
+				expect(issue.severity).toBe('error')
+			}
+		} finally {
+			fs.rmSync(dir, { recursive: true, force: true })
+		}
+	})
+
+	it('should only color the text output when asked to or on a terminal', () => {
+		const file = path.join(FIXTURES_DIR, 'with-dbg.rs')
+		const plain = runCli(['rust', file]).stdout
+
+		expect(plain).toContain('error[no-dbg-macro]')
+		expect(runCli(['rust', file, '--color', 'never']).stdout).toBe(plain)
+		expect(runCli(['rust', file, '--color', 'always']).stdout).toContain(
+			'\x1b[1;31merror\x1b[0m[\x1b[1mno-dbg-macro\x1b[0m]',
+		)
+		expect(runCli(['rust', file, '--color', 'sometimes']).status).toBe(EXIT_CODE.TOOL_ERROR)
+	})
+
+	it('should show source snippets unless turned off', () => {
+		const file = path.join(FIXTURES_DIR, 'with-dbg.rs')
+
+		const { stdout } = runCli(['rust', file, '--context', '1'])
+		const terse = runCli(['rust', file, '--no-snippets']).stdout
+
+		expect(stdout).toContain('2 |     let x = 42;\n3 |     dbg!(x);\n  |     ^^^^')
+		expect(stdout).toContain('4 | }\n')
+		expect(terse).toContain('error[no-dbg-macro]')
+		expect(terse).not.toContain('3 |')
+		expect(runCli(['rust', file, '--context', '-1']).status).toBe(EXIT_CODE.TOOL_ERROR)
+	})
+
+	it('should print run timings to stderr with --stats', () => {
+		const file = path.join(FIXTURES_DIR, 'with-dbg.rs')
+		const { stdout, stderr } = runCli([
+			'rust',
+			file,
+			'--format',
+			'json',
+			'--stats',
+			'--slowest',
+			'2',
+		])
+
+		expect(() => JSON.parse(stdout)).not.toThrow()
+		expect(stderr).toMatch(/Stats: \S+ total, 1 file scanned/)
+		expect(stderr).toContain('Scanning')
+		expect(
+			runCli(['rust', file, '--format', 'json', '--stats', '--quiet']).stderr,
+		).not.toContain('Stats:')
+		expect(runCli(['rust', file, '--slowest', '2']).status).toBe(EXIT_CODE.TOOL_ERROR)
+		expect(runCli(['rust', file, '--stats', '--watch']).status).toBe(EXIT_CODE.TOOL_ERROR)
+	})
+
+	it('should time the passes finishing YAML rule candidates with --stats', () => {
+		const file = path.join(FIXTURES_DIR, 'long-lines.rs')
+		const { stderr } = runCli(['rust', file, '--stats', '--slowest', '1', '--no-cache'])
+
+		expect(stderr).toMatch(/Slowest CLI rules:\n(?: {2}.*\n)* {2}max-line-length +\S+/)
+		expect(stderr).toContain('Slowest files in CLI rules:\n  test-fixtures/rust/long-lines.rs ')
+	})
+
+	it('should fail on finding budgets by count rather than severity', () => {
+		const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-budgets-'))
+		const configPath = path.join(dir, 'tempo-lints.toml')
+		const lint = (max: number) => {
+			fs.writeFileSync(configPath, `[rules]\nno-dbg-macro.max = ${max}\n`)
+			const file = path.join(FIXTURES_DIR, 'with-dbg.rs')
+			return runCli(['rust', file, '--format', 'json', '--config', configPath])
+		}
+		try {
+			const within = lint(1)
+			expect(within.status).toBe(EXIT_CODE.SUCCESS)
+			expect(within.stderr).toContain('no-dbg-macro: 1 finding, within the budget of 1')
+			expect(parseJsonReport(within.stdout).issues.map((issue) => issue.ruleId)).toEqual([
+				'no-dbg-macro',
+			])
+
+			const over = lint(0)
+			expect(over.status).toBe(EXIT_CODE.LINT_ERRORS)
+			expect(over.stderr).toContain('no-dbg-macro: 1 finding, over the budget of 0')
+		} finally {
+			fs.rmSync(dir, { recursive: true, force: true })
+		}
+	})
+
+	it('should only report findings on lines added by --diff', () => {
+		const diff = [
+			'diff --git a/test-fixtures/rust/with-dbg.rs b/test-fixtures/rust/with-dbg.rs',
+			'--- a/test-fixtures/rust/with-dbg.rs',
+			'+++ b/test-fixtures/rust/with-dbg.rs',
+			'@@ -1,3 +1,4 @@',
+			' fn main() {',
+			'     let x = 42;',
+			'+    dbg!(x);',
+			' }',
+			'',
+		].join('\n')
+		const { status, stdout } = runCli(
+			['rust', FIXTURES_DIR, '--format', 'json', '--diff', '-'],
+			diff,
+		)
+		const { issues } = parseJsonReport(stdout)
+
+		expect(status).toBe(EXIT_CODE.LINT_ERRORS)
+		expect(issues.length).toBeGreaterThan(0)
+		for (const issue of issues) {
+			expect(issue).toMatchObject({ file: 'test-fixtures/rust/with-dbg.rs', line: 3 })
+		}
+	})
+
+	it('should only fix findings on lines added by --diff', () => {
+		// Diff paths are relative to the repository root, so the file has to be inside it
+		const dir = fs.mkdtempSync(path.join(PACKAGE_ROOT, 'test-fixtures/tmp-fix-diff-'))
+		const file = path.join(dir, 'main.rs')
+		const source = 'fn main() {\n    dbg!(1);\n    dbg!(2);\n}\n'
+		fs.writeFileSync(file, source)
+		const relative = path.relative(PACKAGE_ROOT, file)
+		const diff = [
+			`diff --git a/${relative} b/${relative}`,
+			`--- a/${relative}`,
+			`+++ b/${relative}`,
+			'@@ -1,3 +1,4 @@',
+			' fn main() {',
+			'     dbg!(1);',
+			'+    dbg!(2);',
+			' }',
+			'',
+		].join('\n')
+		try {
+			const { stdout } = runCli(
+				['rust', dir, '--format', 'json', '--fix', '--no-cache', '--diff', '-'],
+				diff,
+			)
+
+			expect(fs.readFileSync(file, 'utf8')).toBe('fn main() {\n    dbg!(1);\n    2;\n}\n')
+			expect(parseJsonReport(stdout).issues).toEqual([])
+		} finally {
+			fs.rmSync(dir, { recursive: true, force: true })
+		}
+	})
+
+	it('should report configured message templates in JSON', () => {
+		const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-messages-'))
+		const configPath = path.join(dir, 'tempo-lints.toml')
+		fs.writeFileSync(configPath, '[rules]\nno-dbg-macro.message = "{file}:{line}: see wiki/dbg"\n')
+		try {
+			const { issues } = parseJsonReport(
+				runCli(['rust', FIXTURES_DIR, '--format', 'json', '--config', configPath]).stdout,
+			)
+			const dbg = issues.filter((issue) => issue.ruleId === 'no-dbg-macro')
+
+			expect(dbg.length).toBeGreaterThan(0)
+			for (const issue of dbg) {
+				expect(issue.message).toBe(`${issue.file}:${issue.line}: see wiki/dbg`)
+			}
+		} finally {
+			fs.rmSync(dir, { recursive: true, force: true })
+		}
+	})
+
+	it('should apply path-scoped rules to the stdin filename', () => {
+		const source = 'fn main() {\n    "1".parse::<i32>().unwrap();\n}\n'
+		const { stdout } = runCli(
+			['rust', '-', '--stdin-filename', 'src/main.rs', '--format', 'json'],
+			source,
+		)
+
+		expect(parseJsonReport(stdout).issues).toEqual([])
+	})
 })
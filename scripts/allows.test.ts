@@ -0,0 +1,170 @@
+import { describe, expect, it } from 'vitest'
+import {
+	type AllowDirective,
+	applyAllowDirectives,
+	parseAllowDirectives,
+	UNUSED_ALLOW_RULE_ID,
+} from './allows.ts'
+import type { LintIssue } from './shared.ts'
+
+// Positions of every annotation in `source`, the way the directive rule reports them
+function directivesIn(source: string): AllowDirective[] {
+	const positions = source.split('\n').flatMap((text, index) => {
+		const column = text.search(/#!?\[allow|\/\/ (?:tempo-lint-allow|ast-grep-ignore)/)
+		return column === -1 ? [] : [{ line: index + 1, column: column + 1 }]
+	})
+	return parseAllowDirectives('src/lib.rs', source, positions)
+}
+
+function issueAt(line: number, ruleId = 'no-unwrap-in-lib'): LintIssue {
+	return {
+		ruleId,
+		severity: 'error',
+		message: 'Avoid .unwrap()',
+		file: 'src/lib.rs',
+		line,
+		column: 5,
+	}
+}
+
+describe('parseAllowDirectives', () => {
+	it('should cover the whole function for an attribute on it', () => {
+		const source = [
+			'#[allow(tempo::no_unwrap_in_lib)]',
+			'pub fn parse(s: &str) -> u32 {',
+			'    let v = if s.is_empty() { "0" } else { s };',
+			"    let brace = '{';",
+			'    v.parse().unwrap()',
+			'}',
+			'',
+			'pub fn other() {}',
+		].join('\n')
+
+		expect(directivesIn(source)).toEqual([
+			{
+				file: 'src/lib.rs',
+				line: 1,
+				column: 1,
+				ruleIds: ['no-unwrap-in-lib'],
+				startLine: 1,
+				endLine: 6,
+			},
+		])
+	})
+
+	it('should cover only the next line for a comment', () => {
+		const source = [
+			'fn f() {',
+			'    // tempo-lint-allow: no-unwrap-in-lib, no-dbg-macro',
+			'    let a = x.unwrap();',
+			'    let b = y.unwrap();',
+			'}',
+		].join('\n')
+
+		const [directive] = directivesIn(source)
+
+		expect(directive?.ruleIds).toEqual(['no-unwrap-in-lib', 'no-dbg-macro'])
+		expect([directive?.startLine, directive?.endLine]).toEqual([3, 3])
+	})
+
+	it('should cover the enclosing module for an inner attribute', () => {
+		const source = [
+			'mod inner {',
+			'    #![allow(tempo::no_panic_in_lib)]',
+			'    fn f() { panic!() }',
+			'}',
+			'fn g() { panic!() }',
+		].join('\n')
+
+		const [directive] = directivesIn(source)
+
+		expect([directive?.startLine, directive?.endLine]).toEqual([2, 4])
+	})
+
+	it('should cover the whole file for a top-level inner attribute', () => {
+		const source = '#![allow(tempo::no_emojis)]\n\nfn a() {}\n\nfn b() {}\n'
+
+		const [directive] = directivesIn(source)
+
+		expect([directive?.startLine, directive?.endLine]).toEqual([1, 6])
+	})
+
+	it('should stop at the end of a field', () => {
+		const source = [
+			'struct S {',
+			'    #[allow(tempo::no_emojis)]',
+			"    a: &'static str,",
+			'    b: u32,',
+			'}',
+		].join('\n')
+
+		const [directive] = directivesIn(source)
+
+		expect([directive?.startLine, directive?.endLine]).toEqual([2, 3])
+	})
+
+	it('should read several tempo lints from one attribute', () => {
+		const source =
+			'#[allow(dead_code, tempo::no_unwrap_in_lib, tempo::no_expect_in_lib)]\nfn f() {}\n'
+
+		expect(directivesIn(source)[0]?.ruleIds).toEqual(['no-unwrap-in-lib', 'no-expect-in-lib'])
+	})
+
+	it('should map the old ids of renamed rules to the new ones', () => {
+		const source = [
+			'// ast-grep-ignore: no-dbg-macro, tracing-no-format',
+			'info!("{}", format!("{x}"));',
+			'#[allow(tempo::tracing_no_format)]',
+			'fn f() {}',
+			'// ast-grep-ignore: no-dbg-macro',
+			'dbg!(x);',
+		].join('\n')
+
+		expect(directivesIn(source).map((directive) => directive.ruleIds)).toEqual([
+			['no-string-format-in-log'],
+			['no-string-format-in-log'],
+		])
+	})
+})
+
+describe('applyAllowDirectives', () => {
+	const source = [
+		'#[allow(tempo::no_unwrap_in_lib)]',
+		'fn a() {',
+		'    x.unwrap();',
+		'}',
+		'fn b() {',
+		'    // tempo-lint-allow: no-unwrap-in-lib',
+		'    x.unwrap();',
+		'    y.unwrap();',
+		'}',
+	].join('\n')
+
+	it('should drop findings inside the allowed scope for the allowed rule', () => {
+		const issues = [issueAt(3), issueAt(7), issueAt(8), issueAt(3, 'no-dbg-macro')]
+
+		const result = applyAllowDirectives(issues, directivesIn(source))
+
+		expect(result.issues.map((issue) => [issue.line, issue.ruleId])).toEqual([
+			[8, 'no-unwrap-in-lib'],
+			[3, 'no-dbg-macro'],
+		])
+		expect(result.unused).toEqual([])
+	})
+
+	it('should report allows that suppressed nothing', () => {
+		const result = applyAllowDirectives([issueAt(3)], directivesIn(source))
+
+		expect(result.unused).toEqual([
+			{
+				ruleId: UNUSED_ALLOW_RULE_ID,
+				severity: 'warning',
+				message:
+					"Unused allow for 'no-unwrap-in-lib': no finding was suppressed. Remove the annotation.",
+				file: 'src/lib.rs',
+				line: 6,
+				column: 5,
+			},
+		])
+	})
+})
@@ -0,0 +1,353 @@
+import fs from 'node:fs'
+import { RENAMED_RULE_IDS, resolveRuleId } from './config.ts'
+import { groupByFile, type LintIssue, warn } from './shared.ts'
+
+// Rule in `src/<lang>/directives/` whose matches locate inline allow annotations
+export const ALLOW_DIRECTIVE_RULE_ID = 'tempo-allow'
+
+// Rule id used to report allow annotations that never suppressed anything
+export const UNUSED_ALLOW_RULE_ID = 'unused-allow'
+
+const ALLOW_COMMENT = /^\/\/\s*tempo-lint-allow:\s*([\w-]+(?:\s*,\s*[\w-]+)*)/
+// ast-grep suppresses findings itself, but doesn't know the old ids of renamed rules
+const IGNORE_COMMENT = /^\/\/\s*ast-grep-ignore:\s*([\w-]+(?:\s*,\s*[\w-]+)*)/
+const TOOL_LINT_PATH = /\btempo::(\w+)/g
+
+// Sticky patterns, matched at a given offset while scanning Rust source
+const RAW_STRING_START = /b?r(#*)"/y
+const CHAR_LITERAL = /'(\\u\{[0-9A-Fa-f]+\}|\\.|[^\\'])'/y
+const EXPRESSION_CONTINUES = /\s*([;,.?]|else\b)/y
+
+export interface AllowDirective {
+	file: string
+	// 1-based position of the annotation itself
+	line: number
+	column: number
+	ruleIds: string[]
+	// Inclusive 1-based line range the annotation applies to
+	startLine: number
+	endLine: number
+}
+
+/**
+ * Find the allow annotations starting at each directive match in a file's source.
+ * - `// tempo-lint-allow: a, b` covers only the next line
+ * - `#[allow(tempo::a)]` covers the item it's attached to, e.g. a whole function body
+ * - `#![allow(tempo::a)]` covers the enclosing module or block (the whole file at the top level)
+ */
+export function parseAllowDirectives(
+	file: string,
+	source: string,
+	positions: { line: number; column: number }[],
+): AllowDirective[] {
+	const lineStarts = getLineStarts(source)
+	const directives: AllowDirective[] = []
+
+	for (const { line, column } of positions) {
+		const offset = findDirectiveOffset(source, lineStarts, line, column)
+		if (offset === -1) continue
+
+		if (source.startsWith('//', offset)) {
+			const lineEnd = source.indexOf('\n', offset)
+			const comment = source.slice(offset, lineEnd === -1 ? source.length : lineEnd)
+			const allow = comment.match(ALLOW_COMMENT)?.[1]
+			const ignore = comment.match(IGNORE_COMMENT)?.[1]
+			const ids = allow ?? ignore
+			if (ids === undefined) continue
+			const ruleIds = ids
+				.split(',')
+				.map((id) => id.trim())
+				.filter((id) => allow !== undefined || RENAMED_RULE_IDS.has(id))
+				.map((id) => resolveRuleId(id, `${file}:${line}`))
+			if (ruleIds.length === 0) continue
+			directives.push({ file, line, column, ruleIds, startLine: line + 1, endLine: line + 1 })
+			continue
+		}
+
+		const attributeEnd = skipDelimited(source, source.indexOf('[', offset))
+		const attribute = source.slice(offset, attributeEnd)
+		const ruleIds = [...attribute.matchAll(TOOL_LINT_PATH)].map((m) =>
+			resolveRuleId(m[1]!.replaceAll('_', '-'), `${file}:${line}`),
+		)
+		if (ruleIds.length === 0) continue
+
+		const isInner = source.startsWith('#!', offset)
+		const scopeEnd = isInner
+			? findBlockEnd(source, attributeEnd)
+			: findItemEnd(source, attributeEnd)
+		directives.push({
+			file,
+			line,
+			column,
+			ruleIds,
+			startLine: isInner && !isInsideBlock(source, offset) ? 1 : line,
+			endLine: lineOf(lineStarts, scopeEnd),
+		})
+	}
+
+	return directives
+}
+
+/**
+ * Drop issues covered by an allow annotation for their rule.
+ * Returns the remaining issues and one `unused-allow` warning per allowed rule id that
+ * didn't suppress anything.
+ */
+export function applyAllowDirectives(
+	issues: LintIssue[],
+	directives: AllowDirective[],
+): { issues: LintIssue[]; unused: LintIssue[] } {
+	const used = new Set<string>()
+	const byFile = groupBy(directives)
+
+	const remaining = issues.filter((issue) => {
+		const directive = byFile.get(issue.file)?.find(
+			(d) =>
+				d.ruleIds.includes(issue.ruleId) && issue.line >= d.startLine && issue.line <= d.endLine,
+		)
+		if (!directive) return true
+		used.add(directiveKey(directive, issue.ruleId))
+		return false
+	})
+
+	const unused: LintIssue[] = []
+	for (const directive of directives) {
+		for (const ruleId of directive.ruleIds) {
+			if (used.has(directiveKey(directive, ruleId))) continue
+			unused.push({
+				ruleId: UNUSED_ALLOW_RULE_ID,
+				severity: 'warning',
+				message: `Unused allow for '${ruleId}': no finding was suppressed. Remove the annotation.`,
+				file: directive.file,
+				line: directive.line,
+				column: directive.column,
+			})
+		}
+	}
+
+	return { issues: remaining, unused }
+}
+
+/**
+ * Read the files referenced by directive matches and parse their allow annotations.
+ * `readSource` reads a file's contents; stdin mode passes the buffer instead of reading disk.
+ */
+export function collectAllowDirectives(
+	directiveIssues: LintIssue[],
+	readSource: (file: string) => string = (file) => fs.readFileSync(file, 'utf8'),
+): AllowDirective[] {
+	const directives: AllowDirective[] = []
+
+	for (const [file, matches] of Object.entries(groupByFile(directiveIssues))) {
+		let source: string
+		try {
+			source = readSource(file)
+		} catch (err) {
+			warn(`Could not read ${file} to check allow annotations: ${(err as Error).message}`)
+			continue
+		}
+		directives.push(...parseAllowDirectives(file, source, matches))
+	}
+
+	return directives
+}
+
+function directiveKey(directive: AllowDirective, ruleId: string): string {
+	return `${directive.file}:${directive.line}:${directive.column}:${ruleId}`
+}
+
+function groupBy(directives: AllowDirective[]): Map<string, AllowDirective[]> {
+	const map = new Map<string, AllowDirective[]>()
+	for (const directive of directives) {
+		const list = map.get(directive.file) ?? []
+		list.push(directive)
+		map.set(directive.file, list)
+	}
+	return map
+}
+
+function getLineStarts(source: string): number[] {
+	const starts = [0]
+	for (let i = 0; i < source.length; i++) {
+		if (source[i] === '\n') starts.push(i + 1)
+	}
+	return starts
+}
+
+// 1-based line containing `offset`
+function lineOf(lineStarts: number[], offset: number): number {
+	let low = 0
+	let high = lineStarts.length - 1
+	while (low < high) {
+		const mid = Math.ceil((low + high) / 2)
+		if (lineStarts[mid]! <= offset) low = mid
+		else high = mid - 1
+	}
+	return low + 1
+}
+
+// Locates the `#` or `//` of the annotation reported at a 1-based line and column
+function findDirectiveOffset(
+	source: string,
+	lineStarts: number[],
+	line: number,
+	column: number,
+): number {
+	const lineStart = lineStarts[line - 1]
+	if (lineStart === undefined) return -1
+
+	const candidate = lineStart + column - 1
+	if (source[candidate] === '#' || source.startsWith('//', candidate)) {
+		return candidate
+	}
+
+	// Columns may count bytes rather than characters; fall back to the first annotation on the line
+	const lineEnd = lineStarts[line] ?? source.length
+	const text = source.slice(lineStart, lineEnd)
+	const index = text.search(/#!?\[|\/\/\s*(?:tempo-lint-allow|ast-grep-ignore):/)
+	return index === -1 ? -1 : lineStart + index
+}
+
+// Skips a string, char literal or comment at `i`, returning the offset just past it,
+// or `i` itself when there's nothing to skip
+function skipLiteral(source: string, i: number): number {
+	const ch = source[i]
+
+	if (ch === '/' && source[i + 1] === '/') {
+		const end = source.indexOf('\n', i)
+		return end === -1 ? source.length : end
+	}
+
+	if (ch === '/' && source[i + 1] === '*') {
+		let depth = 1
+		let j = i + 2
+		while (j < source.length && depth > 0) {
+			if (source.startsWith('/*', j)) {
+				depth++
+				j += 2
+			} else if (source.startsWith('*/', j)) {
+				depth--
+				j += 2
+			} else {
+				j++
+			}
+		}
+		return j
+	}
+
+	RAW_STRING_START.lastIndex = i
+	const raw = ch === 'b' || ch === 'r' ? RAW_STRING_START.exec(source) : null
+	if (raw && !/\w/.test(source[i - 1] ?? '')) {
+		const terminator = `"${raw[1]}`
+		const end = source.indexOf(terminator, i + raw[0].length)
+		return end === -1 ? source.length : end + terminator.length
+	}
+
+	if (ch === '"') {
+		let j = i + 1
+		while (j < source.length && source[j] !== '"') {
+			j += source[j] === '\\' ? 2 : 1
+		}
+		return j + 1
+	}
+
+	// Char literal ('a', '\n', '\u{1F600}'), as opposed to a lifetime ('a)
+	if (ch === "'") {
+		CHAR_LITERAL.lastIndex = i
+		const char = CHAR_LITERAL.exec(source)
+		return char ? i + char[0].length : i + 1
+	}
+
+	return i
+}
+
+// Given the offset of an opening bracket, returns the offset just past its closing bracket
+function skipDelimited(source: string, open: number): number {
+	let depth = 0
+	let i = open
+	while (i < source.length) {
+		const next = skipLiteral(source, i)
+		if (next !== i) {
+			i = next
+			continue
+		}
+		const ch = source[i]!
+		if ('([{'.includes(ch)) depth++
+		if (')]}'.includes(ch)) {
+			depth--
+			if (depth === 0) return i + 1
+		}
+		i++
+	}
+	return source.length
+}
+
+/**
+ * Offset of the end of the item, statement, field or arm starting at `start`.
+ * Ends at a `;` or `,` outside brackets, after a braced body (`fn`, `impl`, `mod`, `match`, ...),
+ * or at the bracket that closes the enclosing block.
+ */
+function findItemEnd(source: string, start: number): number {
+	let i = start
+	while (i < source.length) {
+		const next = skipLiteral(source, i)
+		if (next !== i) {
+			i = next
+			continue
+		}
+
+		const ch = source[i]!
+		if (ch === ';' || ch === ',') return i
+		if (')]}'.includes(ch)) return i - 1
+		if (ch === '{') {
+			i = skipDelimited(source, i)
+			// A braced body ends the item unless the expression continues, e.g. `Foo { .. };`
+			// or `if a { .. } else { .. }`
+			EXPRESSION_CONTINUES.lastIndex = i
+			if (!EXPRESSION_CONTINUES.test(source)) return i - 1
+			continue
+		}
+		if (ch === '(' || ch === '[') {
+			i = skipDelimited(source, i)
+			continue
+		}
+		i++
+	}
+	return source.length
+}
+
+// Offset of the bracket closing the block that contains `start`, or the end of the file
+function findBlockEnd(source: string, start: number): number {
+	let i = start
+	while (i < source.length) {
+		const next = skipLiteral(source, i)
+		if (next !== i) {
+			i = next
+			continue
+		}
+		const ch = source[i]!
+		if ('([{'.includes(ch)) {
+			i = skipDelimited(source, i)
+			continue
+		}
+		if (')]}'.includes(ch)) return i
+		i++
+	}
+	return source.length
+}
+
+function isInsideBlock(source: string, offset: number): boolean {
+	let depth = 0
+	let i = 0
+	while (i < offset) {
+		const next = skipLiteral(source, i)
+		if (next !== i) {
+			i = next
+			continue
+		}
+		if (source[i] === '{') depth++
+		if (source[i] === '}') depth--
+		i++
+	}
+	return depth > 0
+}
@@ -0,0 +1,121 @@
+import fs from 'node:fs'
+import os from 'node:os'
+import path from 'node:path'
+import { afterEach, describe, expect, it } from 'vitest'
+import {
+	applyBaseline,
+	BASELINE_SCHEMA_VERSION,
+	createBaseline,
+	formatBaseline,
+	hashLine,
+	loadBaseline,
+	parseBaseline,
+	toBaselineEntry,
+} from './baseline.ts'
+import type { LintIssue } from './shared.ts'
+
+const baseDir = process.cwd()
+
+function issue(overrides: Partial<LintIssue>): LintIssue {
+	return {
+		ruleId: 'no-dbg-macro',
+		severity: 'error',
+		message: 'Remove dbg!()',
+		file: 'src/lib.rs',
+		line: 1,
+		column: 5,
+		code: 'dbg!(x);',
+		...overrides,
+	}
+}
+
+describe('toBaselineEntry', () => {
+	it('should key on rule, relative path and line text', () => {
+		expect(toBaselineEntry(issue({}), baseDir)).toEqual({
+			rule_id: 'no-dbg-macro',
+			file: 'src/lib.rs',
+			line_hash: hashLine('dbg!(x);'),
+		})
+	})
+
+	it('should ignore the line number and indentation', () => {
+		const moved = issue({ line: 40, column: 9, code: '    dbg!(x);' })
+
+		expect(toBaselineEntry(moved, baseDir)).toEqual(toBaselineEntry(issue({}), baseDir))
+	})
+
+	it('should make paths relative to the baseline directory', () => {
+		expect(toBaselineEntry(issue({}), path.join(baseDir, 'src')).file).toBe('lib.rs')
+	})
+})
+
+describe('applyBaseline', () => {
+	const baseline = createBaseline([issue({})], baseDir)
+
+	it('should suppress findings recorded in the baseline', () => {
+		const result = applyBaseline([issue({ line: 12 })], baseline, baseDir)
+
+		expect(result.issues).toEqual([])
+		expect(result.suppressed).toBe(1)
+		expect(result.stale).toEqual([])
+	})
+
+	it('should report new findings', () => {
+		const other = issue({ code: 'dbg!(y);' })
+		const result = applyBaseline([issue({}), other], baseline, baseDir)
+
+		expect(result.issues).toEqual([other])
+	})
+
+	it('should let each entry suppress one finding', () => {
+		const duplicate = issue({ line: 2 })
+
+		expect(applyBaseline([issue({}), duplicate], baseline, baseDir).issues).toEqual([duplicate])
+	})
+
+	it('should return entries that matched nothing as stale', () => {
+		const result = applyBaseline([], baseline, baseDir)
+
+		expect(result.stale).toEqual(baseline.entries)
+	})
+})
+
+describe('parseBaseline', () => {
+	it('should round-trip a written baseline', () => {
+		const baseline = createBaseline([issue({ ruleId: 'no-emojis' }), issue({})], baseDir)
+
+		expect(parseBaseline(formatBaseline(baseline), 'baseline.json')).toEqual(baseline)
+		expect(baseline.entries.map((entry) => entry.rule_id)).toEqual(['no-dbg-macro', 'no-emojis'])
+	})
+
+	it('should reject other schema versions', () => {
+		expect(() => parseBaseline('{"schema_version": 99, "entries": []}', 'baseline.json')).toThrow(
+			`baseline.json: unsupported baseline schema_version (expected ${BASELINE_SCHEMA_VERSION})`,
+		)
+	})
+
+	it('should reject malformed entries', () => {
+		expect(() =>
+			parseBaseline('{"schema_version": 1, "entries": [{"rule_id": 1}]}', 'baseline.json'),
+		).toThrow("baseline.json: 'entries' must be an array of {rule_id, file, line_hash}")
+	})
+})
+
+describe('loadBaseline', () => {
+	let tmpDir: string
+
+	afterEach(() => {
+		if (tmpDir) {
+			fs.rmSync(tmpDir, { recursive: true, force: true })
+		}
+	})
+
+	it('should fail on a missing file', () => {
+		tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-baseline-'))
+		const baselinePath = path.join(tmpDir, 'tempo-baseline.json')
+
+		expect(() => loadBaseline(baselinePath)).toThrow(
+			`Could not read baseline file ${baselinePath}`,
+		)
+	})
+})
@@ -0,0 +1,139 @@
+import crypto from 'node:crypto'
+import fs from 'node:fs'
+import path from 'node:path'
+import type { LintIssue } from './shared.ts'
+
+export const DEFAULT_BASELINE_FILE = 'tempo-baseline.json'
+
+/**
+ * Version of the baseline file schema.
+ * Bump this whenever a field is removed, renamed or changes meaning.
+ */
+export const BASELINE_SCHEMA_VERSION = 1
+
+// One grandfathered finding. Line numbers are left out so entries survive edits elsewhere
+// in the file.
+export interface BaselineEntry {
+	rule_id: string
+	// Relative to the directory containing the baseline file, with `/` separators
+	file: string
+	// Hash of the trimmed text of the finding's first line
+	line_hash: string
+}
+
+export interface BaselineFile {
+	schema_version: number
+	entries: BaselineEntry[]
+}
+
+export function hashLine(text: string): string {
+	return crypto.createHash('sha256').update(text.trim()).digest('hex').slice(0, 16)
+}
+
+/**
+ * Build the baseline entry for an issue. `baseDir` is the directory containing the
+ * baseline file; issue paths are resolved against the working directory.
+ */
+export function toBaselineEntry(issue: LintIssue, baseDir: string): BaselineEntry {
+	const file = path.relative(baseDir, path.resolve(issue.file)).split(path.sep).join('/')
+	const line = issue.code?.split('\n')[0] ?? ''
+	return { rule_id: issue.ruleId, file, line_hash: hashLine(line) }
+}
+
+function entryKey(entry: BaselineEntry): string {
+	return `${entry.rule_id}\0${entry.file}\0${entry.line_hash}`
+}
+
+function compareEntries(a: BaselineEntry, b: BaselineEntry): number {
+	const keyA = entryKey(a)
+	const keyB = entryKey(b)
+	return keyA === keyB ? 0 : keyA < keyB ? -1 : 1
+}
+
+/**
+ * Record issues as baseline entries, sorted so the file diffs cleanly between runs.
+ */
+export function createBaseline(issues: LintIssue[], baseDir: string): BaselineFile {
+	return {
+		schema_version: BASELINE_SCHEMA_VERSION,
+		entries: issues.map((issue) => toBaselineEntry(issue, baseDir)).sort(compareEntries),
+	}
+}
+
+export function formatBaseline(baseline: BaselineFile): string {
+	return `${JSON.stringify(baseline, null, 2)}\n`
+}
+
+/**
+ * Parse a baseline file, throwing with a message naming `source` when it's malformed.
+ */
+export function parseBaseline(content: string, source: string): BaselineFile {
+	let data: unknown
+	try {
+		data = JSON.parse(content)
+	} catch (err) {
+		throw new Error(`${source}: invalid JSON: ${(err as Error).message}`)
+	}
+
+	const baseline = data as Partial<BaselineFile> | null
+	if (baseline?.schema_version !== BASELINE_SCHEMA_VERSION) {
+		throw new Error(
+			`${source}: unsupported baseline schema_version (expected ${BASELINE_SCHEMA_VERSION})`,
+		)
+	}
+	if (!Array.isArray(baseline.entries) || !baseline.entries.every(isBaselineEntry)) {
+		throw new Error(`${source}: 'entries' must be an array of {rule_id, file, line_hash}`)
+	}
+	return { schema_version: baseline.schema_version, entries: baseline.entries }
+}
+
+function isBaselineEntry(value: unknown): value is BaselineEntry {
+	const entry = value as Partial<BaselineEntry> | null
+	return (
+		typeof entry?.rule_id === 'string' &&
+		typeof entry.file === 'string' &&
+		typeof entry.line_hash === 'string'
+	)
+}
+
+export function loadBaseline(baselinePath: string): BaselineFile {
+	let content: string
+	try {
+		content = fs.readFileSync(baselinePath, 'utf8')
+	} catch (err) {
+		throw new Error(`Could not read baseline file ${baselinePath}: ${(err as Error).message}`)
+	}
+	return parseBaseline(content, baselinePath)
+}
+
+/**
+ * Drop issues recorded in the baseline. Each entry suppresses at most one issue, so a second
+ * identical finding on a new line is still reported. Entries that matched nothing are
+ * returned as `stale`.
+ */
+export function applyBaseline(
+	issues: LintIssue[],
+	baseline: BaselineFile,
+	baseDir: string,
+): { issues: LintIssue[]; suppressed: number; stale: BaselineEntry[] } {
+	const remaining = new Map<string, BaselineEntry[]>()
+	for (const entry of baseline.entries) {
+		const key = entryKey(entry)
+		remaining.set(key, [...(remaining.get(key) ?? []), entry])
+	}
+
+	const result: LintIssue[] = []
+	let suppressed = 0
+	for (const issue of issues) {
+		const matches = remaining.get(entryKey(toBaselineEntry(issue, baseDir)))
+		if (matches && matches.length > 0) {
+			matches.pop()
+			suppressed++
+		} else {
+			result.push(issue)
+		}
+	}
+
+	const stale = [...remaining.values()].flat().sort(compareEntries)
+	return { issues: result, suppressed, stale }
+}
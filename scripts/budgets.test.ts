@@ -0,0 +1,57 @@
+import { describe, expect, it } from 'vitest'
+import { checkBudgets, failsRun, formatBudget } from './budgets.ts'
+import type { LintIssue } from './shared.ts'
+
+function finding(ruleId: string, severity: LintIssue['severity'] = 'error'): LintIssue {
+	return { ruleId, severity, message: 'Finding.', file: 'src/lib.rs', line: 1, column: 1 }
+}
+
+const UNWRAPS = ['src/a.rs', 'src/b.rs', 'src/c.rs'].map((file) => ({
+	...finding('no-unwrap-in-lib'),
+	file,
+}))
+
+describe('checkBudgets', () => {
+	it('should count the findings of rules with a max', () => {
+		const config = {
+			rules: {},
+			ruleOptions: {
+				'no-unwrap-in-lib': { max: 2 },
+				'no-dbg-macro': { max: 0 },
+				'no-todo-comment': { markers: ['FIXME'] },
+			},
+		}
+
+		expect(checkBudgets(UNWRAPS, config)).toEqual([
+			{ ruleId: 'no-dbg-macro', count: 0, max: 0 },
+			{ ruleId: 'no-unwrap-in-lib', count: 3, max: 2 },
+		])
+	})
+})
+
+describe('failsRun', () => {
+	it('should pass while budgeted rules stay within their max', () => {
+		const budgets = [{ ruleId: 'no-unwrap-in-lib', count: 3, max: 3 }]
+
+		expect(failsRun(UNWRAPS, budgets)).toBe(false)
+		expect(failsRun([...UNWRAPS, finding('no-dbg-macro')], budgets)).toBe(true)
+	})
+
+	it('should fail when a rule goes over its budget, whatever the severity', () => {
+		const warnings = [finding('no-clone-in-loop', 'warning')]
+
+		expect(failsRun(warnings, [{ ruleId: 'no-clone-in-loop', count: 1, max: 0 }])).toBe(true)
+		expect(failsRun(warnings, [])).toBe(false)
+	})
+})
+
+describe('formatBudget', () => {
+	it('should show the count against the budget', () => {
+		expect(formatBudget({ ruleId: 'no-unwrap-in-lib', count: 130, max: 120 })).toBe(
+			'no-unwrap-in-lib: 130 findings, over the budget of 120',
+		)
+		expect(formatBudget({ ruleId: 'no-dbg-macro', count: 1, max: 1 })).toBe(
+			'no-dbg-macro: 1 finding, within the budget of 1',
+		)
+	})
+})
@@ -0,0 +1,45 @@
+import type { LintConfig } from './config.ts'
+import { countBySeverity, type LintIssue, pluralize } from './shared.ts'
+
+// How a rule with a `max` setting did in one run
+export interface BudgetResult {
+	ruleId: string
+	count: number
+	max: number
+}
+
+/**
+ * Count the findings of every rule with a `max` budget, sorted by rule id. Findings of any
+ * severity count, so a budget also limits warnings.
+ */
+export function checkBudgets(issues: LintIssue[], config: LintConfig): BudgetResult[] {
+	return Object.entries(config.ruleOptions ?? {})
+		.filter(([, options]) => options.max !== undefined)
+		.map(([ruleId, options]) => ({
+			ruleId,
+			count: issues.filter((issue) => issue.ruleId === ruleId).length,
+			max: options.max!,
+		}))
+		.sort((a, b) => (a.ruleId < b.ruleId ? -1 : a.ruleId > b.ruleId ? 1 : 0))
+}
+
+export function isOverBudget(result: BudgetResult): boolean {
+	return result.count > result.max
+}
+
+/**
+ * Whether the run fails: a rule went over its budget, or a rule without one reported an
+ * error. A rule's error-severity findings don't fail the run while it stays within budget.
+ */
+export function failsRun(issues: LintIssue[], budgets: BudgetResult[]): boolean {
+	const budgeted = new Set(budgets.map((result) => result.ruleId))
+	const unbudgeted = issues.filter((issue) => !budgeted.has(issue.ruleId))
+	return budgets.some(isOverBudget) || countBySeverity(unbudgeted).error > 0
+}
+
+// e.g. `no-unwrap-in-lib: 130 findings, over the budget of 120`
+export function formatBudget(result: BudgetResult): string {
+	const verdict = isOverBudget(result) ? 'over' : 'within'
+	const findings = `${result.count} ${pluralize(result.count, 'finding')}`
+	return `${result.ruleId}: ${findings}, ${verdict} the budget of ${result.max}`
+}
@@ -0,0 +1,123 @@
+import fs from 'node:fs'
+import os from 'node:os'
+import path from 'node:path'
+import { afterEach, beforeEach, describe, expect, it } from 'vitest'
+import {
+	computeRuleSetHash,
+	hashContent,
+	loadCache,
+	partitionByCache,
+	saveCache,
+	updateCache,
+} from './cache.ts'
+import type { LintIssue } from './shared.ts'
+
+const issue: LintIssue = {
+	ruleId: 'no-dbg-macro',
+	severity: 'error',
+	message: 'Remove dbg!()',
+	file: 'src/lib.rs',
+	line: 1,
+	column: 1,
+}
+
+describe('computeRuleSetHash', () => {
+	let tmpDir: string
+
+	beforeEach(() => {
+		tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-cache-'))
+		fs.writeFileSync(path.join(tmpDir, 'no-dbg-macro.yml'), 'id: no-dbg-macro\n')
+	})
+
+	afterEach(() => {
+		fs.rmSync(tmpDir, { recursive: true, force: true })
+	})
+
+	it('should be stable for the same rules and config', () => {
+		expect(computeRuleSetHash([tmpDir], { rules: {} }, '1.0.0')).toBe(
+			computeRuleSetHash([tmpDir], { rules: {} }, '1.0.0'),
+		)
+	})
+
+	it('should change with rule contents, config and version', () => {
+		const base = computeRuleSetHash([tmpDir], { rules: {} }, '1.0.0')
+
+		expect(computeRuleSetHash([tmpDir], { rules: { 'no-dbg-macro': 'off' } }, '1.0.0')).not.toBe(
+			base,
+		)
+		expect(computeRuleSetHash([tmpDir], { rules: {} }, '1.0.1')).not.toBe(base)
+
+		fs.writeFileSync(path.join(tmpDir, 'no-dbg-macro.yml'), 'id: no-dbg-macro\nseverity: hint\n')
+		expect(computeRuleSetHash([tmpDir], { rules: {} }, '1.0.0')).not.toBe(base)
+	})
+
+	it('should hash rewritten rule files instead of the ones on disk', () => {
+		const base = computeRuleSetHash([tmpDir], { rules: {} }, '1.0.0')
+		const rewritten = new Map([[path.join(tmpDir, 'no-dbg-macro.yml'), 'id: no-dbg-macro\n']])
+
+		expect(computeRuleSetHash([tmpDir], { rules: {} }, '1.0.0', rewritten)).toBe(base)
+		rewritten.set(path.join(tmpDir, 'no-dbg-macro.yml'), 'id: no-dbg-macro\nseverity: hint\n')
+		expect(computeRuleSetHash([tmpDir], { rules: {} }, '1.0.0', rewritten)).not.toBe(base)
+	})
+})
+
+describe('lint cache', () => {
+	let tmpDir: string
+	let cachePath: string
+
+	beforeEach(() => {
+		tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-cache-'))
+		cachePath = path.join(tmpDir, 'target', 'tempo-lints-cache', 'rust.json')
+	})
+
+	afterEach(() => {
+		fs.rmSync(tmpDir, { recursive: true, force: true })
+	})
+
+	it('should start empty without a cache file', () => {
+		const cache = loadCache(cachePath, 'rules')
+		const hashes = new Map([['src/lib.rs', hashContent('fn main() {}')]])
+
+		expect(partitionByCache(cache, hashes)).toEqual({ cached: [], stale: ['src/lib.rs'] })
+	})
+
+	it('should serve unchanged files from the cache', () => {
+		const hashes = new Map([
+			['src/lib.rs', hashContent('dbg!(x)')],
+			['src/main.rs', hashContent('fn main() {}')],
+		])
+		const cache = loadCache(cachePath, 'rules')
+		updateCache(cache, hashes, ['src/lib.rs', 'src/main.rs'], [issue])
+		saveCache(cache)
+
+		const changed = new Map([...hashes, ['src/main.rs', hashContent('fn main() { dbg!(1) }')]])
+		expect(partitionByCache(loadCache(cachePath, 'rules'), changed)).toEqual({
+			cached: [issue],
+			stale: ['src/main.rs'],
+		})
+	})
+
+	it('should discard the cache when the rule set changes', () => {
+		const hashes = new Map([['src/lib.rs', hashContent('dbg!(x)')]])
+		const cache = loadCache(cachePath, 'rules')
+		updateCache(cache, hashes, ['src/lib.rs'], [issue])
+		saveCache(cache)
+
+		expect(loadCache(cachePath, 'other-rules').entries).toEqual({})
+	})
+
+	it('should drop entries for files that are no longer scanned', () => {
+		const cache = loadCache(cachePath, 'rules')
+		updateCache(cache, new Map([['src/lib.rs', 'a']]), ['src/lib.rs'], [])
+		updateCache(cache, new Map([['src/main.rs', 'b']]), ['src/main.rs'], [])
+
+		expect(Object.keys(cache.entries)).toEqual(['src/main.rs'])
+	})
+
+	it('should ignore a corrupt cache file', () => {
+		fs.mkdirSync(path.dirname(cachePath), { recursive: true })
+		fs.writeFileSync(cachePath, '{ not json')
+
+		expect(loadCache(cachePath, 'rules').entries).toEqual({})
+	})
+})
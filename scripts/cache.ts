@@ -0,0 +1,151 @@
+import crypto from 'node:crypto'
+import fs from 'node:fs'
+import path from 'node:path'
+import type { LintIssue } from './shared.ts'
+
+// Relative to the repository root
+export const CACHE_DIR = path.join('target', 'tempo-lints-cache')
+
+// Bump when the cache file layout or the meaning of cached issues changes
+const CACHE_VERSION = 4
+
+export interface CacheEntry {
+	// Hash of the file contents the issues were computed for
+	hash: string
+	issues: LintIssue[]
+}
+
+export interface LintCache {
+	path: string
+	// Hash of everything besides file contents that affects results (rules, config, version)
+	ruleSetHash: string
+	entries: Record<string, CacheEntry>
+}
+
+interface CacheFile {
+	version: number
+	ruleSetHash: string
+	entries: Record<string, CacheEntry>
+}
+
+export function hashContent(content: string | Buffer): string {
+	return crypto.createHash('sha256').update(content).digest('hex')
+}
+
+/**
+ * Hash the active rule set: the contents of every rule file as scanned (rewritten ones from
+ * `ruleOverrides`), the effective config and the tool version. Any change produces a new
+ * hash, which invalidates the whole cache.
+ */
+export function computeRuleSetHash(
+	ruleDirs: string[],
+	config: unknown,
+	toolVersion: string,
+	ruleOverrides: Map<string, string> = new Map(),
+): string {
+	const hash = crypto.createHash('sha256')
+	hash.update(`version:${CACHE_VERSION}:${toolVersion}\n`)
+	hash.update(`config:${JSON.stringify(config)}\n`)
+
+	for (const dir of ruleDirs) {
+		if (!fs.existsSync(dir)) continue
+		for (const file of fs.readdirSync(dir).sort()) {
+			if (!file.endsWith('.yml')) continue
+			hash.update(`rule:${file}\n`)
+			const rulePath = path.join(dir, file)
+			hash.update(ruleOverrides.get(rulePath) ?? fs.readFileSync(rulePath))
+		}
+	}
+
+	return hash.digest('hex')
+}
+
+/**
+ * Load the cache at `cachePath`. A missing or corrupt cache, or one written for a
+ * different rule set, starts out empty.
+ */
+export function loadCache(cachePath: string, ruleSetHash: string): LintCache {
+	const cache: LintCache = { path: cachePath, ruleSetHash, entries: {} }
+
+	let data: Partial<CacheFile>
+	try {
+		data = JSON.parse(fs.readFileSync(cachePath, 'utf8')) as Partial<CacheFile>
+	} catch {
+		return cache
+	}
+
+	if (data.version === CACHE_VERSION && data.ruleSetHash === ruleSetHash && data.entries) {
+		cache.entries = data.entries
+	}
+	return cache
+}
+
+/**
+ * Split files into those with up-to-date cached issues and those that need scanning.
+ */
+export function partitionByCache(
+	cache: LintCache,
+	hashes: Map<string, string>,
+): { cached: LintIssue[]; stale: string[] } {
+	const cached: LintIssue[] = []
+	const stale: string[] = []
+
+	for (const [file, hash] of hashes) {
+		const entry = cache.entries[file]
+		if (entry && entry.hash === hash) {
+			cached.push(...entry.issues)
+		} else {
+			stale.push(file)
+		}
+	}
+
+	return { cached, stale }
+}
+
+/**
+ * Record fresh results for `scannedFiles` and drop entries for files that are no longer
+ * part of the scan. Issues for a file that isn't in `hashes` are not cached.
+ */
+export function updateCache(
+	cache: LintCache,
+	hashes: Map<string, string>,
+	scannedFiles: string[],
+	issues: LintIssue[],
+): void {
+	const byFile = new Map<string, LintIssue[]>()
+	for (const issue of issues) {
+		const list = byFile.get(issue.file) ?? []
+		list.push(issue)
+		byFile.set(issue.file, list)
+	}
+
+	for (const file of scannedFiles) {
+		const hash = hashes.get(file)
+		if (hash !== undefined) {
+			cache.entries[file] = { hash, issues: byFile.get(file) ?? [] }
+		}
+	}
+
+	for (const file of Object.keys(cache.entries)) {
+		if (!hashes.has(file)) {
+			delete cache.entries[file]
+		}
+	}
+}
+
+/**
+ * Write the cache to disk. The file is replaced atomically so a concurrent run never
+ * reads a partial cache.
+ */
+export function saveCache(cache: LintCache): void {
+	const data: CacheFile = {
+		version: CACHE_VERSION,
+		ruleSetHash: cache.ruleSetHash,
+		entries: cache.entries,
+	}
+
+	fs.mkdirSync(path.dirname(cache.path), { recursive: true })
+	const tmpPath = `${cache.path}.${process.pid}.tmp`
+	fs.writeFileSync(tmpPath, JSON.stringify(data))
+	fs.renameSync(tmpPath, cache.path)
+}
@@ -0,0 +1,219 @@
+import fs from 'node:fs'
+import os from 'node:os'
+import path from 'node:path'
+import { afterEach, beforeEach, describe, expect, it } from 'vitest'
+import {
+	applyCrateTargets,
+	classifyTargetFiles,
+	findCrateTargets,
+	findModuleFiles,
+	findWorkspaceCrates,
+} from './cargo.ts'
+import { compileIgnorePattern, type IgnorePattern } from './globs.ts'
+import type { LintIssue } from './shared.ts'
+import { parseToml } from './toml.ts'
+
+let tmpDir: string
+
+function write(file: string, content = ''): string {
+	const full = path.join(tmpDir, file)
+	fs.mkdirSync(path.dirname(full), { recursive: true })
+	fs.writeFileSync(full, content)
+	return full
+}
+
+beforeEach(() => {
+	tmpDir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-cargo-')))
+})
+
+afterEach(() => {
+	fs.rmSync(tmpDir, { recursive: true, force: true })
+})
+
+describe('findWorkspaceCrates', () => {
+	it('should read the root package and the workspace members', () => {
+		write('Cargo.toml', '[package]\nname = "root"\n\n[workspace]\nmembers = ["crates/*"]\n')
+		write('crates/net/Cargo.toml', '[package]\nname = "net"\n')
+		write('crates/node/Cargo.toml', '[package]\nname = "node"\n')
+		// Globs may match directories that aren't crates
+		write('crates/docs/README.md')
+
+		expect(findWorkspaceCrates(tmpDir).map((crate) => [crate.name, crate.dir])).toEqual([
+			['root', tmpDir],
+			['net', path.join(tmpDir, 'crates/net')],
+			['node', path.join(tmpDir, 'crates/node')],
+		])
+	})
+
+	it('should skip excluded members', () => {
+		write('Cargo.toml', '[workspace]\nmembers = ["crates/*"]\nexclude = ["crates/fuzz"]\n')
+		write('crates/net/Cargo.toml', '[package]\nname = "net"\n')
+		write('crates/fuzz/Cargo.toml', '[package]\nname = "fuzz"\n')
+
+		expect(findWorkspaceCrates(tmpDir).map((crate) => crate.name)).toEqual(['net'])
+	})
+
+	it('should name the manifest that cannot be read', () => {
+		write('Cargo.toml', '[workspace]\nmembers = ["missing"]\n')
+
+		expect(() => findWorkspaceCrates(tmpDir)).toThrow(
+			`Could not read ${path.join(tmpDir, 'missing', 'Cargo.toml')}`,
+		)
+		write('Cargo.toml', '[package\n')
+		expect(() => findWorkspaceCrates(tmpDir)).toThrow(`${path.join(tmpDir, 'Cargo.toml')}: line 1:`)
+	})
+})
+
+describe('findCrateTargets', () => {
+	it('should find the default library, binaries, examples, tests and build script', () => {
+		for (const file of [
+			'src/lib.rs',
+			'src/main.rs',
+			'src/bin/tool.rs',
+			'src/bin/daemon/main.rs',
+			'examples/demo.rs',
+			'tests/api.rs',
+			'benches/parse.rs',
+			'build.rs',
+		]) {
+			write(file)
+		}
+
+		const targets = findCrateTargets(tmpDir, 'node', parseToml('[package]\nname = "node"\n'))
+
+		expect(targets.map((target) => [target.kind, path.relative(tmpDir, target.root)])).toEqual([
+			['lib', path.join('src', 'lib.rs')],
+			['bin', path.join('src', 'main.rs')],
+			['bin', path.join('src', 'bin', 'daemon', 'main.rs')],
+			['bin', path.join('src', 'bin', 'tool.rs')],
+			['example', path.join('examples', 'demo.rs')],
+			['test', path.join('tests', 'api.rs')],
+			['bench', path.join('benches', 'parse.rs')],
+			['build', 'build.rs'],
+		])
+	})
+
+	it('should use the paths from target tables and respect auto-discovery flags', () => {
+		write('lib/core.rs')
+		write('src/cli.rs')
+		write('src/bin/unused.rs')
+		write('codegen.rs')
+		const manifest = parseToml(
+			[
+				'[package]',
+				'name = "node"',
+				'autobins = false',
+				'build = "codegen.rs"',
+				'[lib]',
+				'path = "lib/core.rs"',
+				'[[bin]]',
+				'name = "node"',
+				'path = "src/cli.rs"',
+				'',
+			].join('\n'),
+		)
+
+		expect(findCrateTargets(tmpDir, 'node', manifest)).toEqual([
+			{ kind: 'lib', root: path.join(tmpDir, 'lib/core.rs') },
+			{ kind: 'bin', root: path.join(tmpDir, 'src/cli.rs') },
+			{ kind: 'build', root: path.join(tmpDir, 'codegen.rs') },
+		])
+	})
+})
+
+describe('findModuleFiles', () => {
+	it('should follow mod declarations like rustc', () => {
+		const root = write('src/lib.rs', 'pub mod net;\nmod util;\n// mod commented;\n')
+		write('src/net.rs', 'pub(crate) mod peer;\n')
+		write('src/net/peer.rs')
+		write('src/util/mod.rs', 'mod fmt;\n')
+		write('src/util/fmt.rs')
+		write('src/commented.rs')
+
+		expect([...findModuleFiles(root).keys()].map((file) => path.relative(tmpDir, file))).toEqual([
+			path.join('src', 'lib.rs'),
+			path.join('src', 'net.rs'),
+			path.join('src', 'net', 'peer.rs'),
+			path.join('src', 'util', 'mod.rs'),
+			path.join('src', 'util', 'fmt.rs'),
+		])
+	})
+
+	it('should mark modules declared under cfg(test) and follow path attributes', () => {
+		const root = write(
+			'src/lib.rs',
+			'#[cfg(test)]\n// Shared fixtures\nmod tests;\n#[path = "gen/api.rs"] mod api;\n',
+		)
+		write('src/tests.rs', 'mod helpers;\n')
+		write('src/tests/helpers.rs')
+		write('src/gen/api.rs')
+
+		expect(findModuleFiles(root)).toEqual(
+			new Map([
+				[root, false],
+				[path.join(tmpDir, 'src/tests.rs'), true],
+				[path.join(tmpDir, 'src/tests/helpers.rs'), true],
+				[path.join(tmpDir, 'src/gen/api.rs'), false],
+			]),
+		)
+	})
+})
+
+describe('classifyTargetFiles', () => {
+	it('should give shared modules to the library and the rest to their target', () => {
+		const lib = write('src/lib.rs', 'mod cli;\n')
+		const bin = write('src/main.rs', 'mod cli;\nmod args;\n')
+		write('src/cli.rs')
+		write('src/args.rs')
+		const crate = {
+			name: 'node',
+			dir: tmpDir,
+			targets: [
+				{ kind: 'lib' as const, root: lib },
+				{ kind: 'bin' as const, root: bin },
+			],
+		}
+
+		expect(classifyTargetFiles([crate])).toEqual(
+			new Map([
+				[lib, 'lib'],
+				[path.join(tmpDir, 'src/cli.rs'), 'lib'],
+				[bin, 'bin'],
+				[path.join(tmpDir, 'src/args.rs'), 'bin'],
+			]),
+		)
+	})
+})
+
+describe('applyCrateTargets', () => {
+	const issue = (file: string, ruleId = 'no-unwrap-in-lib'): LintIssue => ({
+		ruleId,
+		severity: 'warning',
+		message: 'Avoid .unwrap() in library code.',
+		file,
+		line: 1,
+		column: 1,
+	})
+	const fallback = new Map([
+		[
+			'no-unwrap-in-lib',
+			['**/bin/**', '**/cli/**'].map((glob) => compileIgnorePattern(glob) as IgnorePattern),
+		],
+	])
+
+	it('should only keep library-scoped findings in library code', () => {
+		const kinds = new Map([
+			[path.join(tmpDir, 'src/cli/mod.rs'), 'lib' as const],
+			[path.join(tmpDir, 'src/tool.rs'), 'bin' as const],
+		])
+		const issues = [issue('src/cli/mod.rs'), issue('src/tool.rs'), issue('src/tool.rs', 'no-dbg')]
+
+		expect(applyCrateTargets(issues, kinds, fallback, tmpDir)).toEqual([issues[0], issues[2]])
+	})
+
+	it('should judge files no target reaches by the rule globs', () => {
+		const issues = [issue('src/bin/orphan.rs'), issue('src/orphan.rs')]
+
+		expect(applyCrateTargets(issues, new Map(), fallback, tmpDir)).toEqual([issues[1]])
+	})
+})
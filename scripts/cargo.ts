@@ -0,0 +1,287 @@
+import fs from 'node:fs'
+import path from 'node:path'
+import { globToRegExpSource, type IgnorePattern } from './globs.ts'
+import { isCoveredPath } from './rule-options.ts'
+import type { LintIssue } from './shared.ts'
+import { isTable, parseToml, type TomlTable, type TomlValue } from './toml.ts'
+
+export const CARGO_MANIFEST = 'Cargo.toml'
+
+// How Cargo compiles a file, from the target whose module tree contains it
+export type TargetKind = 'lib' | 'bin' | 'example' | 'test' | 'bench' | 'build'
+
+export interface CargoTarget {
+	kind: TargetKind
+	// Absolute path of the target's crate root, e.g. `src/lib.rs`
+	root: string
+}
+
+export interface CargoCrate {
+	name: string
+	// Absolute directory containing the crate's manifest
+	dir: string
+	targets: CargoTarget[]
+}
+
+// Target tables besides `[lib]`, with the directory Cargo discovers them in and the
+// `[package]` flag that turns discovery off
+const TARGET_TABLES = [
+	{ kind: 'bin', table: 'bin', dir: path.join('src', 'bin'), auto: 'autobins' },
+	{ kind: 'example', table: 'example', dir: 'examples', auto: 'autoexamples' },
+	{ kind: 'test', table: 'test', dir: 'tests', auto: 'autotests' },
+	{ kind: 'bench', table: 'bench', dir: 'benches', auto: 'autobenches' },
+] as const
+
+// `mod name;` with any attributes written on the same line
+const MOD_DECLARATION = /^\s*((?:#\[.*?\]\s*)*)(?:pub(?:\([^)]*\))?\s+)?mod\s+(?:r#)?(\w+)\s*;/
+const ATTRIBUTE_LINE = /^\s*#\[.*\]\s*$/
+const PATH_ATTRIBUTE = /#\[path\s*=\s*"([^"]+)"\s*\]/
+const CFG_TEST_ATTRIBUTE = /#\[cfg\(test\)\]/
+
+/**
+ * Read the crates of the Cargo workspace whose manifest is in `root`: the root package, if
+ * there is one, and every `[workspace] members` entry that isn't excluded. Members may use
+ * `*` globs. Throws with a message naming the manifest when it can't be read.
+ */
+export function findWorkspaceCrates(root: string): CargoCrate[] {
+	const rootDir = path.resolve(root)
+	const manifest = readManifest(rootDir)
+	const crates: CargoCrate[] = []
+	if (isTable(manifest.package)) {
+		crates.push(readCrate(rootDir, manifest))
+	}
+
+	const workspace = isTable(manifest.workspace) ? manifest.workspace : {}
+	const excluded = stringList(workspace.exclude).map((entry) => path.resolve(rootDir, entry))
+	for (const member of stringList(workspace.members)) {
+		const isGlob = /[*?[]/.test(member)
+		for (const dir of expandMemberGlob(rootDir, member)) {
+			if (excluded.includes(dir) || crates.some((crate) => crate.dir === dir)) continue
+			// Like Cargo, a glob may match directories that aren't crates
+			if (isGlob && !fs.existsSync(path.join(dir, CARGO_MANIFEST))) continue
+			crates.push(readCrate(dir, readManifest(dir)))
+		}
+	}
+
+	if (crates.length === 0) {
+		throw new Error(`${path.join(rootDir, CARGO_MANIFEST)} has no [package] or workspace members`)
+	}
+	return crates
+}
+
+function readManifest(dir: string): TomlTable {
+	const file = path.join(dir, CARGO_MANIFEST)
+	let content: string
+	try {
+		content = fs.readFileSync(file, 'utf8')
+	} catch (err) {
+		throw new Error(`Could not read ${file}: ${(err as Error).message}`)
+	}
+	try {
+		return parseToml(content)
+	} catch (err) {
+		throw new Error(`${file}: ${(err as Error).message}`)
+	}
+}
+
+function readCrate(dir: string, manifest: TomlTable): CargoCrate {
+	const pkg = isTable(manifest.package) ? manifest.package : {}
+	const name = typeof pkg.name === 'string' ? pkg.name : path.basename(dir)
+	return { name, dir, targets: findCrateTargets(dir, name, manifest) }
+}
+
+function stringList(value: TomlValue | undefined): string[] {
+	return Array.isArray(value)
+		? value.filter((entry): entry is string => typeof entry === 'string')
+		: []
+}
+
+// Directories matching a member path, where each segment may be a glob
+function expandMemberGlob(rootDir: string, member: string): string[] {
+	let dirs = [rootDir]
+	for (const segment of member.split('/').filter((part) => part !== '' && part !== '.')) {
+		if (!/[*?[]/.test(segment)) {
+			dirs = dirs.map((dir) => path.join(dir, segment))
+			continue
+		}
+		const pattern = new RegExp(`^${globToRegExpSource(segment)}$`)
+		dirs = dirs.flatMap((dir) => {
+			if (!fs.existsSync(dir)) return []
+			return fs
+				.readdirSync(dir, { withFileTypes: true })
+				.filter((entry) => entry.isDirectory() && pattern.test(entry.name))
+				.map((entry) => path.join(dir, entry.name))
+				.sort()
+		})
+	}
+	return dirs
+}
+
+/**
+ * List a crate's targets the way Cargo does: `[lib]`, `[[bin]]`, `[[example]]`, `[[test]]` and
+ * `[[bench]]` tables, the targets Cargo discovers by path (`src/main.rs`, `src/bin/`,
+ * `examples/`, `tests/`, `benches/`) unless `autobins` and the like turn that off, and the
+ * build script. Targets whose root file doesn't exist are left out.
+ */
+export function findCrateTargets(dir: string, name: string, manifest: TomlTable): CargoTarget[] {
+	const pkg = isTable(manifest.package) ? manifest.package : {}
+	const targets: CargoTarget[] = []
+	const addFirst = (kind: TargetKind, candidates: string[]) => {
+		const root = candidates
+			.map((candidate) => path.resolve(dir, candidate))
+			.find((candidate) => fs.existsSync(candidate))
+		if (root !== undefined && !targets.some((target) => target.root === root)) {
+			targets.push({ kind, root })
+		}
+	}
+
+	const lib = isTable(manifest.lib) ? manifest.lib : {}
+	addFirst('lib', [typeof lib.path === 'string' ? lib.path : path.join('src', 'lib.rs')])
+
+	for (const spec of TARGET_TABLES) {
+		const declared = Array.isArray(manifest[spec.table]) ? manifest[spec.table] : []
+		for (const target of (declared as TomlValue[]).filter(isTable)) {
+			if (typeof target.path === 'string') {
+				addFirst(spec.kind, [target.path])
+			} else if (typeof target.name === 'string') {
+				const isMainBin = spec.kind === 'bin' && target.name === name
+				const main = isMainBin ? [path.join('src', 'main.rs')] : []
+				addFirst(spec.kind, [
+					path.join(spec.dir, `${target.name}.rs`),
+					path.join(spec.dir, target.name, 'main.rs'),
+					...main,
+				])
+			}
+		}
+
+		if (pkg[spec.auto] === false) continue
+		if (spec.kind === 'bin') {
+			addFirst('bin', [path.join('src', 'main.rs')])
+		}
+		for (const root of discoverTargetRoots(path.join(dir, spec.dir))) {
+			addFirst(spec.kind, [root])
+		}
+	}
+
+	if (typeof pkg.build === 'string') {
+		addFirst('build', [pkg.build])
+	} else if (pkg.build !== false) {
+		addFirst('build', ['build.rs'])
+	}
+	return targets
+}
+
+// `<dir>/*.rs` and `<dir>/*/main.rs`, which Cargo turns into targets without a table
+function discoverTargetRoots(dir: string): string[] {
+	if (!fs.existsSync(dir)) return []
+	return fs
+		.readdirSync(dir, { withFileTypes: true })
+		.flatMap((entry) => {
+			if (entry.isFile() && entry.name.endsWith('.rs')) return [path.join(dir, entry.name)]
+			const main = path.join(dir, entry.name, 'main.rs')
+			return entry.isDirectory() && fs.existsSync(main) ? [main] : []
+		})
+		.sort()
+}
+
+/**
+ * The files in the module tree of a crate root, found by following `mod name;` declarations
+ * and `#[path]` attributes the way rustc does. Each maps to whether the file is only compiled
+ * for tests, i.e. it's declared under `#[cfg(test)]`. Declarations inside inline
+ * `mod name { ... }` blocks aren't followed.
+ */
+export function findModuleFiles(root: string): Map<string, boolean> {
+	const files = new Map<string, boolean>()
+
+	const visit = (file: string, isModRoot: boolean, test: boolean) => {
+		if (files.has(file)) return
+		files.set(file, test)
+
+		let source: string
+		try {
+			source = fs.readFileSync(file, 'utf8')
+		} catch {
+			return
+		}
+		const dir = path.dirname(file)
+		// Children of `foo.rs` live in `foo/`; children of crate roots and `mod.rs` next to them
+		const childDir = isModRoot ? dir : path.join(dir, path.basename(file, '.rs'))
+
+		let attributes = ''
+		for (const line of source.split('\n')) {
+			if (ATTRIBUTE_LINE.test(line)) {
+				attributes += line
+				continue
+			}
+			const declaration = MOD_DECLARATION.exec(line)
+			if (declaration) {
+				const allAttributes = attributes + declaration[1]
+				const childTest = test || CFG_TEST_ATTRIBUTE.test(allAttributes)
+				const pathAttribute = PATH_ATTRIBUTE.exec(allAttributes)?.[1]
+				if (pathAttribute !== undefined) {
+					// A file loaded through `#[path]` declares its children like a `mod.rs`
+					visit(path.resolve(dir, pathAttribute), true, childTest)
+				} else {
+					const name = declaration[2]!
+					const child = [path.join(childDir, `${name}.rs`), path.join(childDir, name, 'mod.rs')]
+					const found = child.find((candidate) => fs.existsSync(candidate))
+					if (found !== undefined) {
+						visit(found, path.basename(found) === 'mod.rs', childTest)
+					}
+				}
+			}
+			// Comments and blank lines may sit between an attribute and its item
+			const trimmed = line.trim()
+			if (trimmed !== '' && !trimmed.startsWith('//')) {
+				attributes = ''
+			}
+		}
+	}
+
+	visit(path.resolve(root), true, false)
+	return files
+}
+
+/**
+ * Map every file reached from the crates' targets to the kind of target that compiles it.
+ * A file reached from several targets belongs to the first, and the library comes first, so
+ * modules a binary shares with the library are library code. Files only compiled for tests
+ * are 'test' whichever target declares them.
+ */
+export function classifyTargetFiles(crates: CargoCrate[]): Map<string, TargetKind> {
+	const kinds = new Map<string, TargetKind>()
+	for (const crate of crates) {
+		for (const target of crate.targets) {
+			for (const [file, test] of findModuleFiles(target.root)) {
+				if (!kinds.has(file)) {
+					kinds.set(file, test ? 'test' : target.kind)
+				}
+			}
+		}
+	}
+	return kinds
+}
+
+/**
+ * Drop findings of library-scoped rules outside library code. A file compiled by one of the
+ * targets is library code only if that target is the library. Files no target reaches fall
+ * back to the rule's own path globs (`fallback`, keyed by rule id), which are resolved against
+ * `cwd`.
+ */
+export function applyCrateTargets(
+	issues: LintIssue[],
+	kinds: Map<string, TargetKind>,
+	fallback: Map<string, IgnorePattern[]>,
+	cwd: string = process.cwd(),
+): LintIssue[] {
+	return issues.filter((issue) => {
+		const patterns = fallback.get(issue.ruleId)
+		if (!patterns) return true
+
+		const file = path.resolve(cwd, issue.file)
+		const kind = kinds.get(file)
+		if (kind !== undefined) return kind === 'lib'
+		const relative = path.relative(cwd, file).split(path.sep).join('/')
+		return !isCoveredPath(patterns, relative)
+	})
+}
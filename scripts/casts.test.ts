@@ -0,0 +1,151 @@
+import { describe, expect, it } from 'vitest'
+import { applyAsCasts, classifyCast, isLosslessCast, writtenSourceType } from './casts.ts'
+import type { LintIssue } from './shared.ts'
+
+// A finding for the cast `code` in the function `source`, with the captures the YAML rule sets
+function cast(source: string, code: string): LintIssue {
+	const lines = source.split('\n')
+	const line = lines.findIndex((text) => text.includes(code))
+	const [value, target] = code.split(/ as (?!.* as )/)
+	return {
+		ruleId: 'no-as-cast',
+		severity: 'warning',
+		message: '`as` cast can silently truncate; use `TryFrom` instead.',
+		file: 'src/lib.rs',
+		line: line + 1,
+		column: lines[line]!.indexOf(code) + 1,
+		code,
+		captures: {
+			VALUE: { text: value!, line: line + 1, column: lines[line]!.indexOf(code) + 1 },
+			TARGET: { text: target!, line: line + 1, column: lines[line]!.indexOf(code) + 1 },
+			FUNCTION: { text: source, line: 1, column: 1 },
+		},
+	}
+}
+
+const ENCODE = [
+	'fn encode(len: u64, tag: u8, ptr: *const u8) -> u32 {',
+	'    let small: u16 = 7;',
+	'    let total = len as u32 + tag as u32 + small as u32;',
+	'    ptr as usize as u32',
+	'}',
+].join('\n')
+
+describe('isLosslessCast', () => {
+	it('should allow casts std converts with From', () => {
+		expect(isLosslessCast('u8', 'u32')).toBe(true)
+		expect(isLosslessCast('u16', 'i64')).toBe(true)
+		expect(isLosslessCast('i8', 'isize')).toBe(true)
+		expect(isLosslessCast('u16', 'usize')).toBe(true)
+	})
+
+	it('should not allow casts that may truncate or change the sign', () => {
+		expect(isLosslessCast('u64', 'u32')).toBe(false)
+		expect(isLosslessCast('i8', 'u64')).toBe(false)
+		expect(isLosslessCast('u32', 'i32')).toBe(false)
+		expect(isLosslessCast('u32', 'usize')).toBe(false)
+		expect(isLosslessCast('usize', 'u128')).toBe(false)
+		expect(isLosslessCast('f32', 'f64')).toBe(false)
+	})
+})
+
+describe('writtenSourceType', () => {
+	it('should read literal suffixes and earlier casts', () => {
+		expect(writtenSourceType('300u16', null)).toBe('u16')
+		expect(writtenSourceType('0xffu8', null)).toBe('u8')
+		expect(writtenSourceType('1.5f32', null)).toBe('f32')
+		expect(writtenSourceType('(x as u8)', null)).toBe('u8')
+		expect(writtenSourceType('p as *const  u8', null)).toBe('*const u8')
+	})
+
+	it('should look names up in lets and parameters before the cast', () => {
+		const scope = { text: ENCODE, offset: ENCODE.indexOf('len as u32') }
+
+		expect(writtenSourceType('len', scope)).toBe('u64')
+		expect(writtenSourceType('small', scope)).toBe('u16')
+		expect(writtenSourceType('total', scope)).toBeNull()
+	})
+
+	it('should skip the let the cast initializes', () => {
+		const source = 'fn f(x: u64) {\n    let x: u32 = x as u32;\n}'
+		const scope = { text: source, offset: source.indexOf('x as u32') }
+
+		expect(writtenSourceType('x', scope)).toBe('u64')
+	})
+
+	it('should not guess the type of other expressions', () => {
+		expect(writtenSourceType('self.len()', null)).toBeNull()
+		expect(writtenSourceType('len', null)).toBeNull()
+	})
+})
+
+describe('classifyCast', () => {
+	it('should sort casts by what they can lose', () => {
+		expect(classifyCast('u8', 'u32')).toBe('lossless')
+		expect(classifyCast('u64', 'u32')).toBe('lossy')
+		expect(classifyCast(null, 'u32')).toBe('lossy')
+		expect(classifyCast(null, '*const u8')).toBe('pointer')
+		expect(classifyCast('*mut T', 'usize')).toBe('pointer')
+	})
+})
+
+describe('applyAsCasts', () => {
+	it('should name both types and suggest try_from', () => {
+		const [issue] = applyAsCasts([cast(ENCODE, 'len as u32')], { rules: {} })
+
+		expect(issue?.message).toBe(
+			'`as` cast from `u64` to `u32` can silently truncate or wrap; use `u32::try_from` and ' +
+				'handle the error.',
+		)
+	})
+
+	it('should name the expression when its type is not written', () => {
+		const source = 'fn f(v: &[u8]) -> u32 {\n    v.len() as u32\n}'
+		const [issue] = applyAsCasts([cast(source, 'v.len() as u32')], { rules: {} })
+
+		expect(issue?.message).toContain('`as` cast of `v.len()` to `u32` can silently truncate')
+	})
+
+	it('should mention rounding for float casts', () => {
+		const source = 'fn f(ratio: f64) -> u8 {\n    ratio as u8\n}'
+		const [issue] = applyAsCasts([cast(source, 'ratio as u8')], { rules: {} })
+
+		expect(issue?.message).toContain('from `f64` to `u8` can silently round, saturate')
+	})
+
+	it('should drop widening casts unless allow-widening is off', () => {
+		const issues = [cast(ENCODE, 'tag as u32'), cast(ENCODE, 'small as u32')]
+		const config = { rules: {}, ruleOptions: { 'no-as-cast': { allowWidening: false } } }
+
+		expect(applyAsCasts(issues, { rules: {} })).toEqual([])
+		expect(applyAsCasts(issues, config).map((issue) => issue.message)).toEqual([
+			'`as` cast from `u8` to `u32` is lossless; use `u32::from`, which keeps it that way if ' +
+				'the types change.',
+			'`as` cast from `u16` to `u32` is lossless; use `u32::from`, which keeps it that way if ' +
+				'the types change.',
+		])
+	})
+
+	it('should only report pointer casts when pointer-casts is on', () => {
+		const issues = [cast(ENCODE, 'ptr as usize')]
+		const config = { rules: {}, ruleOptions: { 'no-as-cast': { pointerCasts: true } } }
+
+		expect(applyAsCasts(issues, { rules: {} })).toEqual([])
+		expect(applyAsCasts(issues, config)[0]?.message).toBe(
+			'Pointer cast from `*const u8` to `usize` with `as` can silently change the pointee type ' +
+				'or mutability; use `cast()`, `ptr::from_ref` or `addr()` instead.',
+		)
+	})
+
+	it('should read the source of a chained cast from the cast before it', () => {
+		const [issue] = applyAsCasts([cast(ENCODE, 'ptr as usize as u32')], { rules: {} })
+
+		expect(issue?.message).toContain('from `usize` to `u32`')
+	})
+
+	it('should leave other rules alone', () => {
+		const issue = { ...cast(ENCODE, 'len as u32'), ruleId: 'no-dbg-macro' }
+
+		expect(applyAsCasts([issue], { rules: {} })).toEqual([issue])
+	})
+})
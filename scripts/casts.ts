@@ -0,0 +1,200 @@
+import { AS_CAST_RULE_ID, type LintConfig } from './config.ts'
+import { maskCommentsAndLiterals } from './function-length.ts'
+import type { LintIssue } from './shared.ts'
+import { offsetWithin } from './wildcard-imports.ts'
+
+// Widths of the integer primitives; `usize` and `isize` are only known to be at least 16 bits
+const INTEGER_BITS: Record<string, number> = {
+	u8: 8,
+	u16: 16,
+	u32: 32,
+	u64: 64,
+	u128: 128,
+	usize: 16,
+	i8: 8,
+	i16: 16,
+	i32: 32,
+	i64: 64,
+	i128: 128,
+	isize: 16,
+}
+
+// Literals with a type suffix, e.g. `0xffu8` or `1.5f32`
+const SUFFIXED_INTEGER = /^-?(?:0x[\da-fA-F_]+|0o[0-7_]+|0b[01_]+|\d[\d_]*)([iu](?:8|16|32|64|128|size))$/
+const SUFFIXED_FLOAT = /^-?\d[\d_]*(?:\.\d[\d_]*)?(?:[eE][+-]?\d[\d_]*)?(f32|f64)$/
+// The type an expression ending in a cast is cast to, e.g. `u8` in `x as u8`
+const TRAILING_CAST = /\bas\s+(\*\s*(?:const|mut)\s+[^()]+|[\w:]+)$/
+const IDENTIFIER = /^(?:r#)?([A-Za-z_]\w*)$/
+
+export type CastKind = 'lossless' | 'lossy' | 'pointer'
+
+export function isIntegerType(type: string): boolean {
+	return Object.hasOwn(INTEGER_BITS, type)
+}
+
+function isPointerType(type: string): boolean {
+	return /^\*\s*(?:const|mut)\b/.test(type)
+}
+
+/**
+ * Whether every value of the integer type `from` fits in the integer type `to`, on any
+ * target. These are the casts `To::from` accepts, e.g. `u8` to `u32` or `u16` to `i64`, but
+ * not `u32` to `usize`.
+ */
+export function isLosslessCast(from: string, to: string): boolean {
+	if (!isIntegerType(from) || !isIntegerType(to)) return false
+	if (from === to) return true
+	// Nothing is known to be wide enough for every `usize` or `isize`
+	if (from === 'usize' || from === 'isize') return false
+	const fromSigned = from.startsWith('i')
+	const toSigned = to.startsWith('i')
+	if (fromSigned && !toSigned) return false
+	const fromBits = INTEGER_BITS[from]!
+	const toBits = INTEGER_BITS[to]!
+	return fromSigned === toSigned ? toBits >= fromBits : toBits > fromBits
+}
+
+// Where a cast sits: the text of the enclosing function and the cast's offset into it
+export interface CastScope {
+	text: string
+	offset: number
+}
+
+/**
+ * The source type of a cast where the code spells it out, or null: the suffix of a literal
+ * (`300u16`), the target of an earlier cast (`x as u8` in `x as u8 as u32`), or the annotation
+ * of a plain name in `scope`. A name is looked up in the nearest finished `let` before the cast,
+ * then in the function's parameters; which block a `let` is in isn't taken into account.
+ */
+export function writtenSourceType(value: string, scope: CastScope | null): string | null {
+	const expression = stripParens(value.trim())
+
+	const literal = SUFFIXED_INTEGER.exec(expression) ?? SUFFIXED_FLOAT.exec(expression)
+	if (literal) return literal[1]!
+	const cast = TRAILING_CAST.exec(expression)
+	if (cast) return normalizeType(cast[1]!)
+
+	const name = IDENTIFIER.exec(expression)?.[1]
+	if (name === undefined || scope === null) return null
+	return annotatedType(name, maskCommentsAndLiterals(scope.text), scope.offset)
+}
+
+// Remove parentheses wrapping the whole expression, like in `(x as u8) as u32`
+function stripParens(expression: string): string {
+	let current = expression
+	while (current.startsWith('(') && closingParen(current) === current.length - 1) {
+		current = current.slice(1, -1).trim()
+	}
+	return current
+}
+
+function closingParen(text: string): number {
+	let depth = 0
+	for (let i = 0; i < text.length; i++) {
+		if (text[i] === '(') depth++
+		else if (text[i] === ')' && --depth === 0) return i
+	}
+	return -1
+}
+
+function annotatedType(name: string, source: string, offset: number): string | null {
+	const before = source.slice(0, offset)
+	const binding = new RegExp(`\\blet\\s+(?:mut\\s+)?(?:r#)?${name}\\s*:`, 'g')
+	let type: string | null = null
+	for (const match of before.matchAll(binding)) {
+		// Skip the `let` the cast initializes, as in `let x: u32 = x as u32;`
+		if (!before.includes(';', match.index)) continue
+		type = readType(before, match.index + match[0].length)
+	}
+	if (type !== null) return type
+
+	// Parameters come before the body's opening brace
+	const body = source.indexOf('{')
+	const signature = body === -1 ? source : source.slice(0, body)
+	const parameter = new RegExp(`[(,]\\s*(?:mut\\s+)?(?:r#)?${name}\\s*:`).exec(signature)
+	return parameter ? readType(signature, parameter.index + parameter[0].length) : null
+}
+
+// The type written at `start`, up to the `=`, `;`, `,` or `)` ending it
+function readType(text: string, start: number): string | null {
+	let depth = 0
+	let end = start
+	for (; end < text.length; end++) {
+		const ch = text[end]!
+		if ('([<'.includes(ch)) {
+			depth++
+		} else if ((')]'.includes(ch) || (ch === '>' && text[end - 1] !== '-')) && depth > 0) {
+			depth--
+		} else if (depth === 0 && '=;,){'.includes(ch)) {
+			break
+		}
+	}
+	const type = normalizeType(text.slice(start, end))
+	return type === '' ? null : type
+}
+
+function normalizeType(type: string): string {
+	return type.trim().replace(/\s+/g, ' ')
+}
+
+/**
+ * Sort a cast from the written `source` type, or null when it isn't written, to `target`.
+ * Casts from one integer type to another holding all its values are lossless; casts to or from
+ * raw pointers are pointer casts; anything else may lose information.
+ */
+export function classifyCast(source: string | null, target: string): CastKind {
+	if (isPointerType(target) || (source !== null && isPointerType(source))) return 'pointer'
+	if (source !== null && isLosslessCast(source, target)) return 'lossless'
+	return 'lossy'
+}
+
+function describeCast(
+	kind: CastKind,
+	source: string | null,
+	value: string,
+	target: string,
+): string {
+	const from = source === null ? `of \`${value}\`` : `from \`${source}\``
+	if (kind === 'pointer') {
+		return `Pointer cast ${from} to \`${target}\` with \`as\` can silently change the pointee type or mutability; use \`cast()\`, \`ptr::from_ref\` or \`addr()\` instead.`
+	}
+	if (kind === 'lossless') {
+		return `\`as\` cast ${from} to \`${target}\` is lossless; use \`${target}::from\`, which keeps it that way if the types change.`
+	}
+	const integers = isIntegerType(target) && (source === null || isIntegerType(source))
+	if (integers) {
+		return `\`as\` cast ${from} to \`${target}\` can silently truncate or wrap; use \`${target}::try_from\` and handle the error.`
+	}
+	return `\`as\` cast ${from} to \`${target}\` can silently round, saturate or lose precision; check the value's range first.`
+}
+
+/**
+ * Finish the no-as-cast rule, whose YAML matches every cast to a numeric primitive or a raw
+ * pointer: name the source and target types in the message, drop lossless widening casts
+ * unless `allow-widening` is off, and drop pointer casts unless `pointer-casts` is on.
+ */
+export function applyAsCasts(issues: LintIssue[], config: LintConfig): LintIssue[] {
+	const options = config.ruleOptions?.[AS_CAST_RULE_ID]
+	const allowWidening = options?.allowWidening ?? true
+	const pointerCasts = options?.pointerCasts ?? false
+
+	return issues.flatMap((issue) => {
+		const value = issue.captures?.VALUE
+		const target = issue.captures?.TARGET
+		if (issue.ruleId !== AS_CAST_RULE_ID || !value || !target) {
+			return [issue]
+		}
+		const enclosing = issue.captures?.FUNCTION
+		const scope = enclosing
+			? { text: enclosing.text, offset: offsetWithin(enclosing.text, enclosing, issue) }
+			: null
+		const source = writtenSourceType(value.text, scope)
+		const targetType = normalizeType(target.text)
+		const kind = classifyCast(source, targetType)
+		if ((kind === 'lossless' && allowWidening) || (kind === 'pointer' && !pointerCasts)) {
+			return []
+		}
+		const message = describeCast(kind, source, normalizeType(value.text), targetType)
+		return [{ ...issue, message }]
+	})
+}
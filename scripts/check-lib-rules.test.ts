@@ -0,0 +1,43 @@
+import { readdirSync, readFileSync } from 'node:fs'
+import { dirname, join } from 'node:path'
+import { fileURLToPath } from 'node:url'
+import { describe, expect, it } from 'vitest'
+
+const __dirname = dirname(fileURLToPath(import.meta.url))
+const rustRulesDir = join(__dirname, '..', 'src', 'rust', 'rules')
+
+// Reference rule whose file scoping every `*-in-lib` rule copies
+const REFERENCE_RULE = 'no-unwrap-in-lib.yml'
+
+// Returns a top-level YAML list (e.g. `ignores:`) as an array of its entries
+function readTopLevelList(content: string, key: string): string[] {
+	const lines = content.split('\n')
+	const start = lines.indexOf(`${key}:`)
+	if (start === -1) return []
+
+	const entries: string[] = []
+	for (const line of lines.slice(start + 1)) {
+		const match = line.match(/^\s+-\s+(.+)$/)
+		if (!match?.[1]) break
+		entries.push(match[1].trim())
+	}
+	return entries
+}
+
+describe('library-scoped rules', () => {
+	const libRules = readdirSync(rustRulesDir).filter((f) => f.endsWith('-in-lib.yml'))
+	const reference = readFileSync(join(rustRulesDir, REFERENCE_RULE), 'utf-8')
+
+	it('should include the reference rule', () => {
+		expect(libRules).toContain(REFERENCE_RULE)
+	})
+
+	for (const filename of libRules) {
+		it(`${filename} should scope files like ${REFERENCE_RULE}`, () => {
+			const content = readFileSync(join(rustRulesDir, filename), 'utf-8')
+
+			expect(readTopLevelList(content, 'files')).toEqual(readTopLevelList(reference, 'files'))
+			expect(readTopLevelList(content, 'ignores')).toEqual(readTopLevelList(reference, 'ignores'))
+		})
+	}
+})
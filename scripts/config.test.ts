@@ -0,0 +1,619 @@
+import fs from 'node:fs'
+import os from 'node:os'
+import path from 'node:path'
+import { afterEach, describe, expect, it } from 'vitest'
+import {
+	applyMessageTemplates,
+	applyRuleLevels,
+	applyRuleLevelsToMetadata,
+	CONFIG_FILE_NAME,
+	defaultConfig,
+	enableTestRules,
+	findConfigFile,
+	type LintConfig,
+	loadConfig,
+	mergeConfig,
+	parseConfig,
+	selectRules,
+	TEST_RULE_IDS,
+} from './config.ts'
+import type { LintIssue, RuleMetadata } from './shared.ts'
+
+const issues: LintIssue[] = [
+	{
+		ruleId: 'no-dbg-macro',
+		severity: 'error',
+		message: 'Remove dbg!()',
+		file: 'src/lib.rs',
+		line: 1,
+		column: 1,
+	},
+	{
+		ruleId: 'no-unwrap-in-lib',
+		severity: 'warning',
+		message: 'Avoid .unwrap()',
+		file: 'src/lib.rs',
+		line: 2,
+		column: 1,
+	},
+	{
+		ruleId: 'no-emojis',
+		severity: 'warning',
+		message: 'No emojis',
+		file: 'src/lib.rs',
+		line: 3,
+		column: 1,
+	},
+]
+
+describe('parseConfig', () => {
+	it('should read rule levels from the [rules] table', () => {
+		const config = parseConfig(
+			'[rules]\nno-dbg-macro = "warning"\nno-unwrap-in-lib = "error"\nno-emojis = "off"\n',
+			CONFIG_FILE_NAME,
+		)
+
+		expect(config.rules).toEqual({
+			'no-dbg-macro': 'warning',
+			'no-unwrap-in-lib': 'error',
+			'no-emojis': 'off',
+		})
+	})
+
+	it('should accept a file without a [rules] table', () => {
+		expect(parseConfig('# nothing configured\n', CONFIG_FILE_NAME).rules).toEqual({})
+	})
+
+	it('should map booleans to the default severity or off', () => {
+		const config = parseConfig(
+			'[rules]\nno-dbg-macro = true\nno-emojis = false\n',
+			CONFIG_FILE_NAME,
+		)
+
+		expect(config.rules).toEqual({ 'no-emojis': 'off' })
+	})
+
+	it('should read levels and settings from [rules.<id>] tables', () => {
+		const config = parseConfig(
+			'[rules.no-todo-comment]\nlevel = "error"\nmarkers = ["TODO", "SAFETY-TODO"]\n',
+			CONFIG_FILE_NAME,
+		)
+
+		expect(config.rules).toEqual({ 'no-todo-comment': 'error' })
+		expect(config.ruleOptions).toEqual({ 'no-todo-comment': { markers: ['TODO', 'SAFETY-TODO'] } })
+	})
+
+	it('should read the allowlist of no-unsafe-block', () => {
+		const config = parseConfig(
+			'[rules.no-unsafe-block]\nallow = ["crates/sys/**", "ffi::raw"]\n',
+			CONFIG_FILE_NAME,
+		)
+
+		expect(config.rules).toEqual({})
+		expect(config.ruleOptions).toEqual({
+			'no-unsafe-block': { allow: ['crates/sys/**', 'ffi::raw'] },
+		})
+	})
+
+	it('should read a positive max-lines for max-function-length', () => {
+		const config = parseConfig('[rules.max-function-length]\nmax-lines = 80\n', CONFIG_FILE_NAME)
+
+		expect(config.ruleOptions).toEqual({ 'max-function-length': { maxLines: 80 } })
+		expect(() =>
+			parseConfig('[rules.max-function-length]\nmax-lines = 0\n', CONFIG_FILE_NAME),
+		).toThrow("'max-lines' for rule 'max-function-length' must be a positive integer")
+	})
+
+	it('should read type-changes-only for no-shadowing', () => {
+		const config = parseConfig(
+			'[rules.no-shadowing]\nlevel = "warning"\ntype-changes-only = true\n',
+			CONFIG_FILE_NAME,
+		)
+
+		expect(config.rules).toEqual({ 'no-shadowing': 'warning' })
+		expect(config.ruleOptions).toEqual({ 'no-shadowing': { typeChangesOnly: true } })
+		expect(() =>
+			parseConfig('[rules.no-shadowing]\ntype-changes-only = "yes"\n', CONFIG_FILE_NAME),
+		).toThrow("'type-changes-only' for rule 'no-shadowing' must be true or false")
+	})
+
+	it('should read the allowed modules of no-wildcard-import', () => {
+		const config = parseConfig(
+			'[rules.no-wildcard-import]\nallow-modules = ["prelude", "diesel::dsl"]\n',
+			CONFIG_FILE_NAME,
+		)
+
+		expect(config.ruleOptions).toEqual({
+			'no-wildcard-import': { allowModules: ['prelude', 'diesel::dsl'] },
+		})
+	})
+
+	it('should read the methods of no-unwrap-in-lib', () => {
+		const config = parseConfig(
+			'[rules.no-unwrap-in-lib]\nmethods = ["unwrap", "unwrap_unchecked"]\n',
+			CONFIG_FILE_NAME,
+		)
+
+		expect(config.ruleOptions).toEqual({
+			'no-unwrap-in-lib': { methods: ['unwrap', 'unwrap_unchecked'] },
+		})
+	})
+
+	it('should read the logging macros of no-string-format-in-log', () => {
+		const config = parseConfig(
+			'[rules.no-string-format-in-log]\nmacros = ["info", "warn", "audit"]\n',
+			CONFIG_FILE_NAME,
+		)
+
+		expect(config.ruleOptions).toEqual({
+			'no-string-format-in-log': { macros: ['info', 'warn', 'audit'] },
+		})
+	})
+
+	it('should read the size ratio of no-large-enum-variant', () => {
+		const config = parseConfig('[rules.no-large-enum-variant]\nmax-ratio = 8\n', CONFIG_FILE_NAME)
+
+		expect(config.ruleOptions).toEqual({ 'no-large-enum-variant': { maxRatio: 8 } })
+	})
+
+	it('should read the no-impl-trait-in-public-return settings', () => {
+		const toml = [
+			'[rules.no-impl-trait-in-public-return]',
+			'level = "warning"',
+			'pub-crate = true',
+			'arguments = true',
+		].join('\n')
+
+		const config = parseConfig(toml, CONFIG_FILE_NAME)
+
+		expect(config.rules).toEqual({ 'no-impl-trait-in-public-return': 'warning' })
+		expect(config.ruleOptions).toEqual({
+			'no-impl-trait-in-public-return': { pubCrate: true, arguments: true },
+		})
+	})
+
+	it('should read the require-must-use-on-result-returning-fn settings', () => {
+		const config = parseConfig(
+			'[rules.require-must-use-on-result-returning-fn]\nresult-aliases = false\npub-crate = true\n',
+			CONFIG_FILE_NAME,
+		)
+
+		expect(config.ruleOptions).toEqual({
+			'require-must-use-on-result-returning-fn': { resultAliases: false, pubCrate: true },
+		})
+	})
+
+	it('should read the no-as-cast settings', () => {
+		const config = parseConfig(
+			'[rules.no-as-cast]\nallow-widening = false\npointer-casts = true\n',
+			CONFIG_FILE_NAME,
+		)
+
+		expect(config.ruleOptions).toEqual({
+			'no-as-cast': { allowWidening: false, pointerCasts: true },
+		})
+	})
+
+	it('should read the no-redundant-return settings', () => {
+		const config = parseConfig(
+			'[rules.no-redundant-return]\nbare-returns = true\n',
+			CONFIG_FILE_NAME,
+		)
+
+		expect(config.ruleOptions).toEqual({ 'no-redundant-return': { bareReturns: true } })
+		expect(() =>
+			parseConfig('[rules.no-as-cast]\nbare-returns = true\n', CONFIG_FILE_NAME),
+		).toThrow("'bare-returns' is only supported for rule 'no-redundant-return'")
+	})
+
+	it('should read the max-line-length settings', () => {
+		const config = parseConfig(
+			'[rules.max-line-length]\nmax-width = 120\ntab-width = 8\nignore-url-comments = true\n',
+			CONFIG_FILE_NAME,
+		)
+
+		expect(config.ruleOptions).toEqual({
+			'max-line-length': { maxWidth: 120, tabWidth: 8, ignoreUrlComments: true },
+		})
+	})
+
+	it('should read message templates for any rule', () => {
+		const config = parseConfig(
+			'[rules]\nno-unwrap-in-lib.message = "{message} See wiki/unwrap"\n',
+			CONFIG_FILE_NAME,
+		)
+
+		expect(config.rules).toEqual({})
+		expect(config.ruleOptions).toEqual({
+			'no-unwrap-in-lib': { message: '{message} See wiki/unwrap' },
+		})
+	})
+
+	it('should read finding budgets for any rule', () => {
+		const config = parseConfig(
+			'[rules]\nno-unwrap-in-lib.max = 120\nno-dbg-macro = { level = "warning", max = 0 }\n',
+			CONFIG_FILE_NAME,
+		)
+
+		expect(config.rules).toEqual({ 'no-dbg-macro': 'warning' })
+		expect(config.ruleOptions).toEqual({
+			'no-unwrap-in-lib': { max: 120 },
+			'no-dbg-macro': { max: 0 },
+		})
+		expect(() => parseConfig('[rules.no-dbg-macro]\nmax = -1\n', CONFIG_FILE_NAME)).toThrow(
+			`${CONFIG_FILE_NAME}: 'max' for rule 'no-dbg-macro' must be a non-negative integer`,
+		)
+	})
+
+	it('should reject unknown placeholders in message templates', () => {
+		expect(() =>
+			parseConfig('[rules.no-dbg-macro]\nmessage = "Remove it from {path}"\n', CONFIG_FILE_NAME),
+		).toThrow(
+			`${CONFIG_FILE_NAME}: unknown placeholder '{path}' in 'message' for rule 'no-dbg-macro'. Must be one of: {rule}, {file}, {line}, {column}, {message}`,
+		)
+		expect(() =>
+			parseConfig('[rules.no-dbg-macro]\nmessage = ""\n', CONFIG_FILE_NAME),
+		).toThrow("'message' for rule 'no-dbg-macro' must be a non-empty string")
+	})
+
+	it('should read allowed paths for every rule that takes them', () => {
+		const config = parseConfig(
+			[
+				'[rules.no-unsafe-block]',
+				'allow = ["ffi::raw"]',
+				'[rules.no-mod-rs]',
+				'allow = ["crates/legacy/**"]',
+				'',
+			].join('\n'),
+			CONFIG_FILE_NAME,
+		)
+
+		expect(config.ruleOptions).toEqual({
+			'no-unsafe-block': { allow: ['ffi::raw'] },
+			'no-mod-rs': { allow: ['crates/legacy/**'] },
+		})
+	})
+
+	it('should reject settings the rule does not support', () => {
+		expect(() =>
+			parseConfig('[rules.no-dbg-macro]\nmarkers = ["TODO"]\n', CONFIG_FILE_NAME),
+		).toThrow(`${CONFIG_FILE_NAME}: 'markers' is only supported for rule 'no-todo-comment'`)
+		expect(() => parseConfig('[rules.no-dbg-macro]\nallow = ["src"]\n', CONFIG_FILE_NAME)).toThrow(
+			"'allow' is only supported for rules 'no-unsafe-block' and 'no-mod-rs'",
+		)
+		expect(() =>
+			parseConfig('[rules.no-todo-comment]\nmarker = ["TODO"]\n', CONFIG_FILE_NAME),
+		).toThrow(`${CONFIG_FILE_NAME}: unknown setting 'marker' for rule 'no-todo-comment'`)
+	})
+
+	it('should reject an empty or malformed list', () => {
+		expect(() =>
+			parseConfig('[rules.no-todo-comment]\nmarkers = []\n', CONFIG_FILE_NAME),
+		).toThrow("'markers' for rule 'no-todo-comment' must be a non-empty array of strings")
+		expect(() =>
+			parseConfig('[rules.no-unsafe-block]\nallow = "ffi"\n', CONFIG_FILE_NAME),
+		).toThrow("'allow' for rule 'no-unsafe-block' must be a non-empty array of strings")
+	})
+
+	it('should reject unknown levels', () => {
+		expect(() => parseConfig('[rules]\nno-dbg-macro = "fatal"\n', CONFIG_FILE_NAME)).toThrow(
+			`${CONFIG_FILE_NAME}: invalid value "fatal" for rule 'no-dbg-macro'`,
+		)
+	})
+
+	it('should read include and exclude globs', () => {
+		const config = parseConfig(
+			'include = ["src/**/*.rs"]\nexclude = ["target/", "**/generated/*.rs"]\n',
+			CONFIG_FILE_NAME,
+		)
+
+		expect(config.include).toEqual(['src/**/*.rs'])
+		expect(config.exclude).toEqual(['target/', '**/generated/*.rs'])
+	})
+
+	it('should read the plugin list', () => {
+		const config = parseConfig('plugins = ["lints/house-rules.ts"]\n', CONFIG_FILE_NAME)
+
+		expect(config.plugins).toEqual(['lints/house-rules.ts'])
+		expect(() => parseConfig('plugins = "lints/house-rules.ts"\n', CONFIG_FILE_NAME)).toThrow(
+			`${CONFIG_FILE_NAME}: 'plugins' must be an array of module paths`,
+		)
+	})
+
+	it('should read per-path severity overrides in order', () => {
+		const config = parseConfig(
+			[
+				'[[overrides]]',
+				'path = "benches/**"',
+				'rule = "no-unwrap-in-lib"',
+				'severity = "warning"',
+				'',
+				'[[overrides]]',
+				'path = "src/generated/"',
+				'rule = "no-dbg-macro"',
+				'severity = "off"',
+				'',
+			].join('\n'),
+			CONFIG_FILE_NAME,
+		)
+
+		expect(config.overrides).toEqual([
+			{ path: 'benches/**', rule: 'no-unwrap-in-lib', severity: 'warning' },
+			{ path: 'src/generated/', rule: 'no-dbg-macro', severity: 'off' },
+		])
+	})
+
+	it('should map the old id of a renamed rule to the new id', () => {
+		const known = new Set(['no-string-format-in-log'])
+		const config = parseConfig(
+			[
+				'[rules.tracing-no-format]',
+				'level = "error"',
+				'macros = ["info"]',
+				'',
+				'[[overrides]]',
+				'path = "benches/**"',
+				'rule = "tracing-no-format"',
+				'severity = "off"',
+				'',
+			].join('\n'),
+			CONFIG_FILE_NAME,
+			known,
+		)
+
+		expect(config.rules).toEqual({ 'no-string-format-in-log': 'error' })
+		expect(config.ruleOptions).toEqual({ 'no-string-format-in-log': { macros: ['info'] } })
+		expect(config.overrides).toEqual([
+			{ path: 'benches/**', rule: 'no-string-format-in-log', severity: 'off' },
+		])
+	})
+
+	it('should reject malformed overrides', () => {
+		const known = new Set(['no-unwrap-in-lib'])
+		const parse = (entry: string) =>
+			parseConfig(`[[overrides]]\npath = "benches/**"\n${entry}\n`, CONFIG_FILE_NAME, known)
+
+		expect(() => parse('rule = "no-unwrap-in-lib"\nseverity = "info"')).toThrow(
+			`${CONFIG_FILE_NAME}: overrides entry #1: 'severity' must be one of: error, warning, off`,
+		)
+		expect(() => parse('rule = "no-unwrap"\nseverity = "off"')).toThrow(
+			`${CONFIG_FILE_NAME}: overrides entry #1: unknown rule 'no-unwrap'`,
+		)
+		expect(() => parse('rule = "no-unwrap-in-lib"\nlevel = "off"')).toThrow(
+			`${CONFIG_FILE_NAME}: overrides entry #1: unknown setting 'level'`,
+		)
+		expect(() => parseConfig('overrides = ["benches/**"]\n', CONFIG_FILE_NAME)).toThrow(
+			`${CONFIG_FILE_NAME}: 'overrides' must be an array of tables, e.g. [[overrides]]`,
+		)
+	})
+
+	it('should reject globs that are not string arrays', () => {
+		expect(() => parseConfig('exclude = "target/"\n', CONFIG_FILE_NAME)).toThrow(
+			`${CONFIG_FILE_NAME}: 'exclude' must be an array of glob strings`,
+		)
+	})
+
+	it('should reject unknown rule ids', () => {
+		const known = new Set(['no-dbg-macro'])
+
+		expect(() =>
+			parseConfig('[rules]\nno-dbg-macros = "off"\n', CONFIG_FILE_NAME, known),
+		).toThrow(`${CONFIG_FILE_NAME}: unknown rule 'no-dbg-macros' in [rules]`)
+	})
+
+	it('should prefix syntax errors with the file name', () => {
+		expect(() => parseConfig('[rules\n', 'project/tempo-lints.toml')).toThrow(
+			"project/tempo-lints.toml: line 1: expected ']'",
+		)
+	})
+})
+
+describe('mergeConfig', () => {
+	it('should let the override win', () => {
+		const merged = mergeConfig(
+			{ rules: { 'no-dbg-macro': 'error', 'no-emojis': 'warning' } },
+			{ rules: { 'no-dbg-macro': 'off' } },
+		)
+
+		expect(merged.rules).toEqual({ 'no-dbg-macro': 'off', 'no-emojis': 'warning' })
+	})
+
+	it('should merge settings per rule', () => {
+		const merged = mergeConfig(
+			{ rules: {}, ruleOptions: { 'no-todo-comment': { markers: ['TODO'] } } },
+			{ rules: { 'no-todo-comment': 'error' } },
+		)
+
+		expect(merged.ruleOptions).toEqual({ 'no-todo-comment': { markers: ['TODO'] } })
+	})
+
+	it('should replace glob lists instead of concatenating them', () => {
+		const merged = mergeConfig(
+			{ rules: {}, include: ['src/**'], exclude: ['target/'] },
+			{ rules: {}, exclude: ['vendor/'] },
+		)
+
+		expect(merged.include).toEqual(['src/**'])
+		expect(merged.exclude).toEqual(['vendor/'])
+	})
+})
+
+describe('loadConfig', () => {
+	let tmpDir: string
+
+	afterEach(() => {
+		if (tmpDir) {
+			fs.rmSync(tmpDir, { recursive: true, force: true })
+		}
+	})
+
+	it('should return the default config when no file exists', () => {
+		tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-config-'))
+
+		// Assumes no tempo-lints.toml above the system temp dir
+		expect(loadConfig({ startDir: tmpDir })).toEqual({ config: defaultConfig(), path: null })
+	})
+
+	it('should find tempo-lints.toml in a parent directory', () => {
+		tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-config-'))
+		const configPath = path.join(tmpDir, CONFIG_FILE_NAME)
+		const nested = path.join(tmpDir, 'crates', 'core', 'src')
+		fs.mkdirSync(nested, { recursive: true })
+		fs.writeFileSync(configPath, '[rules]\nno-dbg-macro = "warning"\n')
+
+		expect(findConfigFile(nested)).toBe(configPath)
+		expect(loadConfig({ startDir: nested })).toEqual({
+			config: { rules: { 'no-dbg-macro': 'warning' } },
+			path: configPath,
+		})
+	})
+
+	it('should prefer the nearest config file', () => {
+		tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-config-'))
+		const nested = path.join(tmpDir, 'crates', 'core')
+		fs.mkdirSync(nested, { recursive: true })
+		fs.writeFileSync(path.join(tmpDir, CONFIG_FILE_NAME), '[rules]\nno-dbg-macro = "off"\n')
+		fs.writeFileSync(path.join(nested, CONFIG_FILE_NAME), '[rules]\nno-emojis = "off"\n')
+
+		expect(loadConfig({ startDir: nested }).config.rules).toEqual({ 'no-emojis': 'off' })
+	})
+
+	it('should use an explicit config path', () => {
+		tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-config-'))
+		const configPath = path.join(tmpDir, 'lints.toml')
+		fs.writeFileSync(configPath, '[rules]\nno-emojis = false\n')
+
+		expect(loadConfig({ startDir: '/', configPath }).config.rules).toEqual({ 'no-emojis': 'off' })
+	})
+
+	it('should fail on a missing explicit config path', () => {
+		tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-config-'))
+		const configPath = path.join(tmpDir, 'missing.toml')
+
+		expect(() => loadConfig({ startDir: tmpDir, configPath })).toThrow(
+			`Could not read config file ${configPath}`,
+		)
+	})
+
+	it('should reject unknown rule ids in the file', () => {
+		tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-config-'))
+		fs.writeFileSync(path.join(tmpDir, CONFIG_FILE_NAME), '[rules]\nno-dgb-macro = "off"\n')
+
+		expect(() =>
+			loadConfig({ startDir: tmpDir, knownRuleIds: new Set(['no-dbg-macro']) }),
+		).toThrow("unknown rule 'no-dgb-macro'")
+	})
+})
+
+describe('applyRuleLevels', () => {
+	it('should override severities and drop rules that are off', () => {
+		const result = applyRuleLevels(issues, {
+			'no-dbg-macro': 'warning',
+			'no-unwrap-in-lib': 'error',
+			'no-emojis': 'off',
+		})
+
+		expect(result.map((issue) => [issue.ruleId, issue.severity])).toEqual([
+			['no-dbg-macro', 'warning'],
+			['no-unwrap-in-lib', 'error'],
+		])
+	})
+
+	it('should keep the rule severity for unconfigured rules', () => {
+		expect(applyRuleLevels(issues, {})).toEqual(issues)
+	})
+})
+
+describe('applyMessageTemplates', () => {
+	it('should fill in the placeholders of configured templates', () => {
+		const config: LintConfig = {
+			rules: {},
+			ruleOptions: {
+				'no-unwrap-in-lib': {
+					message: '[{rule}] {file}:{line}:{column}: {message} See wiki/unwrap',
+				},
+			},
+		}
+
+		expect(applyMessageTemplates(issues, config).map((issue) => issue.message)).toEqual([
+			'Remove dbg!()',
+			'[no-unwrap-in-lib] src/lib.rs:2:1: Avoid .unwrap() See wiki/unwrap',
+			'No emojis',
+		])
+	})
+
+	it('should leave messages alone without templates', () => {
+		expect(applyMessageTemplates(issues, defaultConfig())).toEqual(issues)
+	})
+})
+
+describe('applyRuleLevelsToMetadata', () => {
+	it('should report the configured severity as the rule default', () => {
+		const rules: RuleMetadata[] = [
+			{ id: 'no-dbg-macro', message: 'Remove dbg!()', severity: 'error' },
+			{ id: 'no-emojis', message: 'No emojis', severity: 'warning' },
+		]
+
+		expect(
+			applyRuleLevelsToMetadata(rules, { 'no-dbg-macro': 'warning', 'no-emojis': 'off' }),
+		).toEqual([{ id: 'no-dbg-macro', message: 'Remove dbg!()', severity: 'warning' }])
+	})
+
+	it('should leave out opt-in rules unless a level turns them on', () => {
+		const rules: RuleMetadata[] = [{ id: 'no-shadowing', message: 'Shadowed', severity: 'off' }]
+
+		expect(applyRuleLevelsToMetadata(rules, {})).toEqual([])
+		expect(applyRuleLevelsToMetadata(rules, { 'no-shadowing': 'error' })).toEqual([
+			{ id: 'no-shadowing', message: 'Shadowed', severity: 'error' },
+		])
+	})
+})
+
+describe('enableTestRules', () => {
+	it('should run the named test rules at their default level', () => {
+		const config = enableTestRules({ rules: { 'no-dbg-macro': 'off' } }, TEST_RULE_IDS)
+
+		expect(config.rules).toEqual({
+			'no-dbg-macro': 'off',
+			'no-noop-assert': 'error',
+			'no-ignored-test': 'warning',
+		})
+		expect(enableTestRules({ rules: {} }, ['no-ignored-test']).rules).toEqual({
+			'no-ignored-test': 'warning',
+		})
+	})
+
+	it('should keep levels from the config file', () => {
+		const base: LintConfig = { rules: { 'no-noop-assert': 'warning', 'no-ignored-test': 'off' } }
+
+		expect(enableTestRules(base, TEST_RULE_IDS).rules).toEqual(base.rules)
+		expect(enableTestRules(base, [])).toEqual(base)
+	})
+})
+
+describe('selectRules', () => {
+	const ruleIds = ['no-dbg-macro', 'no-emojis', 'no-unwrap-in-lib']
+
+	it('should turn off every rule not listed in only', () => {
+		const base: LintConfig = { rules: { 'no-dbg-macro': 'warning' } }
+		const config = selectRules(base, ruleIds, ['no-dbg-macro'], [])
+
+		expect(config.rules).toEqual({
+			'no-dbg-macro': 'warning',
+			'no-emojis': 'off',
+			'no-unwrap-in-lib': 'off',
+		})
+	})
+
+	it('should run listed rules the config turned off', () => {
+		const config = selectRules({ rules: { 'no-emojis': 'off' } }, ruleIds, ['no-emojis'], [])
+
+		expect(config.rules['no-emojis']).toBeUndefined()
+	})
+
+	it('should turn off skipped rules', () => {
+		const config = selectRules({ rules: {} }, ruleIds, null, ['no-emojis'])
+
+		expect(config.rules).toEqual({ 'no-emojis': 'off' })
+	})
+})
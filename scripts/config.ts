@@ -0,0 +1,577 @@
+import fs from 'node:fs'
+import path from 'node:path'
+import { type LintIssue, type RuleMetadata, warn } from './shared.ts'
+import { isTable, parseToml, TomlError, type TomlTable, type TomlValue } from './toml.ts'
+
+export const CONFIG_FILE_NAME = 'tempo-lints.toml'
+
+export const RULE_LEVEL = {
+	ERROR: 'error',
+	WARNING: 'warning',
+	OFF: 'off',
+} as const
+
+export const VALID_RULE_LEVELS = [RULE_LEVEL.ERROR, RULE_LEVEL.WARNING, RULE_LEVEL.OFF] as const
+export type RuleLevel = (typeof VALID_RULE_LEVELS)[number]
+
+export const TODO_RULE_ID = 'no-todo-comment'
+export const UNWRAP_RULE_ID = 'no-unwrap-in-lib'
+export const UNSAFE_RULE_ID = 'no-unsafe-block'
+export const FUNCTION_LENGTH_RULE_ID = 'max-function-length'
+export const SHADOWING_RULE_ID = 'no-shadowing'
+export const WILDCARD_IMPORT_RULE_ID = 'no-wildcard-import'
+export const LINE_LENGTH_RULE_ID = 'max-line-length'
+export const MOD_RS_RULE_ID = 'no-mod-rs'
+export const LOG_FORMAT_RULE_ID = 'no-string-format-in-log'
+export const LARGE_ENUM_VARIANT_RULE_ID = 'no-large-enum-variant'
+export const AS_CAST_RULE_ID = 'no-as-cast'
+export const IMPL_TRAIT_RULE_ID = 'no-impl-trait-in-public-return'
+export const MUST_USE_RULE_ID = 'require-must-use-on-result-returning-fn'
+export const NOOP_ASSERT_RULE_ID = 'no-noop-assert'
+export const IGNORED_TEST_RULE_ID = 'no-ignored-test'
+export const REDUNDANT_RETURN_RULE_ID = 'no-redundant-return'
+
+// Rules for test code, which check what the others allow there. Their rule files ship them
+// off; --tests-only runs them at these levels, unless the config file gives them another one
+export const TEST_RULE_LEVELS: Readonly<Record<string, RuleLevel>> = {
+	[NOOP_ASSERT_RULE_ID]: RULE_LEVEL.ERROR,
+	[IGNORED_TEST_RULE_ID]: RULE_LEVEL.WARNING,
+}
+export const TEST_RULE_IDS: readonly string[] = Object.keys(TEST_RULE_LEVELS)
+
+// Rules whose ast-grep matches are only candidates, which the CLI checks before reporting them
+export const CLI_FINISHED_RULE_IDS: readonly string[] = [
+	AS_CAST_RULE_ID,
+	FUNCTION_LENGTH_RULE_ID,
+	IMPL_TRAIT_RULE_ID,
+	LARGE_ENUM_VARIANT_RULE_ID,
+	LINE_LENGTH_RULE_ID,
+	MUST_USE_RULE_ID,
+	SHADOWING_RULE_ID,
+	WILDCARD_IMPORT_RULE_ID,
+]
+
+// Old ids of renamed rules, which keep working with a warning to move to the new id
+export const RENAMED_RULE_IDS: ReadonlyMap<string, string> = new Map([
+	['tracing-no-format', LOG_FORMAT_RULE_ID],
+])
+
+/**
+ * The current id of a rule named in `where`, e.g. a config file, a flag or a source location.
+ * The old id of a renamed rule maps to the new one with a warning; other ids are kept as-is.
+ */
+export function resolveRuleId(ruleId: string, where: string): string {
+	const renamed = RENAMED_RULE_IDS.get(ruleId)
+	if (renamed === undefined) return ruleId
+	warn(`${where}: rule '${ruleId}' was renamed to '${renamed}'; use the new id`)
+	return renamed
+}
+
+// Settings from a `[rules.<id>]` table besides `level`
+export interface RuleOptions {
+	// no-todo-comment: comment markers to report instead of the built-in list
+	markers?: string[]
+	// no-unwrap-in-lib: which of `unwrap`, `unwrap_err` and `unwrap_unchecked` to report
+	methods?: string[]
+	// no-unsafe-block, no-mod-rs: path globs or Rust module paths where the rule doesn't apply
+	allow?: string[]
+	// max-function-length: most code lines a function body may have
+	maxLines?: number
+	// no-shadowing: only report rebinds that visibly change the binding's type
+	typeChangesOnly?: boolean
+	// no-wildcard-import: modules whose glob imports are allowed, instead of the built-in list
+	allowModules?: string[]
+	// max-line-length: most columns a line may take
+	maxWidth?: number
+	// max-line-length: columns between tab stops
+	tabWidth?: number
+	// max-line-length: skip lines holding only a comment with a URL
+	ignoreUrlComments?: boolean
+	// no-string-format-in-log: logging macro names to check instead of the built-in list
+	macros?: string[]
+	// no-large-enum-variant: most times the smallest variant's size a variant may take
+	maxRatio?: number
+	// no-as-cast: whether lossless widening casts like `u8 as u32` are allowed, on by default
+	allowWidening?: boolean
+	// no-as-cast: also report casts to and from raw pointers
+	pointerCasts?: boolean
+	// no-impl-trait-in-public-return, require-must-use-on-result-returning-fn: also check
+	// functions with a restricted `pub(...)` visibility
+	pubCrate?: boolean
+	// no-impl-trait-in-public-return: also report `impl Trait` argument types
+	arguments?: boolean
+	// require-must-use-on-result-returning-fn: count return types named like `ParseResult` as
+	// result type aliases, on by default
+	resultAliases?: boolean
+	// no-redundant-return: also report a bare `return;` ending a function body
+	bareReturns?: boolean
+	// Any rule: template replacing the reported message, see MESSAGE_PLACEHOLDERS
+	message?: string
+	// Any rule: most findings a run may have before the rule fails it, see budgets.ts
+	max?: number
+}
+
+// What `{name}` stands for in a `message` template
+export const MESSAGE_PLACEHOLDERS = ['rule', 'file', 'line', 'column', 'message'] as const
+
+type RuleOptionSpec =
+	| { option: 'markers' | 'allow' | 'allowModules' | 'macros'; rules: string[]; type: 'strings' }
+	| { option: 'methods'; rules: string[]; type: 'strings' }
+	| { option: 'maxLines' | 'maxWidth' | 'tabWidth' | 'maxRatio'; rules: string[]; type: 'count' }
+	| { option: 'typeChangesOnly' | 'ignoreUrlComments'; rules: string[]; type: 'boolean' }
+	| { option: 'allowWidening' | 'pointerCasts'; rules: string[]; type: 'boolean' }
+	| { option: 'pubCrate' | 'arguments' | 'resultAliases'; rules: string[]; type: 'boolean' }
+	| { option: 'bareReturns'; rules: string[]; type: 'boolean' }
+
+// Which rules accept which settings, keyed by the name used in the config file
+const RULE_OPTION_SPECS: Record<string, RuleOptionSpec> = {
+	markers: { option: 'markers', rules: [TODO_RULE_ID], type: 'strings' },
+	methods: { option: 'methods', rules: [UNWRAP_RULE_ID], type: 'strings' },
+	allow: { option: 'allow', rules: [UNSAFE_RULE_ID, MOD_RS_RULE_ID], type: 'strings' },
+	'max-lines': { option: 'maxLines', rules: [FUNCTION_LENGTH_RULE_ID], type: 'count' },
+	'type-changes-only': { option: 'typeChangesOnly', rules: [SHADOWING_RULE_ID], type: 'boolean' },
+	'allow-modules': { option: 'allowModules', rules: [WILDCARD_IMPORT_RULE_ID], type: 'strings' },
+	'max-width': { option: 'maxWidth', rules: [LINE_LENGTH_RULE_ID], type: 'count' },
+	'tab-width': { option: 'tabWidth', rules: [LINE_LENGTH_RULE_ID], type: 'count' },
+	'ignore-url-comments': {
+		option: 'ignoreUrlComments',
+		rules: [LINE_LENGTH_RULE_ID],
+		type: 'boolean',
+	},
+	macros: { option: 'macros', rules: [LOG_FORMAT_RULE_ID], type: 'strings' },
+	'max-ratio': { option: 'maxRatio', rules: [LARGE_ENUM_VARIANT_RULE_ID], type: 'count' },
+	'allow-widening': { option: 'allowWidening', rules: [AS_CAST_RULE_ID], type: 'boolean' },
+	'pointer-casts': { option: 'pointerCasts', rules: [AS_CAST_RULE_ID], type: 'boolean' },
+	'pub-crate': {
+		option: 'pubCrate',
+		rules: [IMPL_TRAIT_RULE_ID, MUST_USE_RULE_ID],
+		type: 'boolean',
+	},
+	arguments: { option: 'arguments', rules: [IMPL_TRAIT_RULE_ID], type: 'boolean' },
+	'result-aliases': { option: 'resultAliases', rules: [MUST_USE_RULE_ID], type: 'boolean' },
+	'bare-returns': { option: 'bareReturns', rules: [REDUNDANT_RETURN_RULE_ID], type: 'boolean' },
+}
+
+export interface LintConfig {
+	// Level overrides keyed by rule id; rules not listed keep the severity from their YAML
+	rules: Record<string, RuleLevel>
+	// Rule-specific settings keyed by rule id
+	ruleOptions?: Record<string, RuleOptions>
+	// Gitignore-style globs selecting which files are scanned; unset means "not configured"
+	include?: string[]
+	exclude?: string[]
+	// Modules with custom rules, relative to the config file, see plugins.ts
+	plugins?: string[]
+	// Per-path severities from `[[overrides]]`, in the order listed; see overrides.ts
+	overrides?: SeverityOverride[]
+}
+
+// One `[[overrides]]` entry: `rule` takes `severity` in files matching the `path` glob
+export interface SeverityOverride {
+	path: string
+	rule: string
+	severity: RuleLevel
+}
+
+export interface LoadedConfig {
+	config: LintConfig
+	// Path of the config file that was read, or null when none was found
+	path: string | null
+}
+
+export function isValidRuleLevel(value: unknown): value is RuleLevel {
+	return typeof value === 'string' && VALID_RULE_LEVELS.includes(value as RuleLevel)
+}
+
+// Built-in defaults: every rule runs at the severity from its YAML file
+export function defaultConfig(): LintConfig {
+	return { rules: {} }
+}
+
+/**
+ * Parse the contents of a `tempo-lints.toml` file.
+ * Each `[rules]` entry maps a rule id to a level, or to `true`/`false` to keep the rule's
+ * default severity or turn it off. A `[rules.<id>]` table sets the level with `level` next to
+ * rule-specific settings. Throws with a message naming `source` when the file is malformed or
+ * refers to a rule that isn't in `knownRuleIds`.
+ */
+export function parseConfig(
+	content: string,
+	source: string,
+	knownRuleIds?: Set<string>,
+): LintConfig {
+	let data: ReturnType<typeof parseToml>
+	try {
+		data = parseToml(content)
+	} catch (err) {
+		if (err instanceof TomlError) {
+			throw new Error(`${source}: ${err.message}`)
+		}
+		throw err
+	}
+
+	const config = defaultConfig()
+	config.include = parseGlobList(data.include, 'include', source)
+	config.exclude = parseGlobList(data.exclude, 'exclude', source)
+	config.plugins = parsePluginList(data.plugins, source)
+	config.overrides = parseOverrides(data.overrides, source, knownRuleIds)
+
+	const rules = data.rules
+	if (rules === undefined) {
+		return config
+	}
+	if (!isTable(rules)) {
+		throw new Error(`${source}: [rules] must be a table`)
+	}
+
+	for (const [name, value] of Object.entries(rules)) {
+		const ruleId = resolveRuleId(name, source)
+		// Catch typos: an unknown id would otherwise silently leave the intended rule untouched
+		if (knownRuleIds && !knownRuleIds.has(ruleId)) {
+			throw new Error(`${source}: unknown rule '${ruleId}' in [rules]`)
+		}
+
+		if (isTable(value)) {
+			parseRuleTable(config, ruleId, value, source)
+			continue
+		}
+
+		const level = parseRuleLevel(value, ruleId, source)
+		if (level !== undefined) {
+			config.rules[ruleId] = level
+		}
+	}
+
+	return config
+}
+
+// `true` keeps the default severity, so it maps to no level at all
+function parseRuleLevel(value: TomlValue, ruleId: string, source: string): RuleLevel | undefined {
+	if (value === true) return undefined
+	if (value === false) return RULE_LEVEL.OFF
+	if (!isValidRuleLevel(value)) {
+		throw new Error(
+			`${source}: invalid value ${JSON.stringify(value)} for rule '${ruleId}'. Must be true, false or one of: ${VALID_RULE_LEVELS.join(', ')}`,
+		)
+	}
+	return value
+}
+
+function parseRuleTable(
+	config: LintConfig,
+	ruleId: string,
+	table: TomlTable,
+	source: string,
+): void {
+	const options: RuleOptions = {}
+
+	for (const [key, value] of Object.entries(table)) {
+		if (key === 'level') {
+			const level = parseRuleLevel(value, ruleId, source)
+			if (level !== undefined) {
+				config.rules[ruleId] = level
+			}
+			continue
+		}
+
+		if (key === 'message') {
+			options.message = parseMessageTemplate(value, ruleId, source)
+			continue
+		}
+
+		if (key === 'max') {
+			if (typeof value !== 'number' || !Number.isInteger(value) || value < 0) {
+				throw new Error(`${source}: 'max' for rule '${ruleId}' must be a non-negative integer`)
+			}
+			options.max = value
+			continue
+		}
+
+		if (!Object.hasOwn(RULE_OPTION_SPECS, key)) {
+			throw new Error(`${source}: unknown setting '${key}' for rule '${ruleId}'`)
+		}
+		const spec = RULE_OPTION_SPECS[key]!
+		if (!spec.rules.includes(ruleId)) {
+			const rules = spec.rules.map((id) => `'${id}'`).join(' and ')
+			const noun = spec.rules.length === 1 ? 'rule' : 'rules'
+			throw new Error(`${source}: '${key}' is only supported for ${noun} ${rules}`)
+		}
+
+		if (spec.type === 'count') {
+			if (typeof value !== 'number' || !Number.isInteger(value) || value < 1) {
+				throw new Error(`${source}: '${key}' for rule '${ruleId}' must be a positive integer`)
+			}
+			options[spec.option] = value
+			continue
+		}
+
+		if (spec.type === 'boolean') {
+			if (typeof value !== 'boolean') {
+				throw new Error(`${source}: '${key}' for rule '${ruleId}' must be true or false`)
+			}
+			options[spec.option] = value
+			continue
+		}
+
+		if (
+			!Array.isArray(value) ||
+			value.length === 0 ||
+			!value.every((entry) => typeof entry === 'string' && entry.trim() !== '')
+		) {
+			throw new Error(
+				`${source}: '${key}' for rule '${ruleId}' must be a non-empty array of strings`,
+			)
+		}
+		options[spec.option] = value as string[]
+	}
+
+	if (Object.keys(options).length > 0) {
+		config.ruleOptions = { ...config.ruleOptions, [ruleId]: options }
+	}
+}
+
+function parseMessageTemplate(value: TomlValue, ruleId: string, source: string): string {
+	if (typeof value !== 'string' || value.trim() === '') {
+		throw new Error(`${source}: 'message' for rule '${ruleId}' must be a non-empty string`)
+	}
+	for (const [, name] of value.matchAll(/\{([^{}]*)\}/g)) {
+		if (!MESSAGE_PLACEHOLDERS.some((placeholder) => placeholder === name)) {
+			throw new Error(
+				`${source}: unknown placeholder '{${name}}' in 'message' for rule '${ruleId}'. Must be one of: ${MESSAGE_PLACEHOLDERS.map((placeholder) => `{${placeholder}}`).join(', ')}`,
+			)
+		}
+	}
+	return value
+}
+
+function parseGlobList(
+	value: TomlValue | undefined,
+	key: string,
+	source: string,
+): string[] | undefined {
+	if (value === undefined) {
+		return undefined
+	}
+	if (!Array.isArray(value) || !value.every((glob) => typeof glob === 'string')) {
+		throw new Error(`${source}: '${key}' must be an array of glob strings`)
+	}
+	return value as string[]
+}
+
+function parsePluginList(value: TomlValue | undefined, source: string): string[] | undefined {
+	if (value === undefined) {
+		return undefined
+	}
+	if (
+		!Array.isArray(value) ||
+		!value.every((plugin) => typeof plugin === 'string' && plugin.trim() !== '')
+	) {
+		throw new Error(`${source}: 'plugins' must be an array of module paths`)
+	}
+	return value as string[]
+}
+
+const OVERRIDE_KEYS = ['path', 'rule', 'severity']
+
+function parseOverrides(
+	value: TomlValue | undefined,
+	source: string,
+	knownRuleIds?: Set<string>,
+): SeverityOverride[] | undefined {
+	if (value === undefined) {
+		return undefined
+	}
+	if (!Array.isArray(value) || !value.every(isTable)) {
+		throw new Error(`${source}: 'overrides' must be an array of tables, e.g. [[overrides]]`)
+	}
+	return value.map((entry, i) => {
+		const where = `${source}: overrides entry #${i + 1}`
+		for (const key of Object.keys(entry)) {
+			if (!OVERRIDE_KEYS.includes(key)) {
+				throw new Error(`${where}: unknown setting '${key}'`)
+			}
+		}
+		const { path: glob, severity } = entry
+		if (typeof glob !== 'string' || glob.trim() === '') {
+			throw new Error(`${where}: 'path' must be a non-empty glob string`)
+		}
+		if (typeof entry.rule !== 'string' || entry.rule === '') {
+			throw new Error(`${where}: 'rule' must be a rule id`)
+		}
+		const rule = resolveRuleId(entry.rule, where)
+		if (knownRuleIds && !knownRuleIds.has(rule)) {
+			throw new Error(`${where}: unknown rule '${rule}'`)
+		}
+		if (!isValidRuleLevel(severity)) {
+			throw new Error(`${where}: 'severity' must be one of: ${VALID_RULE_LEVELS.join(', ')}`)
+		}
+		return { path: glob, rule, severity }
+	})
+}
+
+/**
+ * Merge `override` over `base`; settings in `override` win.
+ * Glob lists and `overrides` are replaced, not concatenated.
+ */
+export function mergeConfig(base: LintConfig, override: LintConfig): LintConfig {
+	const ruleOptions: Record<string, RuleOptions> = { ...base.ruleOptions }
+	for (const [ruleId, options] of Object.entries(override.ruleOptions ?? {})) {
+		ruleOptions[ruleId] = { ...ruleOptions[ruleId], ...options }
+	}
+
+	return {
+		rules: { ...base.rules, ...override.rules },
+		ruleOptions: Object.keys(ruleOptions).length > 0 ? ruleOptions : undefined,
+		include: override.include ?? base.include,
+		exclude: override.exclude ?? base.exclude,
+		plugins: override.plugins ?? base.plugins,
+		overrides: override.overrides ?? base.overrides,
+	}
+}
+
+/**
+ * Find the nearest `tempo-lints.toml`, starting at `startDir` and walking up to the
+ * filesystem root. Returns null when there is none.
+ */
+export function findConfigFile(startDir: string): string | null {
+	let dir = path.resolve(startDir)
+	while (true) {
+		const candidate = path.join(dir, CONFIG_FILE_NAME)
+		if (fs.existsSync(candidate)) {
+			return candidate
+		}
+		const parent = path.dirname(dir)
+		if (parent === dir) {
+			return null
+		}
+		dir = parent
+	}
+}
+
+/**
+ * Load the project config and merge it over the built-in defaults.
+ * `configPath` names the file explicitly; otherwise the nearest `tempo-lints.toml` above
+ * `startDir` is used. A missing discovered file is not an error.
+ */
+export function loadConfig(options: {
+	startDir: string
+	configPath?: string
+	knownRuleIds?: Set<string>
+}): LoadedConfig {
+	const configPath = options.configPath ?? findConfigFile(options.startDir)
+	if (configPath === null) {
+		return { config: defaultConfig(), path: null }
+	}
+
+	let content: string
+	try {
+		content = fs.readFileSync(configPath, 'utf8')
+	} catch (err) {
+		throw new Error(`Could not read config file ${configPath}: ${(err as Error).message}`)
+	}
+
+	const project = parseConfig(content, configPath, options.knownRuleIds)
+	return { config: mergeConfig(defaultConfig(), project), path: configPath }
+}
+
+/**
+ * Turn on the test rules in `ruleIds` at their default level from TEST_RULE_LEVELS. A level
+ * the config file sets, including `off`, is kept.
+ */
+export function enableTestRules(config: LintConfig, ruleIds: readonly string[]): LintConfig {
+	const rules = { ...config.rules }
+	for (const [ruleId, level] of Object.entries(TEST_RULE_LEVELS)) {
+		if (ruleIds.includes(ruleId)) {
+			rules[ruleId] ??= level
+		}
+	}
+	return { ...config, rules }
+}
+
+/**
+ * Narrow the active rules for one run (`--rules` and `--skip-rules`). With `only`, every
+ * other rule is turned off, and the listed ones run even if the config file turned them off.
+ * Rules in `skip` are turned off.
+ */
+export function selectRules(
+	config: LintConfig,
+	ruleIds: Iterable<string>,
+	only: string[] | null,
+	skip: string[],
+): LintConfig {
+	const rules = { ...config.rules }
+	if (only) {
+		for (const ruleId of ruleIds) {
+			if (!only.includes(ruleId)) {
+				rules[ruleId] = RULE_LEVEL.OFF
+			} else if (rules[ruleId] === RULE_LEVEL.OFF) {
+				delete rules[ruleId]
+			}
+		}
+	}
+	for (const ruleId of skip) {
+		rules[ruleId] = RULE_LEVEL.OFF
+	}
+	return { ...config, rules }
+}
+
+/**
+ * Apply configured rule levels to issues.
+ * Issues from rules set to `off` are dropped; everything else takes the configured severity.
+ */
+export function applyRuleLevels(issues: LintIssue[], rules: LintConfig['rules']): LintIssue[] {
+	const result: LintIssue[] = []
+	for (const issue of issues) {
+		const level = rules[issue.ruleId]
+		if (level === undefined) {
+			result.push(issue)
+		} else if (level !== RULE_LEVEL.OFF) {
+			result.push({ ...issue, severity: level })
+		}
+	}
+	return result
+}
+
+/**
+ * Replace the messages of rules with a configured `message` template. `{message}` is the
+ * rule's own message and `{file}` the path as the issue reports it, so call this on issues
+ * whose paths are already the ones being reported.
+ */
+export function applyMessageTemplates(issues: LintIssue[], config: LintConfig): LintIssue[] {
+	return issues.map((issue) => {
+		const template = config.ruleOptions?.[issue.ruleId]?.message
+		if (template === undefined) {
+			return issue
+		}
+		const values: Record<string, string> = {
+			rule: issue.ruleId,
+			file: issue.file,
+			line: String(issue.line),
+			column: String(issue.column),
+			message: issue.message,
+		}
+		return {
+			...issue,
+			message: template.replace(/\{([^{}]*)\}/g, (match, name) =>
+				Object.hasOwn(values, name) ? values[name]! : match,
+			),
+		}
+	})
+}
+
+/**
+ * Apply configured rule levels to rule metadata, so reports that describe the rules
+ * themselves (e.g. SARIF) show the effective severity. Rules that are `off`, whether set in
+ * the config or by default in their rule file, are removed.
+ */
+export function applyRuleLevelsToMetadata(
+	rules: RuleMetadata[],
+	levels: LintConfig['rules'],
+): RuleMetadata[] {
+	return rules
+		.map((rule) => {
+			const level = levels[rule.id]
+			return level === undefined ? rule : { ...rule, severity: level }
+		})
+		.filter((rule) => rule.severity !== RULE_LEVEL.OFF)
+}
@@ -0,0 +1,119 @@
+import { describe, expect, it } from 'vitest'
+import { filterChangedLines, parseUnifiedDiff } from './diff.ts'
+import type { LintIssue } from './shared.ts'
+
+const DIFF = [
+	'diff --git a/src/lib.rs b/src/lib.rs',
+	'index 3b18e51..a9c2f0d 100644',
+	'--- a/src/lib.rs',
+	'+++ b/src/lib.rs',
+	'@@ -2,5 +2,6 @@ use std::fs;',
+	' fn load() {',
+	'-    let x = read();',
+	'+    let x = read().unwrap();',
+	'+    dbg!(&x);',
+	'     x',
+	' }',
+	' ',
+	'@@ -20,2 +21,3 @@ fn save() {',
+	'     write();',
+	'+    flush();',
+	' }',
+	'diff --git a/src/gone.rs b/src/gone.rs',
+	'deleted file mode 100644',
+	'--- a/src/gone.rs',
+	'+++ /dev/null',
+	'@@ -1 +0,0 @@',
+	'-fn gone() {}',
+	'',
+].join('\n')
+
+function issue(file: string, line: number, endLine?: number): LintIssue {
+	return {
+		ruleId: 'no-unwrap-in-lib',
+		severity: 'error',
+		message: 'Avoid unwrap() in library code.',
+		file,
+		line,
+		column: 5,
+		...(endLine === undefined ? {} : { endLine }),
+	}
+}
+
+describe('parseUnifiedDiff', () => {
+	it('should number added lines as in the changed file', () => {
+		expect(parseUnifiedDiff(DIFF)).toEqual(
+			new Map([
+				[
+					'src/lib.rs',
+					[
+						{ start: 3, end: 4 },
+						{ start: 22, end: 22 },
+					],
+				],
+			]),
+		)
+	})
+
+	it('should not read removed lines that look like file headers as headers', () => {
+		const diff = [
+			'--- a/notes.md',
+			'+++ b/notes.md',
+			'@@ -1,2 +1,2 @@',
+			'--- draft',
+			'+++ final',
+			' body',
+		].join('\n')
+
+		expect(parseUnifiedDiff(diff)).toEqual(new Map([['notes.md', [{ start: 1, end: 1 }]]]))
+	})
+
+	it('should keep paths of diffs made without a/ and b/ prefixes', () => {
+		const diff = '--- b/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1 @@\n-old\n+new\n'
+
+		expect([...parseUnifiedDiff(diff).keys()]).toEqual(['b/src/lib.rs'])
+	})
+
+	it('should read new files and drop timestamps after the path', () => {
+		const diff = [
+			'--- /dev/null\t2026-01-01 10:00:00',
+			'+++ b/src/new.rs\t2026-01-02 10:00:00',
+			'@@ -0,0 +1,2 @@',
+			'+a',
+			'+b',
+		].join('\n')
+
+		expect(parseUnifiedDiff(diff)).toEqual(new Map([['src/new.rs', [{ start: 1, end: 2 }]]]))
+	})
+
+	it('should reject text that is not a diff', () => {
+		expect(parseUnifiedDiff('')).toEqual(new Map())
+		expect(() => parseUnifiedDiff('src/lib.rs\nsrc/main.rs\n')).toThrow('no file headers found')
+		expect(() => parseUnifiedDiff('--- a/x\n+++ b/x\n@@ -1 +1 @\n')).toThrow(
+			"line 3: malformed hunk header '@@ -1 +1 @'",
+		)
+	})
+})
+
+describe('filterChangedLines', () => {
+	const changed = parseUnifiedDiff(DIFF)
+
+	it('should keep issues on changed lines only', () => {
+		const issues = [issue('src/lib.rs', 3), issue('src/lib.rs', 5), issue('src/main.rs', 3)]
+
+		expect(filterChangedLines(issues, changed, process.cwd())).toEqual([issues[0]])
+	})
+
+	it('should keep issues spanning a changed line', () => {
+		const issues = [issue('src/lib.rs', 1, 6), issue('src/lib.rs', 10, 21)]
+
+		expect(filterChangedLines(issues, changed, process.cwd())).toEqual([issues[0]])
+	})
+
+	it('should resolve diff paths against the base directory', () => {
+		const issues = [issue('/repo/src/lib.rs', 22)]
+
+		expect(filterChangedLines(issues, changed, '/repo')).toEqual(issues)
+		expect(filterChangedLines(issues, changed, '/other')).toEqual([])
+	})
+})
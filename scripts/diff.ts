@@ -0,0 +1,124 @@
+import path from 'node:path'
+import type { LintIssue } from './shared.ts'
+
+// Lines `start` to `end`, inclusive and 1-based, as numbered in the changed file
+export interface LineRange {
+	start: number
+	end: number
+}
+
+// Added or changed lines by file path, as the diff names the file after the change
+export type ChangedLines = Map<string, LineRange[]>
+
+const HUNK_HEADER = /^@@ -\d+(?:,(\d+))? \+(\d+)(?:,(\d+))? @@/
+
+/**
+ * Read the lines each file gains from a unified diff, e.g. the output of `git diff`. Line
+ * numbers are those of the file after the change, so they line up with findings from linting
+ * it. A changed line shows up in a diff as a removed line plus an added one, so it counts like
+ * an added line; removed lines and context lines don't count. Deleted files are left out.
+ * Throws when `content` has text but no file headers, or a hunk header is malformed.
+ */
+export function parseUnifiedDiff(content: string): ChangedLines {
+	const changed: ChangedLines = new Map()
+	const lines = content.split('\n')
+	let oldPath: string | null = null
+	let ranges: LineRange[] | null = null
+	let sawHeader = false
+
+	for (let i = 0; i < lines.length; i++) {
+		const line = lines[i]!
+		if (line.startsWith('--- ')) {
+			oldPath = readDiffPath(line.slice(4))
+			continue
+		}
+		if (line.startsWith('+++ ')) {
+			sawHeader = true
+			const newPath = readDiffPath(line.slice(4))
+			ranges = null
+			if (newPath !== '/dev/null') {
+				const file = stripPrefix(newPath, oldPath)
+				ranges = changed.get(file) ?? []
+				changed.set(file, ranges)
+			}
+			continue
+		}
+		if (!line.startsWith('@@')) continue
+
+		const header = HUNK_HEADER.exec(line)
+		if (!header) {
+			throw new Error(`line ${i + 1}: malformed hunk header '${line}'`)
+		}
+		// Walk the hunk by its line counts, so content starting with `---` or `+++` isn't
+		// mistaken for a file header
+		let oldRemaining = header[1] === undefined ? 1 : Number(header[1])
+		let lineNumber = Number(header[2])
+		let newRemaining = header[3] === undefined ? 1 : Number(header[3])
+		while ((oldRemaining > 0 || newRemaining > 0) && i + 1 < lines.length) {
+			const body = lines[++i]!
+			if (body.startsWith('\\')) continue
+			if (body.startsWith('-')) {
+				oldRemaining--
+				continue
+			}
+			if (body.startsWith('+')) {
+				if (ranges !== null) addLine(ranges, lineNumber)
+			} else {
+				// Context line; some tools trim the space off empty ones
+				oldRemaining--
+			}
+			lineNumber++
+			newRemaining--
+		}
+	}
+
+	if (!sawHeader && content.trim() !== '') {
+		throw new Error('no file headers found; expected a unified diff such as `git diff` prints')
+	}
+	return changed
+}
+
+// The path from a `---`/`+++` line, without the timestamp some tools add after a tab
+function readDiffPath(text: string): string {
+	const name = text.split('\t')[0]!.trimEnd()
+	if (name.length >= 2 && name.startsWith('"') && name.endsWith('"')) {
+		return name.slice(1, -1).replace(/\\(.)/g, (_, ch: string) => (ch === 't' ? '\t' : ch))
+	}
+	return name
+}
+
+// git names files `a/...` and `b/...` unless run with --no-prefix
+function stripPrefix(newPath: string, oldPath: string | null): string {
+	const prefixed = oldPath === null || oldPath === '/dev/null' || oldPath.startsWith('a/')
+	return prefixed && newPath.startsWith('b/') ? newPath.slice(2) : newPath
+}
+
+function addLine(ranges: LineRange[], line: number): void {
+	const last = ranges[ranges.length - 1]
+	if (last !== undefined && last.end === line - 1) {
+		last.end = line
+	} else {
+		ranges.push({ start: line, end: line })
+	}
+}
+
+/**
+ * Keep the issues that touch a changed line: a finding spanning several lines, like a long
+ * function, is kept when any of its lines changed. Diff paths are resolved against `baseDir`,
+ * usually the repository root, and issue paths against the cwd.
+ */
+export function filterChangedLines(
+	issues: LintIssue[],
+	changed: ChangedLines,
+	baseDir: string,
+): LintIssue[] {
+	const byFile = new Map<string, LineRange[]>()
+	for (const [file, ranges] of changed) {
+		byFile.set(path.resolve(baseDir, file), ranges)
+	}
+	return issues.filter((issue) => {
+		const ranges = byFile.get(path.resolve(issue.file))
+		const end = issue.endLine ?? issue.line
+		return ranges?.some((range) => range.start <= end && range.end >= issue.line) ?? false
+	})
+}
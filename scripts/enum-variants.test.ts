@@ -0,0 +1,137 @@
+import { describe, expect, it } from 'vitest'
+import { applyLargeEnumVariants, estimateTypeSize, parseEnumVariants } from './enum-variants.ts'
+import type { LintIssue } from './shared.ts'
+
+function variant(enumItem: string, code: string): LintIssue {
+	const lines = enumItem.split('\n')
+	const line = lines.findIndex((text) => text.includes(code))
+	return {
+		ruleId: 'no-large-enum-variant',
+		severity: 'warning',
+		message: 'Enum variant is much larger than the smallest variant.',
+		file: 'src/lib.rs',
+		line: line + 1,
+		column: lines[line]!.indexOf(code) + 1,
+		code,
+		captures: { ENUM: { text: enumItem, line: 1, column: 1 } },
+	}
+}
+
+const EVENT = [
+	'pub enum Event {',
+	'    Tick,',
+	'    Packet { header: Header, payload: [u8; 1024] },',
+	'}',
+].join('\n')
+const MESSAGE = ['enum Message {', '    Quit,', '    Write(String),', '}'].join('\n')
+
+describe('parseEnumVariants', () => {
+	it('should read named and positional fields', () => {
+		const source = [
+			'enum Token {',
+			'    /// End of input, or of a {block}',
+			'    Eof,',
+			'    #[serde(rename = "n", alias = "number")]',
+			'    Number(pub u64, f64),',
+			'    Ident { name: String, span: (u32, u32) },',
+			'    Tagged = 3,',
+			'}',
+		].join('\n')
+
+		expect(parseEnumVariants(source)).toEqual([
+			{ name: 'Eof', fields: [] },
+			{
+				name: 'Number',
+				fields: [
+					{ name: '0', type: 'u64' },
+					{ name: '1', type: 'f64' },
+				],
+			},
+			{
+				name: 'Ident',
+				fields: [
+					{ name: 'name', type: 'String' },
+					{ name: 'span', type: '(u32, u32)' },
+				],
+			},
+			{ name: 'Tagged', fields: [] },
+		])
+	})
+
+	it('should keep commas inside generic arguments in one field', () => {
+		const source = 'enum Cache { Map(HashMap<String, Vec<u8>>), Empty }'
+
+		expect(parseEnumVariants(source)[0]?.fields).toEqual([
+			{ name: '0', type: 'HashMap<String, Vec<u8>>' },
+		])
+	})
+})
+
+describe('estimateTypeSize', () => {
+	it('should size primitives, references and arrays', () => {
+		expect(estimateTypeSize('u8')).toBe(1)
+		expect(estimateTypeSize("&'a Config")).toBe(8)
+		expect(estimateTypeSize('&str')).toBe(16)
+		expect(estimateTypeSize('[u8; 1_024]')).toBe(1024)
+		expect(estimateTypeSize('(u32, u64)')).toBe(12)
+	})
+
+	it('should size common standard types by their last path segment', () => {
+		expect(estimateTypeSize('String')).toBe(24)
+		expect(estimateTypeSize('std::collections::HashMap<K, V>')).toBe(48)
+		expect(estimateTypeSize('Box<Block>')).toBe(8)
+		expect(estimateTypeSize('Box<dyn Error>')).toBe(16)
+		expect(estimateTypeSize('Result<u64, String>')).toBe(32)
+	})
+
+	it('should only add a tag to options of types without a niche', () => {
+		expect(estimateTypeSize('Option<u32>')).toBe(8)
+		expect(estimateTypeSize('Option<Box<Block>>')).toBe(8)
+		expect(estimateTypeSize('Option<NonZeroU64>')).toBe(8)
+	})
+
+	it('should count unknown types and lengths as a word', () => {
+		expect(estimateTypeSize('Header')).toBe(8)
+		expect(estimateTypeSize('[u64; N]')).toBe(8)
+		expect(estimateTypeSize('fn(u8) -> u8')).toBe(8)
+	})
+})
+
+describe('applyLargeEnumVariants', () => {
+	it('should report a variant far larger than the smallest with the field to box', () => {
+		const [issue] = applyLargeEnumVariants([variant(EVENT, 'Packet')], { rules: {} })
+
+		expect(issue?.message).toBe(
+			'Variant `Packet` of `Event` takes about 1032 bytes, 129 times the smallest variant ' +
+				'`Tick`. Box its largest field, `payload`, as `Box<[u8; 1024]>`.',
+		)
+	})
+
+	it('should name positional fields by index', () => {
+		const frame = 'enum Frame {\n    Empty,\n    Data([u64; 64]),\n}'
+		const [issue] = applyLargeEnumVariants([variant(frame, 'Data')], { rules: {} })
+
+		expect(issue?.message).toContain('Box its largest field, field 0, as `Box<[u64; 64]>`.')
+	})
+
+	it('should drop variants within the ratio', () => {
+		expect(applyLargeEnumVariants([variant(MESSAGE, 'Write')], { rules: {} })).toEqual([])
+	})
+
+	it('should use max-ratio from the config', () => {
+		const config = {
+			rules: {},
+			ruleOptions: { 'no-large-enum-variant': { maxRatio: 3 } },
+		}
+
+		const [issue] = applyLargeEnumVariants([variant(MESSAGE, 'Write')], config)
+
+		expect(issue?.message).toContain('takes about 24 bytes, 3 times the smallest variant `Quit`')
+	})
+
+	it('should leave other rules alone', () => {
+		const issue = { ...variant(EVENT, 'Tick'), ruleId: 'no-dbg-macro' }
+
+		expect(applyLargeEnumVariants([issue], { rules: {} })).toEqual([issue])
+	})
+})
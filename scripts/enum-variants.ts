@@ -0,0 +1,284 @@
+import { LARGE_ENUM_VARIANT_RULE_ID, type LintConfig } from './config.ts'
+import { maskCommentsAndLiterals } from './function-length.ts'
+import type { LintIssue } from './shared.ts'
+
+// How many times the smallest variant's size a variant may be when `max-ratio` isn't set
+export const DEFAULT_MAX_VARIANT_RATIO = 16
+
+// Sizes are estimated for a 64-bit target
+const WORD = 8
+
+// Types of a known size, by the last segment of their path
+const KNOWN_SIZES: Record<string, number> = {
+	bool: 1,
+	u8: 1,
+	i8: 1,
+	u16: 2,
+	i16: 2,
+	u32: 4,
+	i32: 4,
+	f32: 4,
+	char: 4,
+	u64: 8,
+	i64: 8,
+	f64: 8,
+	usize: 8,
+	isize: 8,
+	u128: 16,
+	i128: 16,
+	String: 24,
+	Vec: 24,
+	VecDeque: 24,
+	BinaryHeap: 24,
+	BTreeMap: 24,
+	BTreeSet: 24,
+	LinkedList: 24,
+	PathBuf: 24,
+	OsString: 24,
+	Cow: 24,
+	HashMap: 48,
+	HashSet: 48,
+	Duration: 16,
+	Instant: 16,
+	SystemTime: 16,
+	PhantomData: 0,
+	PhantomPinned: 0,
+}
+
+// Pointers, which take two words when they point at something unsized
+const POINTERS = new Set(['Box', 'Rc', 'Arc', 'Weak', 'NonNull'])
+// Wrappers laid out like the type they wrap
+const TRANSPARENT = new Set([
+	'Cell',
+	'UnsafeCell',
+	'ManuallyDrop',
+	'MaybeUninit',
+	'Wrapping',
+	'Pin',
+])
+// Types with a niche, so an `Option` of them takes no extra space
+const NICHE_TYPES = new Set([...POINTERS, 'bool', 'char', 'String', 'Vec', 'PathBuf', 'OsString'])
+
+const VISIBILITY = /^pub(?:\s*\([^)]*\))?\s+/
+
+export interface EnumVariant {
+	name: string
+	fields: VariantField[]
+}
+
+export interface VariantField {
+	// The field name, or its position in a tuple variant
+	name: string
+	type: string
+}
+
+/**
+ * Read the variants of an `enum` item and the written types of their fields. Comments and
+ * attributes are skipped, and so are explicit discriminants (`A = 1`).
+ */
+export function parseEnumVariants(source: string): EnumVariant[] {
+	const masked = maskCommentsAndLiterals(source)
+	const open = masked.indexOf('{')
+	const close = masked.lastIndexOf('}')
+	if (open === -1 || close < open) return []
+
+	return splitTopLevel(stripAttributes(masked.slice(open + 1, close))).flatMap((entry) => {
+		const head = /^(?:r#)?(\w+)\s*/.exec(entry)
+		if (!head) return []
+		const rest = entry.slice(head[0].length)
+		const isStruct = rest.startsWith('{')
+		const body = isStruct || rest.startsWith('(') ? rest.slice(1, closeOf(rest)) : ''
+		const fields = splitTopLevel(body).map((part, i) => {
+			const field = part.replace(VISIBILITY, '')
+			if (!isStruct) {
+				return { name: String(i), type: normalizeType(field) }
+			}
+			const colon = field.indexOf(':')
+			return { name: field.slice(0, colon).trim(), type: normalizeType(field.slice(colon + 1)) }
+		})
+		return [{ name: head[1]!, fields }]
+	})
+}
+
+// Index of the bracket closing the one `text` starts with
+function closeOf(text: string): number {
+	let depth = 0
+	for (let i = 0; i < text.length; i++) {
+		if ('([{'.includes(text[i]!)) depth++
+		else if (')]}'.includes(text[i]!) && --depth === 0) return i
+	}
+	return text.length
+}
+
+// Remove `#[...]` attributes, which may hold commas of their own
+function stripAttributes(text: string): string {
+	let out = ''
+	let i = 0
+	while (i < text.length) {
+		const attribute = /^#\s*\[/.exec(text.slice(i))
+		if (attribute) {
+			const open = i + attribute[0].length - 1
+			i = open + closeOf(text.slice(open)) + 1
+		} else {
+			out += text[i]
+			i++
+		}
+	}
+	return out
+}
+
+// Split on commas outside brackets and generic arguments, dropping empty entries
+function splitTopLevel(text: string): string[] {
+	const parts: string[] = []
+	let depth = 0
+	let current = ''
+	for (let i = 0; i < text.length; i++) {
+		const ch = text[i]!
+		if ('([{<'.includes(ch)) {
+			depth++
+		} else if (')]}'.includes(ch) || (ch === '>' && text[i - 1] !== '-')) {
+			depth = Math.max(depth - 1, 0)
+		} else if (ch === ',' && depth === 0) {
+			parts.push(current)
+			current = ''
+			continue
+		}
+		current += ch
+	}
+	parts.push(current)
+	return parts.map((part) => part.trim()).filter((part) => part !== '')
+}
+
+function normalizeType(type: string): string {
+	return type.trim().replace(/\s+/g, ' ')
+}
+
+/**
+ * Estimate how many bytes a value of the written `type` takes, from its syntax alone. Known
+ * standard types, primitives, references, arrays with a literal length and tuples are sized;
+ * any other type, e.g. a struct declared elsewhere or a generic parameter, counts as a word.
+ * Padding is ignored.
+ */
+export function estimateTypeSize(type: string): number {
+	const written = normalizeType(type)
+
+	const reference = /^(?:&\s*(?:'\w+\s*)?(?:mut\s+)?|\*\s*(?:const|mut)\s+)/.exec(written)
+	if (reference) {
+		return isUnsized(written.slice(reference[0].length)) ? 2 * WORD : WORD
+	}
+	if (written.startsWith('[')) {
+		const inner = written.slice(1, -1)
+		const semicolon = topLevelSemicolon(inner)
+		if (semicolon === -1) return 2 * WORD
+		const length = /^(\d[\d_]*)(?:usize)?$/.exec(inner.slice(semicolon + 1).trim())
+		const element = estimateTypeSize(inner.slice(0, semicolon))
+		// A named length can't be resolved, so it counts as a single element
+		return length ? element * Number(length[1]!.replaceAll('_', '')) : element
+	}
+	if (written.startsWith('(')) {
+		return splitTopLevel(written.slice(1, -1)).reduce(
+			(total, element) => total + estimateTypeSize(element),
+			0,
+		)
+	}
+	// Literals are masked, so `extern "C" fn` reads `extern ___ fn`
+	if (/^(?:unsafe\s+)?(?:extern\s+(?:_+\s+)?)?fn\s*\(/.test(written)) {
+		return WORD
+	}
+
+	const { name, args } = splitPathType(written)
+	if (POINTERS.has(name)) {
+		return args[0] !== undefined && isUnsized(args[0]) ? 2 * WORD : WORD
+	}
+	if (TRANSPARENT.has(name) && args[0] !== undefined) {
+		return estimateTypeSize(args[0])
+	}
+	if (name === 'Option' && args[0] !== undefined) {
+		const inner = estimateTypeSize(args[0])
+		return hasNiche(args[0]) ? inner : inner + Math.max(1, Math.min(inner, WORD))
+	}
+	if (name === 'Result' && args.length === 2) {
+		return Math.max(...args.map(estimateTypeSize)) + WORD
+	}
+	return Object.hasOwn(KNOWN_SIZES, name) ? KNOWN_SIZES[name]! : WORD
+}
+
+// The last segment of a path type and its generic arguments, e.g. `HashMap` and `K`, `V`
+function splitPathType(type: string): { name: string; args: string[] } {
+	const generics = type.indexOf('<')
+	const path = (generics === -1 ? type : type.slice(0, generics)).replace(/\s+/g, '')
+	const args = generics === -1 ? [] : splitTopLevel(type.slice(generics + 1, type.lastIndexOf('>')))
+	return { name: path.split('::').pop() ?? path, args }
+}
+
+function isUnsized(type: string): boolean {
+	return type === 'str' || (type.startsWith('[') && !type.includes(';')) || /^dyn\b/.test(type)
+}
+
+function hasNiche(type: string): boolean {
+	if (type.startsWith('&')) return true
+	const { name } = splitPathType(type)
+	return NICHE_TYPES.has(name) || name.startsWith('NonZero')
+}
+
+// The `;` separating an array's element type from its length
+function topLevelSemicolon(text: string): number {
+	let depth = 0
+	let found = -1
+	for (let i = 0; i < text.length; i++) {
+		if ('([{<'.includes(text[i]!)) depth++
+		else if (')]}>'.includes(text[i]!)) depth--
+		else if (text[i] === ';' && depth === 0) found = i
+	}
+	return found
+}
+
+// Fields are laid out one after another, so a variant takes what its fields add up to
+export function estimateVariantSize(variant: EnumVariant): number {
+	return variant.fields.reduce((total, field) => total + estimateTypeSize(field.type), 0)
+}
+
+/**
+ * Finish the no-large-enum-variant rule, whose YAML matches every variant with fields: keep
+ * the ones estimated at `max-ratio` times the smallest variant or more, naming the largest
+ * field to box. Variants smaller than a word count as one, since the enum's tag takes about
+ * that much anyway.
+ */
+export function applyLargeEnumVariants(issues: LintIssue[], config: LintConfig): LintIssue[] {
+	const ratio =
+		config.ruleOptions?.[LARGE_ENUM_VARIANT_RULE_ID]?.maxRatio ?? DEFAULT_MAX_VARIANT_RATIO
+
+	return issues.flatMap((issue) => {
+		const enumItem = issue.captures?.ENUM
+		if (issue.ruleId !== LARGE_ENUM_VARIANT_RULE_ID || !enumItem || issue.code === undefined) {
+			return [issue]
+		}
+		const variants = parseEnumVariants(enumItem.text)
+		const name = /^(?:r#)?(\w+)/.exec(issue.code)?.[1]
+		const variant = variants.find((candidate) => candidate.name === name)
+		if (!variant || variant.fields.length === 0 || variants.length < 2) {
+			return []
+		}
+
+		const sizes = variants.map(estimateVariantSize)
+		const smallest = Math.min(...sizes)
+		const floor = Math.max(smallest, WORD)
+		const size = estimateVariantSize(variant)
+		if (size < ratio * floor) {
+			return []
+		}
+
+		const fieldSizes = variant.fields.map((field) => estimateTypeSize(field.type))
+		const index = fieldSizes.indexOf(Math.max(...fieldSizes))
+		const field = variant.fields[index]!
+		const label = field.name === String(index) ? `field ${index}` : `\`${field.name}\``
+		const smallestName = variants[sizes.indexOf(smallest)]!.name
+		const enumName = /\benum\s+(?:r#)?(\w+)/.exec(enumItem.text)?.[1] ?? 'the enum'
+		return [
+			{
+				...issue,
+				message: `Variant \`${variant.name}\` of \`${enumName}\` takes about ${size} bytes, ${Math.floor(size / floor)} times the smallest variant \`${smallestName}\`. Box its largest field, ${label}, as \`Box<${field.type}>\`.`,
+			},
+		]
+	})
+}
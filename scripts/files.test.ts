@@ -0,0 +1,220 @@
+import fs from 'node:fs'
+import os from 'node:os'
+import path from 'node:path'
+import { afterEach, beforeEach, describe, expect, it } from 'vitest'
+import {
+	buildGlobArgs,
+	buildIgnoreArgs,
+	dedupeScanPaths,
+	type DiscoverOptions,
+	discoverFiles,
+	getLanguageExtensions,
+	isPathPattern,
+	parseFileList,
+	splitExcludes,
+} from './files.ts'
+
+describe('isPathPattern', () => {
+	it('should treat rule ids as rules', () => {
+		expect(isPathPattern('no-dbg-macro')).toBe(false)
+		expect(isPathPattern('no-unwrap-in-lib')).toBe(false)
+	})
+
+	it('should treat globs and paths as path patterns', () => {
+		expect(isPathPattern('target/')).toBe(true)
+		expect(isPathPattern('**/generated/*.rs')).toBe(true)
+		expect(isPathPattern('*.pb.rs')).toBe(true)
+		expect(isPathPattern('build.rs')).toBe(true)
+	})
+})
+
+describe('splitExcludes', () => {
+	const ruleIds = new Set(['no-dbg-macro', 'no-emojis'])
+	let tmpDir: string
+
+	beforeEach(() => {
+		tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-excludes-'))
+		fs.mkdirSync(path.join(tmpDir, 'vendor'))
+		fs.mkdirSync(path.join(tmpDir, 'no-emojis'))
+		fs.writeFileSync(path.join(tmpDir, 'LICENSE'), '')
+	})
+
+	afterEach(() => {
+		fs.rmSync(tmpDir, { recursive: true, force: true })
+	})
+
+	it('should separate rule ids from path globs', () => {
+		const values = ['no-dbg-macro', 'vendor/**', 'no-emojis', '**/generated/*.rs']
+
+		expect(splitExcludes(values, ruleIds, tmpDir)).toEqual({
+			rules: ['no-dbg-macro', 'no-emojis'],
+			paths: ['vendor/**', '**/generated/*.rs'],
+		})
+	})
+
+	it('should treat plain words naming a directory as paths unless they are rule ids', () => {
+		const values = ['vendor', 'no-emojis', 'LICENSE', 'no-such-rule']
+
+		expect(splitExcludes(values, ruleIds, tmpDir)).toEqual({
+			rules: ['no-emojis', 'LICENSE', 'no-such-rule'],
+			paths: ['vendor'],
+		})
+	})
+})
+
+describe('buildGlobArgs', () => {
+	it('should return no arguments without globs', () => {
+		expect(buildGlobArgs({ include: [], exclude: [] })).toEqual([])
+	})
+
+	it('should put excludes after includes so they win', () => {
+		expect(buildGlobArgs({ include: ['src/**/*.rs'], exclude: ['**/generated/*.rs'] })).toEqual([
+			'--globs',
+			'src/**/*.rs',
+			'--globs',
+			'!**/generated/*.rs',
+		])
+	})
+})
+
+describe('buildIgnoreArgs', () => {
+	it('should keep ast-grep defaults when ignore files are honored', () => {
+		expect(buildIgnoreArgs(false)).toEqual([])
+	})
+
+	it('should disable every ignore file source for --no-ignore', () => {
+		expect(buildIgnoreArgs(true)).toEqual([
+			'--no-ignore',
+			'dot',
+			'--no-ignore',
+			'vcs',
+			'--no-ignore',
+			'exclude',
+			'--no-ignore',
+			'global',
+			'--no-ignore',
+			'parent',
+		])
+	})
+})
+
+describe('getLanguageExtensions', () => {
+	it('should combine every language for all', () => {
+		expect(getLanguageExtensions('rust')).toEqual(['.rs'])
+		expect(getLanguageExtensions('all')).toEqual(['.rs', '.ts', '.cts', '.mts'])
+	})
+})
+
+describe('dedupeScanPaths', () => {
+	it('should drop repeated paths and keep the given order', () => {
+		expect(dedupeScanPaths(['tests/', 'src', './src/', 'tests'], '/repo')).toEqual([
+			'tests/',
+			'src',
+		])
+	})
+
+	it('should drop paths inside another scan path', () => {
+		expect(dedupeScanPaths(['src/lib.rs', 'examples', 'src', '/repo/src/net'], '/repo')).toEqual([
+			'examples',
+			'src',
+		])
+		expect(dedupeScanPaths(['src/net', '.'], '/repo')).toEqual(['.'])
+	})
+
+	it('should keep sibling paths that share a prefix', () => {
+		const paths = ['src', 'src-gen', '..src']
+		expect(dedupeScanPaths(paths, '/repo')).toEqual(paths)
+	})
+})
+
+describe('parseFileList', () => {
+	let tmpDir: string
+
+	beforeEach(() => {
+		tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-file-list-'))
+		fs.mkdirSync(path.join(tmpDir, 'src'))
+		for (const file of ['src/lib.rs', 'src/main.rs', 'README.md']) {
+			fs.writeFileSync(path.join(tmpDir, file), '')
+		}
+	})
+
+	afterEach(() => {
+		fs.rmSync(tmpDir, { recursive: true, force: true })
+	})
+
+	it('should read one file per line and keep each once', () => {
+		const list = 'src/lib.rs\r\n\n  src/main.rs\n./src/lib.rs\n'
+
+		expect(parseFileList(list, ['.rs'], tmpDir)).toEqual({
+			files: ['src/lib.rs', 'src/main.rs'],
+			skipped: [],
+		})
+	})
+
+	it('should skip missing files, directories and other languages', () => {
+		const list = ['src/lib.rs', 'src/deleted.rs', 'src', 'README.md'].join('\n')
+
+		expect(parseFileList(list, ['.rs'], tmpDir)).toEqual({
+			files: ['src/lib.rs'],
+			skipped: [
+				{ file: 'src/deleted.rs', reason: 'no such file' },
+				{ file: 'src', reason: 'not a file' },
+				{ file: 'README.md', reason: 'not a .rs file' },
+			],
+		})
+	})
+})
+
+describe('discoverFiles', () => {
+	let tmpDir: string
+	let options: DiscoverOptions
+
+	const write = (file: string) => {
+		fs.mkdirSync(path.dirname(path.join(tmpDir, file)), { recursive: true })
+		fs.writeFileSync(path.join(tmpDir, file), 'fn main() {}\n')
+	}
+
+	beforeEach(() => {
+		tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-files-'))
+		fs.mkdirSync(path.join(tmpDir, '.git'))
+		for (const file of ['build.rs', 'src/lib.rs', 'src/gen/a.rs', 'src/main.ts', 'target/b.rs']) {
+			write(file)
+		}
+		write('.hidden/c.rs')
+		fs.writeFileSync(path.join(tmpDir, '.gitignore'), 'target/\n')
+		options = {
+			extensions: ['.rs'],
+			filter: { include: [], exclude: [] },
+			noIgnore: false,
+			cwd: tmpDir,
+		}
+	})
+
+	afterEach(() => {
+		fs.rmSync(tmpDir, { recursive: true, force: true })
+	})
+
+	it('should list matching files and skip ignored and hidden ones', () => {
+		expect(discoverFiles('.', options)).toEqual(['build.rs', 'src/gen/a.rs', 'src/lib.rs'])
+	})
+
+	it('should let a nested .ignore file take precedence', () => {
+		fs.writeFileSync(path.join(tmpDir, 'src', '.ignore'), 'gen/\n')
+
+		expect(discoverFiles('src', options)).toEqual(['src/lib.rs'])
+	})
+
+	it('should include ignored files with noIgnore', () => {
+		expect(discoverFiles('.', { ...options, noIgnore: true })).toContain('target/b.rs')
+	})
+
+	it('should apply include and exclude globs relative to cwd', () => {
+		const filter = { include: ['src/**/*.rs'], exclude: ['**/gen/**'] }
+
+		expect(discoverFiles('.', { ...options, filter })).toEqual(['src/lib.rs'])
+	})
+
+	it('should return an explicitly named file', () => {
+		expect(discoverFiles('./target/b.rs', options)).toEqual(['target/b.rs'])
+	})
+})
@@ -0,0 +1,294 @@
+import fs from 'node:fs'
+import os from 'node:os'
+import path from 'node:path'
+import {
+	compileIgnorePattern,
+	type IgnorePattern,
+	matchIgnorePatterns,
+	parseIgnoreFile,
+} from './globs.ts'
+import { LANG, type Language } from './shared.ts'
+
+// Characters that only appear in path globs, never in rule ids
+const PATH_PATTERN_CHARS = /[/*?[{.]/
+
+export interface FileFilter {
+	include: string[]
+	exclude: string[]
+}
+
+/**
+ * Whether an `--exclude` entry is a path glob rather than a rule id.
+ * Rule ids are plain kebab-case words, so anything with a path separator, wildcard
+ * or extension (e.g. `target/` or `*.pb.rs`) is treated as a glob.
+ */
+export function isPathPattern(value: string): boolean {
+	return PATH_PATTERN_CHARS.test(value)
+}
+
+/**
+ * Split `--exclude` entries into rule ids and path globs.
+ * A plain word that isn't one of `ruleIds` but names a directory under `cwd`, like `vendor`,
+ * is a path too; `--exclude-path` takes paths that could pass for rule ids.
+ */
+export function splitExcludes(
+	values: string[],
+	ruleIds: Set<string>,
+	cwd: string = process.cwd(),
+): { rules: string[]; paths: string[] } {
+	const isDirectory = (value: string) =>
+		fs.statSync(path.resolve(cwd, value), { throwIfNoEntry: false })?.isDirectory() === true
+
+	const rules: string[] = []
+	const paths: string[] = []
+	for (const value of values) {
+		if (isPathPattern(value) || (!ruleIds.has(value) && isDirectory(value))) {
+			paths.push(value)
+		} else {
+			rules.push(value)
+		}
+	}
+	return { rules, paths }
+}
+
+/**
+ * Build ast-grep `--globs` arguments for the file filter.
+ * ast-grep matches these like gitignore patterns relative to the working directory, and a later
+ * glob wins over an earlier one, so excludes go last to take precedence over includes.
+ */
+export function buildGlobArgs(filter: FileFilter): string[] {
+	const globs = [...filter.include, ...filter.exclude.map((glob) => `!${glob}`)]
+	return globs.flatMap((glob) => ['--globs', glob])
+}
+
+// ast-grep ignore sources turned off by `--no-ignore`; like ripgrep, hidden files stay skipped
+const IGNORE_SOURCES = ['dot', 'vcs', 'exclude', 'global', 'parent'] as const
+
+/**
+ * Build ast-grep arguments controlling ignore files.
+ * By default ast-grep walks directories the way ripgrep does, honoring `.gitignore`,
+ * `.ignore` and nested ignore files; `--no-ignore` turns all of them off. Explicit
+ * exclude globs still apply either way.
+ */
+export function buildIgnoreArgs(noIgnore: boolean): string[] {
+	if (!noIgnore) {
+		return []
+	}
+	return IGNORE_SOURCES.flatMap((source) => ['--no-ignore', source])
+}
+
+// File extensions ast-grep associates with each language
+const LANGUAGE_EXTENSIONS: Record<Exclude<Language, typeof LANG.ALL>, string[]> = {
+	[LANG.RUST]: ['.rs'],
+	[LANG.TYPESCRIPT]: ['.ts', '.cts', '.mts'],
+}
+
+export function getLanguageExtensions(language: Language): string[] {
+	if (language === LANG.ALL) {
+		return Object.values(LANGUAGE_EXTENSIONS).flat()
+	}
+	return LANGUAGE_EXTENSIONS[language]
+}
+
+/**
+ * Drop scan paths that repeat or lie inside another scan path, so no file is scanned twice.
+ * The rest keep the order they were given in.
+ */
+export function dedupeScanPaths(scanPaths: string[], cwd: string): string[] {
+	const resolved = scanPaths.map((scanPath) => path.resolve(cwd, scanPath))
+	return scanPaths
+		.filter((_, i) =>
+			resolved.every((other, j) => {
+				if (other === resolved[i]) return j >= i
+				const relative = path.relative(other, resolved[i]!)
+				const outside = relative === '..' || relative.startsWith(`..${path.sep}`)
+				return outside || path.isAbsolute(relative)
+			}),
+		)
+		.map((scanPath) => path.normalize(scanPath))
+}
+
+export interface FileList {
+	files: string[]
+	// Entries left out, each with why
+	skipped: Array<{ file: string; reason: string }>
+}
+
+/**
+ * Read a newline-separated list of files to lint, e.g. from `git diff --cached --name-only`.
+ * Blank lines are ignored and repeated files kept once. Entries that don't exist, aren't
+ * files or don't have one of `extensions` are skipped rather than treated as errors, so a
+ * list of mixed changes can be passed as-is.
+ */
+export function parseFileList(content: string, extensions: string[], cwd: string): FileList {
+	const files: string[] = []
+	const skipped: FileList['skipped'] = []
+	for (const line of content.split('\n')) {
+		const file = line.trim()
+		if (file === '') continue
+
+		const stat = fs.statSync(path.resolve(cwd, file), { throwIfNoEntry: false })
+		if (stat === undefined) {
+			skipped.push({ file, reason: 'no such file' })
+		} else if (!stat.isFile()) {
+			skipped.push({ file, reason: 'not a file' })
+		} else if (!extensions.includes(path.extname(file))) {
+			skipped.push({ file, reason: `not a ${extensions.join(' or ')} file` })
+		} else {
+			files.push(file)
+		}
+	}
+	return { files: dedupeScanPaths(files, cwd), skipped }
+}
+
+export interface DiscoverOptions {
+	extensions: string[]
+	filter: FileFilter
+	noIgnore: boolean
+	// Directory that include/exclude globs are relative to
+	cwd: string
+}
+
+// Patterns from one ignore file, relative to the directory containing it
+interface IgnoreFile {
+	base: string
+	patterns: IgnorePattern[]
+}
+
+/**
+ * List the files ast-grep would scan under `scanPath`, sorted by path.
+ * Mirrors ast-grep's (ripgrep's) walk: include/exclude globs first, then `.ignore`,
+ * `.gitignore` (inside a git repository), `.git/info/exclude` and the global git excludes
+ * file, with deeper ignore files taking precedence. Hidden entries are skipped and
+ * symlinks are not followed. Returned paths start with `scanPath`.
+ */
+export function discoverFiles(scanPath: string, options: DiscoverOptions): string[] {
+	const absolute = path.resolve(options.cwd, scanPath)
+	const stat = fs.statSync(absolute)
+	const hasExtension = (file: string) => options.extensions.includes(path.extname(file))
+
+	// Like ripgrep, a file named explicitly is scanned regardless of ignore rules
+	if (!stat.isDirectory()) {
+		return hasExtension(absolute) ? [path.normalize(scanPath)] : []
+	}
+
+	const overrides = [
+		...options.filter.include.map((glob) => compileIgnorePattern(glob)),
+		...options.filter.exclude.map((glob) => compileIgnorePattern(`!${glob}`)),
+	].filter((pattern): pattern is IgnorePattern => pattern !== null)
+	const hasIncludes = options.filter.include.length > 0
+
+	const repoRoot = findGitRoot(absolute)
+	const ignoreFiles = options.noIgnore ? [] : loadParentIgnoreFiles(absolute, repoRoot)
+	const files: string[] = []
+
+	const walk = (dir: string, relative: string, inherited: IgnoreFile[]) => {
+		const ignores = options.noIgnore
+			? inherited
+			: [...inherited, ...loadDirIgnoreFiles(dir, repoRoot !== null)]
+
+		const entries = fs.readdirSync(dir, { withFileTypes: true })
+		for (const entry of entries) {
+			if (entry.name.startsWith('.') || entry.isSymbolicLink()) continue
+
+			const entryPath = path.join(dir, entry.name)
+			const entryRelative = relative === '' ? entry.name : path.join(relative, entry.name)
+			const isDir = entry.isDirectory()
+			if (!isDir && !entry.isFile()) continue
+
+			const verdict = matchOverrides(overrides, hasIncludes, entryPath, options.cwd, isDir)
+			if (verdict === 'ignore') continue
+			if (verdict === null && isIgnored(ignores, entryPath, isDir)) continue
+
+			if (isDir) {
+				walk(entryPath, entryRelative, ignores)
+			} else if (hasExtension(entry.name)) {
+				files.push(path.join(scanPath, entryRelative))
+			}
+		}
+	}
+
+	walk(absolute, '', ignoreFiles)
+	return files.sort((a, b) => (a === b ? 0 : a < b ? -1 : 1))
+}
+
+// Include/exclude globs win over ignore files. With includes set, files matching none are skipped;
+// directories are still descended into so nested matches are found.
+function matchOverrides(
+	overrides: IgnorePattern[],
+	hasIncludes: boolean,
+	absolute: string,
+	cwd: string,
+	isDir: boolean,
+): 'ignore' | 'include' | null {
+	if (overrides.length === 0) return null
+
+	const relative = toSlashPath(path.relative(cwd, absolute))
+	// Overrides are compiled so a match on an include glob is 'ignore' and on an exclude is
+	// 'include'; flip them back
+	const match = matchIgnorePatterns(overrides, relative, isDir)
+	if (match === 'ignore') return 'include'
+	if (match === 'include') return 'ignore'
+	return hasIncludes && !isDir ? 'ignore' : null
+}
+
+function isIgnored(ignores: IgnoreFile[], absolute: string, isDir: boolean): boolean {
+	// The innermost ignore file with a matching pattern decides
+	for (let i = ignores.length - 1; i >= 0; i--) {
+		const file = ignores[i]!
+		const relative = path.relative(file.base, absolute)
+		if (relative.startsWith('..')) continue
+		const match = matchIgnorePatterns(file.patterns, toSlashPath(relative), isDir)
+		if (match !== null) return match === 'ignore'
+	}
+	return false
+}
+
+// Ignore files in `dir`, ordered so `.ignore` takes precedence over `.gitignore`
+function loadDirIgnoreFiles(dir: string, inGitRepo: boolean): IgnoreFile[] {
+	const names = inGitRepo ? ['.gitignore', '.ignore'] : ['.ignore']
+	return names.flatMap((name) => readIgnoreFile(path.join(dir, name), dir))
+}
+
+// Global excludes and ignore files from directories above the scan root
+function loadParentIgnoreFiles(scanDir: string, repoRoot: string | null): IgnoreFile[] {
+	const files: IgnoreFile[] = []
+
+	if (repoRoot !== null) {
+		const configHome = process.env.XDG_CONFIG_HOME || path.join(os.homedir(), '.config')
+		files.push(...readIgnoreFile(path.join(configHome, 'git', 'ignore'), repoRoot))
+		files.push(...readIgnoreFile(path.join(repoRoot, '.git', 'info', 'exclude'), repoRoot))
+	}
+
+	const parents: string[] = []
+	for (let dir = path.dirname(scanDir); dir !== path.dirname(dir); dir = path.dirname(dir)) {
+		parents.unshift(dir)
+	}
+	for (const dir of parents) {
+		const inRepo = repoRoot !== null && !path.relative(repoRoot, dir).startsWith('..')
+		files.push(...loadDirIgnoreFiles(dir, inRepo))
+	}
+
+	return files
+}
+
+function readIgnoreFile(file: string, base: string): IgnoreFile[] {
+	let content: string
+	try {
+		content = fs.readFileSync(file, 'utf8')
+	} catch {
+		return []
+	}
+	return [{ base, patterns: parseIgnoreFile(content) }]
+}
+
+function findGitRoot(startDir: string): string | null {
+	for (let dir = startDir; ; dir = path.dirname(dir)) {
+		if (fs.existsSync(path.join(dir, '.git'))) return dir
+		if (dir === path.dirname(dir)) return null
+	}
+}
+
+function toSlashPath(value: string): string {
+	return value.split(path.sep).join('/')
+}
@@ -0,0 +1,117 @@
+import fs from 'node:fs'
+import os from 'node:os'
+import path from 'node:path'
+import { afterEach, describe, expect, it } from 'vitest'
+import { applyFixes, applyFixesToSource } from './fix.ts'
+import type { IssueFix, LintIssue } from './shared.ts'
+
+// Build a fix replacing the first occurrence of `target` in `source`
+function fixFor(source: string, target: string, replacement: string): IssueFix {
+	const start = Buffer.from(source.slice(0, source.indexOf(target))).length
+	return { start, end: start + Buffer.from(target).length, replacement }
+}
+
+describe('applyFixesToSource', () => {
+	it('should replace the span and keep surrounding formatting', () => {
+		const source = 'fn main() {\n    let v = dbg!(compute());\n}\n'
+		const { output, applied } = applyFixesToSource(source, [
+			fixFor(source, 'dbg!(compute())', 'compute()'),
+		])
+
+		expect(output).toBe('fn main() {\n    let v = compute();\n}\n')
+		expect(applied).toHaveLength(1)
+	})
+
+	it('should delete lines left holding an empty statement', () => {
+		const source = 'fn main() {\n    let x = 1;\n    dbg!();\n    x\n}\n'
+		const { output } = applyFixesToSource(source, [fixFor(source, 'dbg!()', '()')])
+
+		expect(output).toBe('fn main() {\n    let x = 1;\n    x\n}\n')
+	})
+
+	it('should delete lines a fix left blank', () => {
+		const source = 'fn stop() {\n    cleanup();\n    return;\n}\n'
+		const { output } = applyFixesToSource(source, [fixFor(source, 'return;', '')])
+
+		expect(output).toBe('fn stop() {\n    cleanup();\n}\n')
+	})
+
+	it('should apply multiple fixes in one file', () => {
+		const source = 'let a = dbg!(x);\nlet b = dbg!(y);\n'
+		const { output, applied } = applyFixesToSource(source, [
+			fixFor(source, 'dbg!(x)', 'x'),
+			fixFor(source, 'dbg!(y)', 'y'),
+		])
+
+		expect(output).toBe('let a = x;\nlet b = y;\n')
+		expect(applied).toHaveLength(2)
+	})
+
+	it('should use byte offsets for multibyte text', () => {
+		const source = 'let s = "été"; dbg!(s);\n'
+		const { output } = applyFixesToSource(source, [fixFor(source, 'dbg!(s)', 's')])
+
+		expect(output).toBe('let s = "été"; s;\n')
+	})
+
+	it('should skip fixes overlapping an applied fix', () => {
+		const source = 'dbg!(dbg!(x));\n'
+		const outer = fixFor(source, 'dbg!(dbg!(x))', 'dbg!(x)')
+		const inner = fixFor(source, 'dbg!(x)', 'x')
+		const { output, applied } = applyFixesToSource(source, [outer, inner])
+
+		// The inner fix comes later in the file and wins; the outer one is left for the next run
+		expect(output).toBe('dbg!(x);\n')
+		expect(applied).toEqual([inner])
+	})
+
+	it('should be a no-op without fixes', () => {
+		const source = 'let x = 1;\n'
+
+		expect(applyFixesToSource(source, []).output).toBe(source)
+	})
+})
+
+describe('applyFixes', () => {
+	let tmpDir: string
+
+	afterEach(() => {
+		if (tmpDir) {
+			fs.rmSync(tmpDir, { recursive: true, force: true })
+		}
+	})
+
+	it('should write fixed files and report what is left', () => {
+		tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-fix-'))
+		const file = path.join(tmpDir, 'lib.rs')
+		const source = 'fn f() -> u32 {\n    dbg!(1) + s.parse().unwrap()\n}\n'
+		fs.writeFileSync(file, source)
+
+		const issues: LintIssue[] = [
+			{
+				ruleId: 'no-dbg-macro',
+				severity: 'error',
+				message: 'Remove dbg!()',
+				file,
+				line: 2,
+				column: 5,
+				fix: fixFor(source, 'dbg!(1)', '1'),
+			},
+			{
+				ruleId: 'no-unwrap-in-lib',
+				severity: 'warning',
+				message: 'Avoid .unwrap()',
+				file,
+				line: 2,
+				column: 15,
+			},
+		]
+
+		const result = applyFixes(issues)
+
+		expect(result.applied).toBe(1)
+		expect(result.filesChanged).toBe(1)
+		expect(result.remaining.map((issue) => issue.ruleId)).toEqual(['no-unwrap-in-lib'])
+		expect(fs.readFileSync(file, 'utf8')).toBe('fn f() -> u32 {\n    1 + s.parse().unwrap()\n}\n')
+	})
+})
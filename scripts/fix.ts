@@ -0,0 +1,104 @@
+import fs from 'node:fs'
+import { groupByFile, type IssueFix, type LintIssue, warn } from './shared.ts'
+
+export interface FixResult {
+	// Issues that are still present after fixing (no fix available, or the fix was skipped)
+	remaining: LintIssue[]
+	applied: number
+	filesChanged: number
+}
+
+// A line left holding nothing but an empty or unit statement, or nothing at all, once a fix
+// removed its expression or statement
+const EMPTY_STATEMENT_LINE = /^[ \t]*((\(\))?;)?[ \t]*$/
+
+/**
+ * Apply byte-range fixes to a source file's contents.
+ * Fixes that overlap an already applied fix are skipped so only spans we're sure
+ * about are touched; running again picks them up once the outer fix is gone.
+ * If a fix leaves its line blank or holding just `;` or `();` (e.g. a removed
+ * `dbg!();` or `return;`), the whole line is deleted.
+ */
+export function applyFixesToSource(
+	source: string,
+	fixes: IssueFix[],
+): { output: string; applied: IssueFix[] } {
+	let buffer: Buffer = Buffer.from(source, 'utf8')
+	const applied: IssueFix[] = []
+
+	// Apply from the end of the file so earlier offsets stay valid
+	const sorted = [...fixes].sort((a, b) => b.start - a.start || b.end - a.end)
+	let lowestStart = Number.POSITIVE_INFINITY
+
+	for (const fix of sorted) {
+		if (fix.end > lowestStart || fix.start > fix.end || fix.end > buffer.length) {
+			continue
+		}
+
+		buffer = Buffer.concat([
+			buffer.subarray(0, fix.start),
+			Buffer.from(fix.replacement, 'utf8'),
+			buffer.subarray(fix.end),
+		])
+		buffer = removeEmptyStatementLine(buffer, fix.start)
+		lowestStart = fix.start
+		applied.push(fix)
+	}
+
+	return { output: buffer.toString('utf8'), applied }
+}
+
+function removeEmptyStatementLine(buffer: Buffer, offset: number): Buffer {
+	const newline = 0x0a
+	const lineStart = offset === 0 ? 0 : buffer.lastIndexOf(newline, offset - 1) + 1
+	const nextNewline = buffer.indexOf(newline, offset)
+	const lineEnd = nextNewline === -1 ? buffer.length : nextNewline
+
+	const line = buffer.subarray(lineStart, lineEnd).toString('utf8').replace(/\r$/, '')
+	if (!EMPTY_STATEMENT_LINE.test(line)) {
+		return buffer
+	}
+
+	const removeEnd = nextNewline === -1 ? lineEnd : nextNewline + 1
+	return Buffer.concat([buffer.subarray(0, lineStart), buffer.subarray(removeEnd)])
+}
+
+/**
+ * Apply the auto-fixes attached to `issues` to the files on disk.
+ * Fixing is idempotent: once applied, the fixed code no longer matches the rule.
+ */
+export function applyFixes(issues: LintIssue[]): FixResult {
+	const remaining: LintIssue[] = []
+	let applied = 0
+	let filesChanged = 0
+
+	for (const [file, fileIssues] of Object.entries(groupByFile(issues))) {
+		const fixable = fileIssues.filter((issue) => issue.fix)
+		if (fixable.length === 0) {
+			remaining.push(...fileIssues)
+			continue
+		}
+
+		let source: string
+		try {
+			source = fs.readFileSync(file, 'utf8')
+		} catch (err) {
+			warn(`Could not read ${file} to apply fixes: ${(err as Error).message}`)
+			remaining.push(...fileIssues)
+			continue
+		}
+
+		const result = applyFixesToSource(source, fixable.map((issue) => issue.fix!))
+		const appliedFixes = new Set(result.applied)
+
+		if (result.applied.length > 0) {
+			fs.writeFileSync(file, result.output)
+			applied += result.applied.length
+			filesChanged++
+		}
+
+		remaining.push(...fileIssues.filter((issue) => !issue.fix || !appliedFixes.has(issue.fix)))
+	}
+
+	return { remaining, applied, filesChanged }
+}
@@ -0,0 +1,385 @@
+import { describe, expect, it } from 'vitest'
+import {
+	buildSummary,
+	type FormatContext,
+	formatSnippet,
+	getFormatter,
+	githubFormatter,
+	isValidOutputFormat,
+	JSON_SCHEMA_VERSION,
+	jsonArrayFormatter,
+	jsonFormatter,
+	OUTPUT_FORMAT,
+	parseJsonReport,
+	resolveColor,
+	textFormatter,
+} from './formatters.ts'
+import { sarifFormatter } from './sarif.ts'
+import type { LintIssue } from './shared.ts'
+
+const context: FormatContext = { rules: [], rootDir: '/repo', toolVersion: '0.0.0' }
+
+const sampleIssues: LintIssue[] = [
+	{
+		ruleId: 'no-dbg-macro',
+		severity: 'error',
+		message: 'Remove dbg!() macro before committing.',
+		file: 'src/lib.rs',
+		line: 3,
+		column: 5,
+		endLine: 5,
+		endColumn: 7,
+		byteOffset: { start: 40, end: 68 },
+		code: 'dbg!(x);',
+	},
+	{
+		ruleId: 'no-unwrap-in-lib',
+		severity: 'warning',
+		message: 'Avoid .unwrap() in library code.',
+		file: 'src/config.rs',
+		line: 12,
+		column: 9,
+	},
+]
+
+const summaryContext: FormatContext = { ...context, summary: buildSummary(sampleIssues, 10) }
+
+describe('isValidOutputFormat', () => {
+	it('should accept known formats', () => {
+		expect(isValidOutputFormat('text')).toBe(true)
+		expect(isValidOutputFormat('json')).toBe(true)
+		expect(isValidOutputFormat('json-array')).toBe(true)
+		expect(isValidOutputFormat('github')).toBe(true)
+		expect(isValidOutputFormat('sarif')).toBe(true)
+	})
+
+	it('should reject unknown formats', () => {
+		expect(isValidOutputFormat('xml')).toBe(false)
+		expect(isValidOutputFormat('')).toBe(false)
+		expect(isValidOutputFormat(undefined)).toBe(false)
+	})
+})
+
+describe('getFormatter', () => {
+	it('should return the formatter for each format', () => {
+		expect(getFormatter(OUTPUT_FORMAT.TEXT)).toBe(textFormatter)
+		expect(getFormatter(OUTPUT_FORMAT.JSON)).toBe(jsonFormatter)
+		expect(getFormatter(OUTPUT_FORMAT.JSON_ARRAY)).toBe(jsonArrayFormatter)
+		expect(getFormatter(OUTPUT_FORMAT.GITHUB)).toBe(githubFormatter)
+		expect(getFormatter(OUTPUT_FORMAT.SARIF)).toBe(sarifFormatter)
+	})
+})
+
+describe('jsonFormatter', () => {
+	it('should emit a versioned report', () => {
+		const report = JSON.parse(jsonFormatter.format(sampleIssues, context))
+
+		expect(report.schema_version).toBe(JSON_SCHEMA_VERSION)
+		expect(report.findings).toHaveLength(2)
+		expect(report.findings[0]).toEqual({
+			rule_id: 'no-dbg-macro',
+			severity: 'error',
+			message: 'Remove dbg!() macro before committing.',
+			file: 'src/lib.rs',
+			line: 3,
+			column: 5,
+			end_line: 5,
+			end_column: 7,
+			byte_offset: { start: 40, end: 68 },
+			code: 'dbg!(x);',
+		})
+	})
+
+	it('should leave out the span when the end is unknown', () => {
+		const report = JSON.parse(jsonFormatter.format(sampleIssues, context))
+
+		expect(Object.keys(report.findings[1])).toEqual([
+			'rule_id',
+			'severity',
+			'message',
+			'file',
+			'line',
+			'column',
+		])
+	})
+
+	it('should emit an empty findings array when there are no issues', () => {
+		const report = JSON.parse(jsonFormatter.format([], context))
+
+		expect(report).toEqual({ schema_version: JSON_SCHEMA_VERSION, findings: [] })
+	})
+
+	it('should include the summary when one is given', () => {
+		const report = JSON.parse(jsonFormatter.format(sampleIssues, summaryContext))
+
+		expect(report.summary).toEqual({
+			total: 2,
+			by_severity: { error: 1, warning: 1, hint: 0 },
+			by_rule: { 'no-dbg-macro': 1, 'no-unwrap-in-lib': 1 },
+			files_scanned: 10,
+			files_with_findings: 2,
+		})
+	})
+
+	it('should print a single line in compact mode', () => {
+		const output = jsonFormatter.format(sampleIssues, { ...context, compact: true })
+
+		expect(output).not.toContain('\n')
+		expect(JSON.parse(output)).toEqual(JSON.parse(jsonFormatter.format(sampleIssues, context)))
+	})
+})
+
+describe('jsonArrayFormatter', () => {
+	it('should emit a bare array of findings with 0-based positions', () => {
+		const findings = JSON.parse(jsonArrayFormatter.format(sampleIssues, summaryContext))
+
+		expect(findings).toEqual([
+			{
+				ruleId: 'no-dbg-macro',
+				severity: 'error',
+				message: 'Remove dbg!() macro before committing.',
+				file: 'src/lib.rs',
+				line: 2,
+				column: 4,
+				code: 'dbg!(x);',
+			},
+			{
+				ruleId: 'no-unwrap-in-lib',
+				severity: 'warning',
+				message: 'Avoid .unwrap() in library code.',
+				file: 'src/config.rs',
+				line: 11,
+				column: 8,
+			},
+		])
+	})
+
+	it('should emit [] when there are no issues', () => {
+		expect(jsonArrayFormatter.format([], context)).toBe('[]')
+	})
+})
+
+describe('textFormatter', () => {
+	it('should render one diagnostic per issue', () => {
+		const output = textFormatter.format(sampleIssues, context)
+
+		expect(output).toContain('error[no-dbg-macro]: Remove dbg!() macro before committing.')
+		expect(output).toContain('  --> src/lib.rs:3:5-5:7')
+		expect(output).toContain('warning[no-unwrap-in-lib]: Avoid .unwrap() in library code.')
+		expect(output).toContain('  --> src/config.rs:12:9\n')
+	})
+
+	it('should render nothing when there are no issues', () => {
+		expect(textFormatter.format([], context)).toBe('')
+	})
+
+	it('should highlight severity, rule id and location with color', () => {
+		const output = textFormatter.format(sampleIssues, { ...context, color: true })
+
+		expect(output).toContain(
+			'\x1b[1;31merror\x1b[0m[\x1b[1mno-dbg-macro\x1b[0m]: Remove dbg!() macro before committing.',
+		)
+		expect(output).toContain('  --> \x1b[34msrc/lib.rs:3:5-5:7\x1b[0m\n')
+		expect(output).toContain('\x1b[1;33mwarning\x1b[0m')
+	})
+
+	it('should not change the plain output when color is off', () => {
+		const plain = textFormatter.format(sampleIssues, context)
+
+		expect(textFormatter.format(sampleIssues, { ...context, color: false })).toBe(plain)
+		expect(plain).not.toContain('\x1b[')
+	})
+
+	it('should show the source of findings with a span when snippets are on', () => {
+		const source = 'fn main() {\n    let x = 1;\n    dbg!(x);\n}\n'
+		const issue = { ...sampleIssues[0]!, endLine: 3, endColumn: 12 }
+		const readSource = (file: string) => (file === 'src/lib.rs' ? source : null)
+
+		const output = textFormatter.format([issue, sampleIssues[1]!], {
+			...context,
+			snippets: { context: 0, readSource },
+		})
+
+		expect(output).toContain('  --> src/lib.rs:3:5-3:12\n  |\n3 |     dbg!(x);\n  |     ^^^^^^^\n')
+		expect(output).toContain('  --> src/config.rs:12:9\n')
+		expect(output).not.toContain('12 |')
+	})
+
+	it('should end with a summary footer', () => {
+		const output = textFormatter.format(sampleIssues, summaryContext)
+
+		expect(output).toMatch(
+			/\nSummary: 2 findings \(1 error, 1 warning, 0 hints\) in 2 of 10 files scanned\n {2}no-dbg-macro: 1\n {2}no-unwrap-in-lib: 1\n$/,
+		)
+	})
+
+	it('should summarize a clean run', () => {
+		const output = textFormatter.format([], { ...context, summary: buildSummary([], 1) })
+
+		expect(output).toBe('No findings in 1 file scanned.\n')
+	})
+})
+
+describe('formatSnippet', () => {
+	const source = [
+		'fn total(items: &[u32]) -> u32 {',
+		'\tlet sum = items.iter().sum();',
+		'\tsum',
+		'}',
+	].join('\n')
+	const issue: LintIssue = {
+		ruleId: 'no-redundant-return',
+		severity: 'warning',
+		message: 'Use a tail expression.',
+		file: 'src/lib.rs',
+		line: 2,
+		column: 12,
+		endLine: 2,
+		endColumn: 30,
+	}
+
+	it('should underline the span and keep tabs in the indent', () => {
+		expect(formatSnippet(issue, source, 0)).toBe(
+			'  |\n2 | \tlet sum = items.iter().sum();\n  | \t          ^^^^^^^^^^^^^^^^^^\n',
+		)
+	})
+
+	it('should clamp the context to the start and end of the file', () => {
+		const top = { ...issue, line: 1, column: 1, endLine: 1, endColumn: 3 }
+		const bottom = { ...issue, line: 4, column: 1, endLine: 4, endColumn: 2 }
+
+		const first = formatSnippet(top, source, 2)
+		const last = formatSnippet(bottom, source, 2)
+
+		expect(first).toBe(
+			[
+				'  |',
+				'1 | fn total(items: &[u32]) -> u32 {',
+				'  | ^^',
+				'2 | \tlet sum = items.iter().sum();',
+				'3 | \tsum',
+				'',
+			].join('\n'),
+		)
+		expect(last).toBe(
+			['  |', '2 | \tlet sum = items.iter().sum();', '3 | \tsum', '4 | }', '  | ^', ''].join('\n'),
+		)
+	})
+
+	it('should underline a multi-line span to the end of its first line', () => {
+		const span = { ...issue, line: 1, column: 1, endLine: 4, endColumn: 2 }
+
+		expect(formatSnippet(span, source, 0)).toContain(`  | ${'^'.repeat(32)}\n`)
+	})
+
+	it('should widen the gutter for the longest line number', () => {
+		const long = Array.from({ length: 12 }, (_, i) => `line ${i + 1}`).join('\n')
+
+		const span = { ...issue, line: 10, column: 1, endLine: 10, endColumn: 5 }
+
+		const output = formatSnippet(span, long, 1)
+
+		expect(output).toBe('   |\n 9 | line 9\n10 | line 10\n   | ^^^^\n11 | line 11\n')
+	})
+
+	it('should render nothing for a line the source does not have', () => {
+		expect(formatSnippet({ ...issue, line: 9 }, source, 1)).toBe('')
+	})
+
+	it('should color the gutter and the underline', () => {
+		const output = formatSnippet(issue, source, 0, true)
+
+		expect(output).toContain('\x1b[34m2 |\x1b[0m \tlet sum')
+		expect(output).toContain('\x1b[34m  |\x1b[0m \t          \x1b[1;33m^^^^')
+	})
+})
+
+describe('resolveColor', () => {
+	it('should color a terminal unless NO_COLOR is set', () => {
+		expect(resolveColor('auto', true, {})).toBe(true)
+		expect(resolveColor('auto', false, {})).toBe(false)
+		expect(resolveColor('auto', true, { NO_COLOR: '1' })).toBe(false)
+		expect(resolveColor('auto', true, { NO_COLOR: '' })).toBe(true)
+	})
+
+	it('should let always and never win over the terminal and NO_COLOR', () => {
+		expect(resolveColor('always', false, { NO_COLOR: '1' })).toBe(true)
+		expect(resolveColor('never', true, {})).toBe(false)
+	})
+})
+
+describe('githubFormatter', () => {
+	it('should emit workflow annotations', () => {
+		const output = githubFormatter.format(sampleIssues, context)
+
+		expect(output).toContain(
+			'::error file=src/lib.rs,line=3,col=5,endLine=5::no-dbg-macro: Remove dbg!() macro before committing.',
+		)
+		expect(output).toContain(
+			'::warning file=src/config.rs,line=12,col=9::no-unwrap-in-lib: Avoid .unwrap() in library code.',
+		)
+		expect(output).toContain('Total issues: 2')
+	})
+
+	it('should convert single-line spans to an inclusive end column', () => {
+		const issue: LintIssue = { ...sampleIssues[1]!, endLine: 12, endColumn: 18 }
+
+		expect(githubFormatter.format([issue], context)).toContain(
+			'::warning file=src/config.rs,line=12,col=9,endLine=12,endColumn=17::',
+		)
+	})
+
+	it('should add file and per-rule counts from the summary', () => {
+		const output = githubFormatter.format(sampleIssues, summaryContext)
+
+		expect(output).toContain(
+			'Files with findings: 2 of 10\n  no-dbg-macro: 1\n  no-unwrap-in-lib: 1\n',
+		)
+	})
+
+	it('should report success when there are no issues', () => {
+		expect(githubFormatter.format([], context)).toContain('No lint issues found!')
+	})
+})
+
+describe('buildSummary', () => {
+	it('should order rules by count, then by id', () => {
+		const issues = [sampleIssues[1]!, sampleIssues[0]!, { ...sampleIssues[1]!, line: 20 }]
+		const summary = buildSummary(issues, 3)
+
+		expect(summary.rules).toEqual([
+			['no-unwrap-in-lib', 2],
+			['no-dbg-macro', 1],
+		])
+		expect(summary.filesWithFindings).toBe(2)
+		expect(summary.severity).toEqual({ error: 1, warning: 2, hint: 0 })
+	})
+})
+
+describe('parseJsonReport', () => {
+	it('should round-trip the JSON formatter output', () => {
+		const { issues, error } = parseJsonReport(jsonFormatter.format(sampleIssues, context))
+
+		expect(error).toBeNull()
+		expect(issues).toEqual(sampleIssues)
+	})
+
+	it('should return error for invalid JSON', () => {
+		const { issues, error } = parseJsonReport('not json')
+
+		expect(error).toContain('Failed to parse JSON')
+		expect(issues).toHaveLength(0)
+	})
+
+	it('should return error for a bare array', () => {
+		const { error } = parseJsonReport('[]')
+
+		expect(error).toContain('findings array')
+	})
+
+	it('should return error for an unknown schema version', () => {
+		const { error } = parseJsonReport(JSON.stringify({ schema_version: 999, findings: [] }))
+
+		expect(error).toContain('Unsupported report schema_version 999')
+	})
+})
@@ -0,0 +1,459 @@
+import { sarifFormatter } from './sarif.ts'
+import {
+	countBySeverity,
+	type LintIssue,
+	pluralize,
+	type RuleMetadata,
+	type SeverityCounts,
+} from './shared.ts'
+
+export const OUTPUT_FORMAT = {
+	TEXT: 'text',
+	JSON: 'json',
+	JSON_ARRAY: 'json-array',
+	GITHUB: 'github',
+	SARIF: 'sarif',
+} as const
+
+export const VALID_OUTPUT_FORMATS = [
+	OUTPUT_FORMAT.TEXT,
+	OUTPUT_FORMAT.JSON,
+	OUTPUT_FORMAT.JSON_ARRAY,
+	OUTPUT_FORMAT.GITHUB,
+	OUTPUT_FORMAT.SARIF,
+] as const
+export type OutputFormat = (typeof VALID_OUTPUT_FORMATS)[number]
+
+// When the text format is colored (`--color`)
+export const COLOR_MODE = {
+	AUTO: 'auto',
+	ALWAYS: 'always',
+	NEVER: 'never',
+} as const
+
+export const VALID_COLOR_MODES = [COLOR_MODE.AUTO, COLOR_MODE.ALWAYS, COLOR_MODE.NEVER] as const
+export type ColorMode = (typeof VALID_COLOR_MODES)[number]
+
+/**
+ * Version of the `--format json` report schema.
+ * Bump this whenever a field is removed, renamed or changes meaning.
+ */
+export const JSON_SCHEMA_VERSION = 1
+
+// Stable JSON report format (`--format json`)
+export interface JsonFinding {
+	rule_id: string
+	severity: string
+	message: string
+	file: string
+	line: number
+	column: number
+	// 1-based, pointing just past the last character of the finding
+	end_line?: number
+	end_column?: number
+	// 0-based byte offsets into the file, end exclusive
+	byte_offset?: { start: number; end: number }
+	code?: string
+}
+
+export interface JsonSummary {
+	total: number
+	by_severity: SeverityCounts
+	by_rule: Record<string, number>
+	files_scanned: number
+	files_with_findings: number
+}
+
+export interface JsonReport {
+	schema_version: number
+	findings: JsonFinding[]
+	// Left out with --no-summary
+	summary?: JsonSummary
+}
+
+// One finding as printed by `--json` (`--format json-array`), positions 0-based
+export interface JsonArrayFinding {
+	ruleId: string
+	severity: string
+	message: string
+	file: string
+	line: number
+	column: number
+	code?: string
+}
+
+// Totals printed after the findings, so progress on a cleanup shows from run to run
+export interface RunSummary {
+	total: number
+	severity: SeverityCounts
+	// Finding count per rule id, most frequent first
+	rules: [string, number][]
+	filesScanned: number
+	filesWithFindings: number
+}
+
+// Information about the run that some formats need besides the issues themselves
+export interface FormatContext {
+	rules: RuleMetadata[]
+	// Directory reported paths are relative to (--relative-to); SARIF's %SRCROOT%
+	rootDir: string
+	toolVersion: string
+	// Print machine-readable reports on a single line, for readers that parse line by line
+	compact?: boolean
+	// Unset with --no-summary
+	summary?: RunSummary
+	// Highlight the text format with ANSI colors, see resolveColor
+	color?: boolean
+	// Show the source of each finding in the text format; unset with --no-snippets
+	snippets?: SnippetOptions
+}
+
+export interface SnippetOptions {
+	// Lines shown before and after the finding's first line (--context)
+	context: number
+	// The contents of a reported file, or null when it can't be read
+	readSource: (file: string) => string | null
+}
+
+/**
+ * Renders lint issues for a single output format.
+ * Every `--format` value is backed by one implementation of this interface. Issues arrive
+ * sorted (see `sortIssues`) and are rendered in that order.
+ */
+export interface Formatter {
+	format(issues: LintIssue[], context: FormatContext): string
+}
+
+export function isValidOutputFormat(value: unknown): value is OutputFormat {
+	return typeof value === 'string' && VALID_OUTPUT_FORMATS.includes(value as OutputFormat)
+}
+
+export function isValidColorMode(value: unknown): value is ColorMode {
+	return typeof value === 'string' && VALID_COLOR_MODES.includes(value as ColorMode)
+}
+
+/**
+ * Whether to color the output. `auto` colors only a terminal, and not when `NO_COLOR` is set
+ * to anything but an empty string (https://no-color.org); `always` and `never` win over both.
+ */
+export function resolveColor(mode: ColorMode, isTTY: boolean, env: NodeJS.ProcessEnv): boolean {
+	if (mode === COLOR_MODE.AUTO) {
+		return isTTY && !env.NO_COLOR
+	}
+	return mode === COLOR_MODE.ALWAYS
+}
+
+// ANSI SGR parameters for the highlighted parts of a diagnostic; other severities are cyan
+const SEVERITY_STYLES: Record<string, string> = { error: '1;31', warning: '1;33' }
+const HINT_STYLE = '1;36'
+const RULE_STYLE = '1'
+const LOCATION_STYLE = '34'
+
+function paint(text: string, style: string, color: boolean | undefined): string {
+	return color ? `\x1b[${style}m${text}\x1b[0m` : text
+}
+
+/**
+ * Human-readable output modeled after rustc diagnostics.
+ * Returns an empty string when there are no issues. With `snippets`, findings with a known span
+ * show their first line with the span underlined. With `color` the severity, rule id, location
+ * and underline are highlighted; without it the output holds no escape codes.
+ */
+export const textFormatter: Formatter = {
+	format(issues, context) {
+		const { color, snippets } = context
+		const sources = new Map<string, string | null>()
+		const readSource = (file: string) => {
+			if (!sources.has(file)) {
+				sources.set(file, snippets?.readSource(file) ?? null)
+			}
+			return sources.get(file) ?? null
+		}
+
+		const diagnostics = issues.map((issue) => {
+			const style = SEVERITY_STYLES[issue.severity] ?? HINT_STYLE
+			const severity = paint(issue.severity, style, color)
+			const rule = paint(issue.ruleId, RULE_STYLE, color)
+			const location = paint(formatLocation(issue), LOCATION_STYLE, color)
+			const source = snippets && issue.endLine !== undefined ? readSource(issue.file) : null
+			const snippet = source === null ? '' : formatSnippet(issue, source, snippets!.context, color)
+			return `${severity}[${rule}]: ${issue.message}\n  --> ${location}\n${snippet}`
+		})
+		if (context.summary) {
+			diagnostics.push(formatTextSummary(context.summary))
+		}
+		return diagnostics.join('\n')
+	},
+}
+
+/**
+ * The lines of `source` around a finding, rustc style: numbered, with `^` under the finding's
+ * span on its first line, or to the end of that line when it spans several. The `context`
+ * lines before and after stop at the start and end of the file. Empty when the finding's line
+ * isn't in `source`, e.g. because the file changed since it was linted.
+ */
+export function formatSnippet(
+	issue: LintIssue,
+	source: string,
+	context: number,
+	color?: boolean,
+): string {
+	const lines = source.replace(/\r?\n$/, '').split(/\r?\n/)
+	if (issue.line < 1 || issue.line > lines.length) {
+		return ''
+	}
+	const first = Math.max(1, issue.line - context)
+	const last = Math.min(lines.length, issue.line + context)
+	const width = String(last).length
+	const gutter = (label: string, text: string) => {
+		const bar = paint(`${label.padStart(width)} |`, LOCATION_STYLE, color)
+		return text === '' ? bar : `${bar} ${text}`
+	}
+
+	const out = [gutter('', '')]
+	for (let line = first; line <= last; line++) {
+		out.push(gutter(String(line), lines[line - 1]!))
+		if (line === issue.line) {
+			out.push(gutter('', underline(issue, lines[line - 1]!, color)))
+		}
+	}
+	return `${out.join('\n')}\n`
+}
+
+// Carets under the span on the finding's first line, indented with the line's own tabs
+function underline(issue: LintIssue, text: string, color?: boolean): string {
+	const chars = [...text]
+	const start = Math.min(issue.column - 1, chars.length)
+	const end =
+		issue.endLine === issue.line && issue.endColumn !== undefined
+			? issue.endColumn - 1
+			: chars.length
+	const indent = chars
+		.slice(0, start)
+		.map((ch) => (ch === '\t' ? '\t' : ' '))
+		.join('')
+	const style = SEVERITY_STYLES[issue.severity] ?? HINT_STYLE
+	return indent + paint('^'.repeat(Math.max(1, end - start)), style, color)
+}
+
+export const jsonFormatter: Formatter = {
+	format(issues, context) {
+		const report: JsonReport = {
+			schema_version: JSON_SCHEMA_VERSION,
+			findings: issues.map(toJsonFinding),
+			summary: context.summary && toJsonSummary(context.summary),
+		}
+		return context.compact ? JSON.stringify(report) : JSON.stringify(report, null, 2)
+	},
+}
+
+/**
+ * The bare array of findings `--json` has always printed, kept for existing consumers.
+ * Unlike the versioned report it has no summary, and lines and columns are 0-based.
+ */
+export const jsonArrayFormatter: Formatter = {
+	format(issues, context) {
+		const findings = issues.map(toJsonArrayFinding)
+		return context.compact ? JSON.stringify(findings) : JSON.stringify(findings, null, 2)
+	},
+}
+
+/**
+ * GitHub Actions workflow commands followed by a plain-text summary.
+ * The annotations show up in the PR "Files changed" view.
+ */
+export const githubFormatter: Formatter = {
+	format(issues, context) {
+		const counts = countBySeverity(issues)
+		const lines: string[] = []
+
+		for (const issue of issues) {
+			const annotationType = issue.severity === 'error' ? 'error' : 'warning'
+			// Format: ::warning file={name},line={line},col={col},endLine=...,endColumn=...::{message}
+			lines.push(
+				`::${annotationType} file=${issue.file},line=${issue.line},col=${issue.column}${formatAnnotationEnd(issue)}::${issue.ruleId}: ${issue.message}`,
+			)
+		}
+
+		lines.push('')
+		lines.push('========================================')
+		lines.push('Tempo Lint Results')
+		lines.push('========================================')
+		lines.push(`Total issues: ${issues.length}`)
+		lines.push(`Errors: ${counts.error}`)
+		lines.push(`Warnings: ${counts.warning}`)
+		lines.push(`Hints: ${counts.hint}`)
+		if (context.summary) {
+			const { filesScanned, filesWithFindings, rules } = context.summary
+			lines.push(`Files with findings: ${filesWithFindings} of ${filesScanned}`)
+			for (const [ruleId, count] of rules) {
+				lines.push(`  ${ruleId}: ${count}`)
+			}
+		}
+		lines.push('')
+
+		if (issues.length === 0) {
+			lines.push('No lint issues found!')
+			return lines.join('\n')
+		}
+
+		for (const issue of issues) {
+			const prefix =
+				issue.severity === 'error' ? '[ERROR]' : issue.severity === 'warning' ? '[WARN]' : '[HINT]'
+			lines.push(`${prefix} ${formatLocation(issue)}`)
+			lines.push(`  ${issue.ruleId}: ${issue.message}`)
+			if (issue.code) {
+				lines.push(`  > ${issue.code}`)
+			}
+			lines.push('')
+		}
+
+		return lines.join('\n')
+	},
+}
+
+export function getFormatter(format: OutputFormat): Formatter {
+	switch (format) {
+		case OUTPUT_FORMAT.JSON:
+			return jsonFormatter
+		case OUTPUT_FORMAT.JSON_ARRAY:
+			return jsonArrayFormatter
+		case OUTPUT_FORMAT.GITHUB:
+			return githubFormatter
+		case OUTPUT_FORMAT.SARIF:
+			return sarifFormatter
+		default:
+			return textFormatter
+	}
+}
+
+function toJsonFinding(issue: LintIssue): JsonFinding {
+	return {
+		rule_id: issue.ruleId,
+		severity: issue.severity,
+		message: issue.message,
+		file: issue.file,
+		line: issue.line,
+		column: issue.column,
+		end_line: issue.endLine,
+		end_column: issue.endColumn,
+		byte_offset: issue.byteOffset,
+		code: issue.code,
+	}
+}
+
+function toJsonArrayFinding(issue: LintIssue): JsonArrayFinding {
+	return {
+		ruleId: issue.ruleId,
+		severity: issue.severity,
+		message: issue.message,
+		file: issue.file,
+		line: issue.line - 1,
+		column: issue.column - 1,
+		code: issue.code,
+	}
+}
+
+export function buildSummary(issues: LintIssue[], filesScanned: number): RunSummary {
+	const rules = new Map<string, number>()
+	for (const issue of issues) {
+		rules.set(issue.ruleId, (rules.get(issue.ruleId) ?? 0) + 1)
+	}
+
+	return {
+		total: issues.length,
+		severity: countBySeverity(issues),
+		rules: [...rules].sort(([idA, a], [idB, b]) => b - a || (idA < idB ? -1 : 1)),
+		filesScanned,
+		filesWithFindings: new Set(issues.map((issue) => issue.file)).size,
+	}
+}
+
+function formatTextSummary(summary: RunSummary): string {
+	const count = (n: number, noun: string) => `${n} ${pluralize(n, noun)}`
+	const files = `${count(summary.filesScanned, 'file')} scanned`
+	if (summary.total === 0) {
+		return `No findings in ${files}.\n`
+	}
+
+	const { error, warning, hint } = summary.severity
+	const severities = [count(error, 'error'), count(warning, 'warning'), count(hint, 'hint')]
+	const lines = [
+		`Summary: ${count(summary.total, 'finding')} (${severities.join(', ')}) in ${summary.filesWithFindings} of ${files}`,
+		...summary.rules.map(([ruleId, findings]) => `  ${ruleId}: ${findings}`),
+	]
+	return `${lines.join('\n')}\n`
+}
+
+function toJsonSummary(summary: RunSummary): JsonSummary {
+	return {
+		total: summary.total,
+		by_severity: summary.severity,
+		by_rule: Object.fromEntries(summary.rules),
+		files_scanned: summary.filesScanned,
+		files_with_findings: summary.filesWithFindings,
+	}
+}
+
+/**
+ * `file:line:col`, extended to `file:line:col-endLine:endCol` when the end of the
+ * finding is known. The end column is exclusive, matching the JSON and SARIF output.
+ */
+export function formatLocation(issue: LintIssue): string {
+	const start = `${issue.file}:${issue.line}:${issue.column}`
+	if (issue.endLine === undefined || issue.endColumn === undefined) {
+		return start
+	}
+	return `${start}-${issue.endLine}:${issue.endColumn}`
+}
+
+// GitHub treats `endColumn` as inclusive and only honors it on single-line annotations
+function formatAnnotationEnd(issue: LintIssue): string {
+	if (issue.endLine === undefined || issue.endColumn === undefined) {
+		return ''
+	}
+	if (issue.endLine !== issue.line) {
+		return `,endLine=${issue.endLine}`
+	}
+	return `,endLine=${issue.endLine},endColumn=${Math.max(issue.column, issue.endColumn - 1)}`
+}
+
+/**
+ * Parses a report produced by `--format json` back into lint issues.
+ * Used by consumers of the CLI output such as the PR comment script.
+ */
+export function parseJsonReport(input: string): { issues: LintIssue[]; error: string | null } {
+	let data: unknown
+	try {
+		data = JSON.parse(input)
+	} catch (err) {
+		return { issues: [], error: `Failed to parse JSON: ${(err as Error).message}` }
+	}
+
+	const report = data as Partial<JsonReport> | null
+	if (typeof report !== 'object' || report === null || !Array.isArray(report.findings)) {
+		return { issues: [], error: 'Expected a tempo-lints JSON report with a findings array' }
+	}
+
+	if (report.schema_version !== JSON_SCHEMA_VERSION) {
+		return {
+			issues: [],
+			error: `Unsupported report schema_version ${String(report.schema_version)} (expected ${JSON_SCHEMA_VERSION})`,
+		}
+	}
+
+	const issues = report.findings.map((finding) => ({
+		ruleId: finding.rule_id,
+		severity: finding.severity,
+		message: finding.message,
+		file: finding.file,
+		line: finding.line,
+		column: finding.column,
+		endLine: finding.end_line,
+		endColumn: finding.end_column,
+		byteOffset: finding.byte_offset,
+		code: finding.code,
+	}))
+
+	return { issues, error: null }
+}
@@ -0,0 +1,102 @@
+import { describe, expect, it } from 'vitest'
+import {
+	applyFunctionLength,
+	countBodyLines,
+	DEFAULT_MAX_FUNCTION_LINES,
+	maskCommentsAndLiterals,
+} from './function-length.ts'
+import type { LintIssue } from './shared.ts'
+
+function functionWithBody(lines: string[]): string {
+	return ['fn long() {', ...lines.map((line) => `    ${line}`), '}'].join('\n')
+}
+
+function candidate(code: string): LintIssue {
+	return {
+		ruleId: 'max-function-length',
+		severity: 'warning',
+		message: 'Function body is longer than the configured limit.',
+		file: 'src/lib.rs',
+		line: 1,
+		column: 1,
+		code,
+	}
+}
+
+describe('maskCommentsAndLiterals', () => {
+	it('should blank comments and keep line breaks', () => {
+		expect(maskCommentsAndLiterals('a // b\n/* c\n /* d */ */e')).toBe(
+			'a     \n    \n           e',
+		)
+	})
+
+	it('should mask braces inside literals', () => {
+		expect(maskCommentsAndLiterals('f("{", \'}\', r#"{"#)')).toBe('f(___, ___, ______)')
+	})
+
+	it('should leave lifetimes and identifiers ending in r alone', () => {
+		expect(maskCommentsAndLiterals("fn f<'a>(bar: &'a str) {}")).toBe(
+			"fn f<'a>(bar: &'a str) {}",
+		)
+		expect(maskCommentsAndLiterals('for"x"')).toBe('for___')
+	})
+})
+
+describe('countBodyLines', () => {
+	it('should skip blank lines and comments', () => {
+		const source = functionWithBody(['let a = 1;', '', '// explain', '/* more', '   text */', 'a'])
+
+		expect(countBodyLines(source)).toBe(2)
+	})
+
+	it('should count every line of a large match', () => {
+		const arms = Array.from({ length: 5 }, (_, i) => `    ${i} => ${i},`)
+		const source = functionWithBody(['match x {', ...arms, '    _ => 0,', '}'])
+
+		expect(countBodyLines(source)).toBe(8)
+	})
+
+	it('should count the lines of a multi-line string', () => {
+		expect(countBodyLines(functionWithBody(['let s = "one', 'two', '";']))).toBe(3)
+	})
+
+	it('should ignore braces in the signature', () => {
+		expect(countBodyLines('fn f() -> Foo<{ N }> where T: Bar {\n    a\n}')).toBe(1)
+	})
+
+	it('should measure closures bound with let', () => {
+		expect(countBodyLines('let f = |x| {\n    x + 1\n};')).toBe(1)
+	})
+
+	it('should return null without a body', () => {
+		expect(countBodyLines('fn f();')).toBeNull()
+	})
+})
+
+describe('applyFunctionLength', () => {
+	const longBody = Array.from({ length: DEFAULT_MAX_FUNCTION_LINES + 1 }, (_, i) => `f(${i});`)
+
+	it('should report functions over the limit with their length', () => {
+		const [issue] = applyFunctionLength([candidate(functionWithBody(longBody))], { rules: {} })
+
+		expect(issue?.message).toBe('Function body is 61 lines long (limit 60).')
+	})
+
+	it('should drop candidates within the limit', () => {
+		const padded = functionWithBody([...longBody.slice(1), '', '// note'])
+
+		expect(applyFunctionLength([candidate(padded)], { rules: {} })).toEqual([])
+	})
+
+	it('should use the configured limit', () => {
+		const config = { rules: {}, ruleOptions: { 'max-function-length': { maxLines: 80 } } }
+
+		expect(applyFunctionLength([candidate(functionWithBody(longBody))], config)).toEqual([])
+	})
+
+	it('should leave other rules alone', () => {
+		const issue = { ...candidate('fn f() {}'), ruleId: 'no-dbg-macro' }
+
+		expect(applyFunctionLength([issue], { rules: {} })).toEqual([issue])
+	})
+})
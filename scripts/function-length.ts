@@ -0,0 +1,134 @@
+import { FUNCTION_LENGTH_RULE_ID, type LintConfig } from './config.ts'
+import type { LintIssue } from './shared.ts'
+
+export const DEFAULT_MAX_FUNCTION_LINES = 60
+
+const IDENTIFIER_CHAR = /\w/
+const RAW_STRING_START = /r(#*)"/y
+
+/**
+ * Blank out comments and mask string and char literals in Rust source, keeping every line
+ * break where it was. Comments become spaces; literal contents become `_`, so braces inside
+ * them don't count and lines holding only part of a string still read as code.
+ */
+export function maskCommentsAndLiterals(source: string): string {
+	let out = ''
+	let i = 0
+
+	while (i < source.length) {
+		const start = i
+		const ch = source[i]!
+		const next = source[i + 1]
+		const hashes = ch === 'r' ? rawStringHashes(source, i) : null
+
+		if (ch === '/' && next === '/') {
+			i = source.indexOf('\n', i)
+			if (i === -1) i = source.length
+			out += blank(source.slice(start, i))
+		} else if (ch === '/' && next === '*') {
+			// Block comments nest in Rust
+			let depth = 0
+			while (i < source.length) {
+				if (source.startsWith('/*', i)) {
+					depth++
+					i += 2
+				} else if (source.startsWith('*/', i)) {
+					depth--
+					i += 2
+					if (depth === 0) break
+				} else {
+					i++
+				}
+			}
+			out += blank(source.slice(start, i))
+		} else if (hashes !== null) {
+			const close = source.indexOf(`"${hashes}`, i + hashes.length + 2)
+			i = close === -1 ? source.length : close + hashes.length + 1
+			out += mask(source.slice(start, i))
+		} else if (ch === '"') {
+			i++
+			while (i < source.length && source[i] !== '"') {
+				i += source[i] === '\\' ? 2 : 1
+			}
+			i = Math.min(i + 1, source.length)
+			out += mask(source.slice(start, i))
+		} else if (ch === "'" && (next === '\\' || source[i + 2] === "'")) {
+			// A char literal; anything else starting with `'` is a lifetime or label
+			const close = source.indexOf("'", next === '\\' ? i + 3 : i + 2)
+			i = close === -1 ? source.length : close + 1
+			out += mask(source.slice(start, i))
+		} else {
+			out += ch
+			i++
+		}
+	}
+
+	return out
+}
+
+// The `#`s of a raw string (`r"..."`, `r#"..."#`, `br"..."`) starting at `i`, or null when
+// the `r` is part of an identifier
+function rawStringHashes(source: string, i: number): string | null {
+	const before = source[i - 1] ?? ''
+	const prefix = before === 'b' ? (source[i - 2] ?? '') : before
+	if (IDENTIFIER_CHAR.test(prefix)) return null
+
+	RAW_STRING_START.lastIndex = i
+	return RAW_STRING_START.exec(source)?.[1] ?? null
+}
+
+function blank(text: string): string {
+	return text.replace(/[^\n]/g, ' ')
+}
+
+function mask(text: string): string {
+	return text.replace(/\S/g, '_')
+}
+
+/**
+ * Count the lines with code in a function's body. The body is the last brace group opened at
+ * the top level of `source`, so braces in the signature (e.g. const generic arguments) are
+ * skipped. Returns null when there is no body.
+ */
+export function countBodyLines(source: string): number | null {
+	const masked = maskCommentsAndLiterals(source)
+
+	let depth = 0
+	let open = -1
+	let body: [number, number] | null = null
+	for (let i = 0; i < masked.length; i++) {
+		if (masked[i] === '{') {
+			if (depth === 0) open = i
+			depth++
+		} else if (masked[i] === '}' && depth > 0) {
+			depth--
+			if (depth === 0) body = [open + 1, i]
+		}
+	}
+	if (!body) return null
+
+	return masked
+		.slice(...body)
+		.split('\n')
+		.filter((line) => line.trim() !== '').length
+}
+
+/**
+ * Finish the max-function-length rule, whose YAML only matches candidates: measure each
+ * matched function and keep those over the limit, with a message giving both numbers.
+ */
+export function applyFunctionLength(issues: LintIssue[], config: LintConfig): LintIssue[] {
+	const limit =
+		config.ruleOptions?.[FUNCTION_LENGTH_RULE_ID]?.maxLines ?? DEFAULT_MAX_FUNCTION_LINES
+
+	return issues.flatMap((issue) => {
+		if (issue.ruleId !== FUNCTION_LENGTH_RULE_ID || issue.code === undefined) {
+			return [issue]
+		}
+		const length = countBodyLines(issue.code)
+		if (length === null || length <= limit) {
+			return []
+		}
+		return [{ ...issue, message: `Function body is ${length} lines long (limit ${limit}).` }]
+	})
+}
@@ -0,0 +1,53 @@
+import { describe, expect, it } from 'vitest'
+import { compileIgnorePattern, matchIgnorePatterns, parseIgnoreFile } from './globs.ts'
+
+const match = (content: string, relativePath: string, isDir = false) =>
+	matchIgnorePatterns(parseIgnoreFile(content), relativePath, isDir)
+
+describe('compileIgnorePattern', () => {
+	it('should skip blank lines and comments', () => {
+		expect(compileIgnorePattern('')).toBeNull()
+		expect(compileIgnorePattern('   ')).toBeNull()
+		expect(compileIgnorePattern('# generated code')).toBeNull()
+	})
+
+	it('should read negation and directory markers', () => {
+		expect(compileIgnorePattern('!keep/')).toMatchObject({ negated: true, dirOnly: true })
+	})
+})
+
+describe('matchIgnorePatterns', () => {
+	it('should match unanchored patterns at any depth', () => {
+		expect(match('*.pb.rs', 'src/proto/types.pb.rs')).toBe('ignore')
+		expect(match('build.rs', 'crates/core/build.rs')).toBe('ignore')
+	})
+
+	it('should anchor patterns containing a slash', () => {
+		expect(match('/build.rs', 'build.rs')).toBe('ignore')
+		expect(match('/build.rs', 'crates/core/build.rs')).toBeNull()
+		expect(match('src/*.rs', 'src/lib.rs')).toBe('ignore')
+		expect(match('src/*.rs', 'src/nested/lib.rs')).toBeNull()
+	})
+
+	it('should match any number of directories with **', () => {
+		expect(match('**/generated/*.rs', 'generated/a.rs')).toBe('ignore')
+		expect(match('**/generated/*.rs', 'src/generated/a.rs')).toBe('ignore')
+		expect(match('vendor/**', 'vendor/a/b/c.rs')).toBe('ignore')
+	})
+
+	it('should only match directories with a trailing slash', () => {
+		expect(match('target/', 'target', true)).toBe('ignore')
+		expect(match('target/', 'target', false)).toBeNull()
+	})
+
+	it('should support character classes and alternatives', () => {
+		expect(match('[!a]x.rs', 'bx.rs')).toBe('ignore')
+		expect(match('[!a]x.rs', 'ax.rs')).toBeNull()
+		expect(match('{foo,bar}.rs', 'bar.rs')).toBe('ignore')
+	})
+
+	it('should let the last matching pattern win', () => {
+		expect(match('*.rs\n!keep.rs', 'src/keep.rs')).toBe('include')
+		expect(match('!keep.rs\n*.rs', 'src/keep.rs')).toBe('ignore')
+	})
+})
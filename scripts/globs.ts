@@ -0,0 +1,138 @@
+/**
+ * Gitignore-style glob matching, used to select files the same way ast-grep does
+ * (`--globs` overrides and `.gitignore`/`.ignore` files).
+ * Paths are matched relative to a base directory, with `/` as the separator.
+ */
+
+export interface IgnorePattern {
+	regex: RegExp
+	// `!pattern`: re-includes a path excluded by an earlier pattern
+	negated: boolean
+	// `pattern/`: only matches directories
+	dirOnly: boolean
+}
+
+// Characters with a special meaning in regular expressions but not in globs
+const REGEX_SPECIAL = /[.+^$()|\\]/
+
+/**
+ * Translate a glob to a regular expression source matching a whole relative path.
+ * Supports `*`, `?`, `**`, `[...]` character classes, `{a,b}` alternatives and `\` escapes.
+ */
+export function globToRegExpSource(glob: string): string {
+	let source = ''
+	let braceDepth = 0
+
+	for (let i = 0; i < glob.length; i++) {
+		const ch = glob[i]!
+
+		if (ch === '*' && glob[i + 1] === '*') {
+			const atStart = i === 0 || glob[i - 1] === '/'
+			const next = glob[i + 2]
+			if (atStart && next === '/') {
+				// `**/` matches zero or more directories
+				source += '(?:.*/)?'
+				i += 2
+				continue
+			}
+			if (atStart && next === undefined) {
+				// A trailing `**` matches everything inside
+				source += '.*'
+				i += 1
+				continue
+			}
+			// `**` anywhere else acts like `*`
+			source += '[^/]*'
+			i += 1
+			continue
+		}
+
+		if (ch === '*') {
+			source += '[^/]*'
+		} else if (ch === '?') {
+			source += '[^/]'
+		} else if (ch === '[') {
+			const end = glob.indexOf(']', i + 2)
+			if (end === -1) {
+				source += '\\['
+				continue
+			}
+			let body = glob.slice(i + 1, end)
+			if (body.startsWith('!')) body = `^${body.slice(1)}`
+			source += `[${body.replaceAll('\\', '\\\\')}]`
+			i = end
+		} else if (ch === '{') {
+			braceDepth++
+			source += '(?:'
+		} else if (ch === '}' && braceDepth > 0) {
+			braceDepth--
+			source += ')'
+		} else if (ch === ',' && braceDepth > 0) {
+			source += '|'
+		} else if (ch === '\\' && i + 1 < glob.length) {
+			i++
+			source += escapeRegExpChar(glob[i]!)
+		} else {
+			source += escapeRegExpChar(ch)
+		}
+	}
+
+	return source
+}
+
+function escapeRegExpChar(ch: string): string {
+	return REGEX_SPECIAL.test(ch) || '*?[]{}'.includes(ch) ? `\\${ch}` : ch
+}
+
+/**
+ * Compile one line of a gitignore-style file (or one `--globs` entry).
+ * Returns null for blank lines and comments.
+ */
+export function compileIgnorePattern(line: string): IgnorePattern | null {
+	let pattern = line.replace(/(?<!\\)\s+$/, '')
+	if (pattern === '' || pattern.startsWith('#')) {
+		return null
+	}
+
+	const negated = pattern.startsWith('!')
+	if (negated) pattern = pattern.slice(1)
+	if (pattern.startsWith('\\#') || pattern.startsWith('\\!')) pattern = pattern.slice(1)
+
+	const dirOnly = pattern.endsWith('/')
+	if (dirOnly) pattern = pattern.slice(0, -1)
+
+	// A pattern with a `/` anywhere but the end is relative to the base directory;
+	// otherwise it matches a file or directory name at any depth
+	const anchored = pattern.includes('/')
+	if (pattern.startsWith('/')) pattern = pattern.slice(1)
+
+	const source = globToRegExpSource(pattern)
+	const regex = new RegExp(anchored ? `^${source}$` : `^(?:.*/)?${source}$`)
+	return { regex, negated, dirOnly }
+}
+
+export function parseIgnoreFile(content: string): IgnorePattern[] {
+	return content
+		.split(/\r?\n/)
+		.map(compileIgnorePattern)
+		.filter((pattern): pattern is IgnorePattern => pattern !== null)
+}
+
+/**
+ * Match a relative path against patterns; the last matching pattern wins.
+ * Returns 'ignore', 'include' (matched a negated pattern) or null when nothing matched.
+ */
+export function matchIgnorePatterns(
+	patterns: IgnorePattern[],
+	relativePath: string,
+	isDir: boolean,
+): 'ignore' | 'include' | null {
+	for (let i = patterns.length - 1; i >= 0; i--) {
+		const pattern = patterns[i]!
+		if (pattern.dirOnly && !isDir) continue
+		if (pattern.regex.test(relativePath)) {
+			return pattern.negated ? 'include' : 'ignore'
+		}
+	}
+	return null
+}
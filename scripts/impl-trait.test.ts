@@ -0,0 +1,115 @@
+import { describe, expect, it } from 'vitest'
+import {
+	applyImplTraitSignatures,
+	hasRestrictedVisibility,
+	implTraitPosition,
+} from './impl-trait.ts'
+import type { LintIssue } from './shared.ts'
+
+// A finding for the `impl Trait` type `impl` in the function `fn`, as the YAML rule reports it
+function candidate(fn: string, impl: string, container?: string): LintIssue {
+	const column = fn.indexOf(impl) + 1
+	return {
+		ruleId: 'no-impl-trait-in-public-return',
+		severity: 'warning',
+		message: '`impl Trait` in a public function signature hides the concrete type.',
+		file: 'src/lib.rs',
+		line: 1,
+		column,
+		captures: {
+			FUNCTION: { text: fn, line: 1, column: 1 },
+			IMPL: { text: impl, line: 1, column },
+			...(container === undefined ? {} : { CONTAINER: { text: container, line: 1, column: 1 } }),
+		},
+	}
+}
+
+const ITEMS = 'pub fn items(&self) -> impl Iterator<Item = &u8> + \'_ {\n    self.0.iter()\n}'
+const LOAD = 'pub fn load(path: impl AsRef<Path>) -> Config {\n    todo!()\n}'
+
+describe('implTraitPosition', () => {
+	it('should tell the return type from the parameters', () => {
+		expect(implTraitPosition(ITEMS, ITEMS.indexOf('impl'))).toBe('return')
+		expect(implTraitPosition(LOAD, LOAD.indexOf('impl'))).toBe('argument')
+	})
+
+	it('should skip arrows inside bounds and parameter types', () => {
+		const fn = 'pub fn apply<F: Fn(u8) -> u8>(f: F, g: impl Fn() -> u8) -> u8 { f(g()) }'
+
+		expect(implTraitPosition(fn, fn.indexOf('impl'))).toBe('argument')
+	})
+
+	it('should read declarations without a body', () => {
+		const fn = 'fn keys(&self) -> impl Iterator<Item = u32>;'
+
+		expect(implTraitPosition(fn, fn.indexOf('impl'))).toBe('return')
+	})
+})
+
+describe('hasRestrictedVisibility', () => {
+	it('should read the function visibility, then its trait', () => {
+		expect(hasRestrictedVisibility('pub fn a() {}')).toBe(false)
+		expect(hasRestrictedVisibility('pub(crate) fn a() {}')).toBe(true)
+		expect(hasRestrictedVisibility('pub(in crate::db) fn a() {}')).toBe(true)
+		expect(hasRestrictedVisibility('fn a();', 'pub(crate) trait Store {}')).toBe(true)
+		expect(hasRestrictedVisibility('fn a() {}', 'impl Store for Db {}')).toBe(false)
+	})
+})
+
+describe('applyImplTraitSignatures', () => {
+	it('should name the function and the returned type', () => {
+		const issues = [candidate(ITEMS, "impl Iterator<Item = &u8> + '_")]
+
+		const [issue] = applyImplTraitSignatures(issues, { rules: {} })
+
+		expect(issue?.message).toBe(
+			"`items` returns `impl Iterator<Item = &u8> + '_`, which keeps the concrete type out of " +
+				'its public signature; return a named type instead.',
+		)
+	})
+
+	it('should only report argument types with arguments on', () => {
+		const issues = [candidate(LOAD, 'impl AsRef<Path>')]
+		const config = {
+			rules: {},
+			ruleOptions: { 'no-impl-trait-in-public-return': { arguments: true } },
+		}
+
+		expect(applyImplTraitSignatures(issues, { rules: {} })).toEqual([])
+		expect(applyImplTraitSignatures(issues, config)[0]?.message).toBe(
+			"`load` takes `impl AsRef<Path>`, a type parameter callers can't name; declare a named " +
+				'generic parameter instead.',
+		)
+	})
+
+	it('should only report restricted visibilities with pub-crate on', () => {
+		const fn = 'pub(crate) fn ids() -> impl Iterator<Item = u64> { 0..3 }'
+		const method = 'fn ids(&self) -> impl Iterator<Item = u64>;'
+		const issues = [
+			candidate(fn, 'impl Iterator<Item = u64>'),
+			candidate(method, 'impl Iterator<Item = u64>', 'pub(crate) trait Index {}'),
+		]
+		const config = {
+			rules: {},
+			ruleOptions: { 'no-impl-trait-in-public-return': { pubCrate: true } },
+		}
+
+		expect(applyImplTraitSignatures(issues, { rules: {} })).toEqual([])
+		expect(applyImplTraitSignatures(issues, config)).toHaveLength(2)
+	})
+
+	it('should report methods of trait impls', () => {
+		const method = 'fn ids(&self) -> impl Iterator<Item = u64> { 0..3 }'
+		const issue = candidate(method, 'impl Iterator<Item = u64>', 'impl Index for Db {}')
+
+		expect(applyImplTraitSignatures([issue], { rules: {} })[0]?.message).toContain(
+			'`ids` returns `impl Iterator<Item = u64>`',
+		)
+	})
+
+	it('should leave other rules alone', () => {
+		const issue = { ...candidate(ITEMS, 'impl Iterator'), ruleId: 'no-dbg-macro' }
+
+		expect(applyImplTraitSignatures([issue], { rules: {} })).toEqual([issue])
+	})
+})
@@ -0,0 +1,87 @@
+import { IMPL_TRAIT_RULE_ID, type LintConfig } from './config.ts'
+import { maskCommentsAndLiterals } from './function-length.ts'
+import type { LintIssue } from './shared.ts'
+import { offsetWithin } from './wildcard-imports.ts'
+
+// Where an `impl Trait` sits in a function signature
+export type ImplTraitPosition = 'argument' | 'return'
+
+// `pub(crate)`, `pub(super)`, `pub(in path)` and `pub(self)`
+const RESTRICTED_VISIBILITY = /^pub\s*\(/
+
+/**
+ * Whether the `impl Trait` at `offset` in the text of a function is in its return type or in
+ * its parameters. The return type starts at the first `->` outside the parameter list and
+ * generic arguments, so the arrow of a bound like `F: Fn(u8) -> u8` doesn't count.
+ */
+export function implTraitPosition(fn: string, offset: number): ImplTraitPosition {
+	const arrow = returnArrow(maskCommentsAndLiterals(fn))
+	return arrow !== -1 && offset > arrow ? 'return' : 'argument'
+}
+
+function returnArrow(text: string): number {
+	let depth = 0
+	for (let i = 0; i < text.length; i++) {
+		const ch = text[i]!
+		if (depth === 0 && (ch === '{' || ch === ';')) {
+			// The body or the end of a declaration, without a return type before it
+			return -1
+		}
+		if (ch === '-' && text[i + 1] === '>') {
+			if (depth === 0) return i
+			i++
+		} else if ('([<'.includes(ch)) {
+			depth++
+		} else if (')]>'.includes(ch)) {
+			depth = Math.max(depth - 1, 0)
+		}
+	}
+	return -1
+}
+
+/**
+ * Whether a function is only visible within its crate or an enclosing module: it's declared
+ * with a restricted `pub(...)`, or it's a method of a trait declared that way. Methods of trait
+ * impls are as public as the trait, which may be declared anywhere, so they never count as
+ * restricted.
+ */
+export function hasRestrictedVisibility(fn: string, container?: string): boolean {
+	if (fn.startsWith('pub')) {
+		return RESTRICTED_VISIBILITY.test(fn)
+	}
+	return container !== undefined && RESTRICTED_VISIBILITY.test(container)
+}
+
+/**
+ * Finish the no-impl-trait-in-public-return rule, whose YAML matches every `impl Trait` in the
+ * signature of a visible function: name the function in the message, and drop functions with
+ * a restricted visibility unless `pub-crate` is on, and argument types unless `arguments` is.
+ */
+export function applyImplTraitSignatures(issues: LintIssue[], config: LintConfig): LintIssue[] {
+	const options = config.ruleOptions?.[IMPL_TRAIT_RULE_ID]
+	const pubCrate = options?.pubCrate ?? false
+	const checkArguments = options?.arguments ?? false
+
+	return issues.flatMap((issue) => {
+		const fn = issue.captures?.FUNCTION
+		const impl = issue.captures?.IMPL
+		if (issue.ruleId !== IMPL_TRAIT_RULE_ID || !fn || !impl) {
+			return [issue]
+		}
+		if (!pubCrate && hasRestrictedVisibility(fn.text, issue.captures?.CONTAINER?.text)) {
+			return []
+		}
+		const position = implTraitPosition(fn.text, offsetWithin(fn.text, fn, impl))
+		if (position === 'argument' && !checkArguments) {
+			return []
+		}
+
+		const name = /\bfn\s+(?:r#)?(\w+)/.exec(fn.text)?.[1] ?? 'function'
+		const type = impl.text.replace(/\s+/g, ' ')
+		const message =
+			position === 'return'
+				? `\`${name}\` returns \`${type}\`, which keeps the concrete type out of its public signature; return a named type instead.`
+				: `\`${name}\` takes \`${type}\`, a type parameter callers can't name; declare a named generic parameter instead.`
+		return [{ ...issue, message }]
+	})
+}
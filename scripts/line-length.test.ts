@@ -0,0 +1,94 @@
+import { describe, expect, it } from 'vitest'
+import { applyLineLength, isUrlComment, lineWidth } from './line-length.ts'
+import type { LintIssue } from './shared.ts'
+
+function candidate(code: string, column = 1): LintIssue {
+	return {
+		ruleId: 'max-line-length',
+		severity: 'warning',
+		message: 'Line is longer than the configured limit.',
+		file: 'src/lib.rs',
+		line: 1,
+		column,
+		byteOffset: { start: column - 1, end: column - 1 + Buffer.byteLength(code) },
+		code,
+	}
+}
+
+describe('lineWidth', () => {
+	it('should count characters rather than bytes or code units', () => {
+		expect(lineWidth('let s = "grüße";')).toBe(16)
+		expect(lineWidth('// 🦀🦀')).toBe(5)
+	})
+
+	it('should expand tabs to the next tab stop', () => {
+		expect(lineWidth('\tx')).toBe(5)
+		expect(lineWidth('ab\tx', 4)).toBe(5)
+		expect(lineWidth('\t\tx', 8)).toBe(17)
+	})
+})
+
+describe('isUrlComment', () => {
+	it('should match lines holding only a comment with a URL', () => {
+		expect(isUrlComment('    /// See https://doc.rust-lang.org/std/primitive.f64.html')).toBe(true)
+		expect(isUrlComment('//! ftp://example.com/archive')).toBe(true)
+	})
+
+	it('should not match code or comments without a URL', () => {
+		expect(isUrlComment('let url = "https://example.com"; // docs')).toBe(false)
+		expect(isUrlComment('// see the module docs for details')).toBe(false)
+	})
+})
+
+describe('applyLineLength', () => {
+	const long = `let value = compute(${'x'.repeat(100)});`
+
+	it('should report each line over the limit with its width', () => {
+		const issues = applyLineLength([candidate(`fn main() {\n    ${long}\n}\n`)], { rules: {} })
+
+		expect(issues).toEqual([
+			{
+				ruleId: 'max-line-length',
+				severity: 'warning',
+				message: 'Line is 126 columns wide (limit 100).',
+				file: 'src/lib.rs',
+				line: 2,
+				column: 101,
+				endLine: 2,
+				endColumn: 127,
+				byteOffset: { start: 112, end: 138 },
+				code: `    ${long}`,
+			},
+		])
+	})
+
+	it('should drop candidates without long lines', () => {
+		expect(applyLineLength([candidate('fn main() {\n\tf();\n}\n')], { rules: {} })).toEqual([])
+	})
+
+	it('should count the indentation before the first token', () => {
+		const [issue] = applyLineLength([candidate('x'.repeat(98), 4)], { rules: {} })
+
+		expect(issue?.message).toBe('Line is 101 columns wide (limit 100).')
+		expect(issue?.column).toBe(101)
+	})
+
+	it('should use the configured width and tab width', () => {
+		const config = {
+			rules: {},
+			ruleOptions: { 'max-line-length': { maxWidth: 20, tabWidth: 8 } },
+		}
+
+		expect(applyLineLength([candidate('\t\tlet x = 1;')], config)[0]?.message).toBe(
+			'Line is 26 columns wide (limit 20).',
+		)
+	})
+
+	it('should skip URL comments only when configured', () => {
+		const comment = `/// See https://example.com/${'a'.repeat(100)}`
+		const config = { rules: {}, ruleOptions: { 'max-line-length': { ignoreUrlComments: true } } }
+
+		expect(applyLineLength([candidate(comment)], { rules: {} })).toHaveLength(1)
+		expect(applyLineLength([candidate(comment)], config)).toEqual([])
+	})
+})
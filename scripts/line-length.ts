@@ -0,0 +1,83 @@
+import { LINE_LENGTH_RULE_ID, type LintConfig } from './config.ts'
+import type { LintIssue } from './shared.ts'
+
+export const DEFAULT_MAX_LINE_WIDTH = 100
+export const DEFAULT_TAB_WIDTH = 4
+
+// A line holding nothing but a `//`, `///` or `//!` comment that mentions a URL
+const URL_COMMENT = /^\s*\/\/.*\b[a-z][a-z\d+.-]*:\/\/\S/i
+
+/**
+ * The width of a line in columns: every character counts once, however many bytes or UTF-16
+ * code units it takes, and a tab advances to the next multiple of `tabWidth`.
+ */
+export function lineWidth(line: string, tabWidth: number = DEFAULT_TAB_WIDTH): number {
+	let width = 0
+	for (const ch of line) {
+		width += ch === '\t' ? tabWidth - (width % tabWidth) : 1
+	}
+	return width
+}
+
+// Index (in code units) of the first character that ends past `limit` columns
+function overflowIndex(line: string, limit: number, tabWidth: number): number {
+	let width = 0
+	let index = 0
+	for (const ch of line) {
+		width += ch === '\t' ? tabWidth - (width % tabWidth) : 1
+		if (width > limit) return index
+		index += ch.length
+	}
+	return line.length
+}
+
+export function isUrlComment(line: string): boolean {
+	return URL_COMMENT.test(line)
+}
+
+/**
+ * Finish the max-line-length rule, whose YAML only matches files that may have a long line:
+ * measure every line of the match and report each one over the limit, from the first column
+ * past it to the end of the line, with a message giving the width.
+ */
+export function applyLineLength(issues: LintIssue[], config: LintConfig): LintIssue[] {
+	const options = config.ruleOptions?.[LINE_LENGTH_RULE_ID]
+	const limit = options?.maxWidth ?? DEFAULT_MAX_LINE_WIDTH
+	const tabWidth = options?.tabWidth ?? DEFAULT_TAB_WIDTH
+	const ignoreUrlComments = options?.ignoreUrlComments ?? false
+
+	return issues.flatMap((issue) => {
+		if (issue.ruleId !== LINE_LENGTH_RULE_ID || issue.code === undefined) {
+			return [issue]
+		}
+
+		const found: LintIssue[] = []
+		let byte = issue.byteOffset?.start ?? 0
+		issue.code.split('\n').forEach((raw, index) => {
+			const text = raw.replace(/\r$/, '')
+			// The match starts at the file's first token, past any indentation on its line
+			const indent = index === 0 ? ' '.repeat(issue.column - 1) : ''
+			const line = indent + text
+			const width = lineWidth(line, tabWidth)
+			if (width > limit && !(ignoreUrlComments && isUrlComment(line))) {
+				const start = overflowIndex(line, limit, tabWidth) - indent.length
+				const lineNumber = issue.line + index
+				const startByte = byte + Buffer.byteLength(text.slice(0, start))
+				found.push({
+					ruleId: issue.ruleId,
+					severity: issue.severity,
+					message: `Line is ${width} columns wide (limit ${limit}).`,
+					file: issue.file,
+					line: lineNumber,
+					column: indent.length + start + 1,
+					endLine: lineNumber,
+					endColumn: indent.length + text.length + 1,
+					byteOffset: { start: startByte, end: byte + Buffer.byteLength(text) },
+					code: text,
+				})
+			}
+			byte += Buffer.byteLength(raw) + 1
+		})
+		return found
+	})
+}
@@ -0,0 +1,84 @@
+import { describe, expect, it } from 'vitest'
+import { applyMustUseResults, resultTypeName } from './must-use.ts'
+import type { LintIssue } from './shared.ts'
+
+// A finding for the function `fn` returning `returned`, as the YAML rule reports it
+function candidate(fn: string, returned: string, container?: string): LintIssue {
+	const column = fn.indexOf(returned) + 1
+	return {
+		ruleId: 'require-must-use-on-result-returning-fn',
+		severity: 'warning',
+		message: 'Public function returning `Result` without `#[must_use]`.',
+		file: 'src/lib.rs',
+		line: 1,
+		column: 1,
+		captures: {
+			FUNCTION: { text: fn, line: 1, column: 1 },
+			RETURN: { text: returned, line: 1, column },
+			...(container === undefined ? {} : { CONTAINER: { text: container, line: 1, column: 1 } }),
+		},
+	}
+}
+
+const PARSE = 'pub fn parse(input: &str) -> Result<Config, Error> {\n    toml::from_str(input)\n}'
+const PARSE_EXPR = 'pub fn parse_expr(tokens: &[Token]) -> ParseResult<Expr> {\n    todo!()\n}'
+
+describe('resultTypeName', () => {
+	it('should read the name without the path and generic arguments', () => {
+		expect(resultTypeName('Result<Config, Error>')).toBe('Result')
+		expect(resultTypeName('std::io::Result<Vec<u8>>')).toBe('Result')
+		expect(resultTypeName('ParseResult<Expr>')).toBe('ParseResult')
+		expect(resultTypeName('FmtResult')).toBe('FmtResult')
+	})
+
+	it('should not read other types', () => {
+		expect(resultTypeName('Option<Result<u8, Error>>')).toBeNull()
+		expect(resultTypeName('SearchResults')).toBeNull()
+	})
+})
+
+describe('applyMustUseResults', () => {
+	it('should name the function and the attribute to add', () => {
+		const [issue] = applyMustUseResults([candidate(PARSE, 'Result<Config, Error>')], {
+			rules: {},
+		})
+
+		expect(issue?.message).toBe(
+			"`parse` returns `Result<Config, Error>` but isn't marked `#[must_use]`; add " +
+				"`#[must_use]` before it so callers can't ignore the error.",
+		)
+	})
+
+	it('should drop result type aliases when result-aliases is off', () => {
+		const issues = [candidate(PARSE_EXPR, 'ParseResult<Expr>')]
+		const config = {
+			rules: {},
+			ruleOptions: { 'require-must-use-on-result-returning-fn': { resultAliases: false } },
+		}
+
+		expect(applyMustUseResults(issues, { rules: {} })).toHaveLength(1)
+		expect(applyMustUseResults(issues, config)).toEqual([])
+	})
+
+	it('should only check restricted visibilities when pub-crate is on', () => {
+		const fn = 'pub(crate) fn load() -> Result<(), Error> {\n    Ok(())\n}'
+		const method = 'fn get(&self) -> Result<u8, Error>;'
+		const issues = [
+			candidate(fn, 'Result<(), Error>'),
+			candidate(method, 'Result<u8, Error>', 'pub(crate) trait Store {\n    ...\n}'),
+		]
+		const config = {
+			rules: {},
+			ruleOptions: { 'require-must-use-on-result-returning-fn': { pubCrate: true } },
+		}
+
+		expect(applyMustUseResults(issues, { rules: {} })).toEqual([])
+		expect(applyMustUseResults(issues, config)).toHaveLength(2)
+	})
+
+	it('should leave other rules alone', () => {
+		const issue = { ...candidate(PARSE, 'Result<Config, Error>'), ruleId: 'no-dbg-macro' }
+
+		expect(applyMustUseResults([issue], { rules: {} })).toEqual([issue])
+	})
+})
@@ -0,0 +1,43 @@
+import { type LintConfig, MUST_USE_RULE_ID } from './config.ts'
+import { hasRestrictedVisibility } from './impl-trait.ts'
+import type { LintIssue } from './shared.ts'
+
+/**
+ * The name of the result type a return type spells, without its path and generic arguments,
+ * e.g. `Result` for `std::io::Result<Vec<u8>>` or `ParseResult` for `ParseResult<Expr>`.
+ */
+export function resultTypeName(returnType: string): string | null {
+	return /^(?:\w+::)*(\w*Result)\s*(?:<|$)/.exec(returnType.trim())?.[1] ?? null
+}
+
+/**
+ * Finish the require-must-use-on-result-returning-fn rule, whose YAML matches visible functions
+ * without `#[must_use]` returning a type named like a `Result`: name the function and the
+ * attribute to add, drop result type aliases unless `result-aliases` is on, and functions with
+ * a restricted visibility unless `pub-crate` is.
+ */
+export function applyMustUseResults(issues: LintIssue[], config: LintConfig): LintIssue[] {
+	const options = config.ruleOptions?.[MUST_USE_RULE_ID]
+	const resultAliases = options?.resultAliases ?? true
+	const pubCrate = options?.pubCrate ?? false
+
+	return issues.flatMap((issue) => {
+		const fn = issue.captures?.FUNCTION
+		const returned = issue.captures?.RETURN
+		if (issue.ruleId !== MUST_USE_RULE_ID || !fn || !returned) {
+			return [issue]
+		}
+		if (!pubCrate && hasRestrictedVisibility(fn.text, issue.captures?.CONTAINER?.text)) {
+			return []
+		}
+		const typeName = resultTypeName(returned.text)
+		if (typeName === null || (typeName !== 'Result' && !resultAliases)) {
+			return []
+		}
+
+		const name = /\bfn\s+(?:r#)?(\w+)/.exec(fn.text)?.[1] ?? 'function'
+		const type = returned.text.replace(/\s+/g, ' ')
+		const message = `\`${name}\` returns \`${type}\` but isn't marked \`#[must_use]\`; add \`#[must_use]\` before it so callers can't ignore the error.`
+		return [{ ...issue, message }]
+	})
+}
@@ -0,0 +1,81 @@
+import { describe, expect, it } from 'vitest'
+import type { SeverityOverride } from './config.ts'
+import { applySeverityOverrides, findSeverityOverride, overrideSpecificity } from './overrides.ts'
+import type { LintIssue } from './shared.ts'
+
+function unwrap(file: string): LintIssue {
+	return {
+		ruleId: 'no-unwrap-in-lib',
+		severity: 'error',
+		message: 'Avoid unwrap() in library code.',
+		file,
+		line: 3,
+		column: 5,
+	}
+}
+
+const OVERRIDES: SeverityOverride[] = [
+	{ path: 'benches/**', rule: 'no-unwrap-in-lib', severity: 'warning' },
+	{ path: 'benches/hot/**', rule: 'no-unwrap-in-lib', severity: 'error' },
+	{ path: '**/generated/', rule: 'no-unwrap-in-lib', severity: 'off' },
+]
+
+describe('overrideSpecificity', () => {
+	it('should rank globs by literal segments, then literal characters', () => {
+		expect(overrideSpecificity('benches/hot/**')).toEqual([2, 10])
+		expect(overrideSpecificity('benches/**')).toEqual([1, 7])
+		expect(overrideSpecificity('**/*.rs')).toEqual([0, 3])
+	})
+})
+
+describe('findSeverityOverride', () => {
+	it('should pick the most specific matching glob', () => {
+		expect(findSeverityOverride(OVERRIDES, 'no-unwrap-in-lib', 'benches/hot/run.rs')).toBe(
+			OVERRIDES[1],
+		)
+		expect(findSeverityOverride(OVERRIDES, 'no-unwrap-in-lib', 'benches/cold.rs')).toBe(
+			OVERRIDES[0],
+		)
+	})
+
+	it('should match directories above the file', () => {
+		const path = 'crates/node/src/generated/types.rs'
+
+		expect(findSeverityOverride(OVERRIDES, 'no-unwrap-in-lib', path)).toBe(OVERRIDES[2])
+	})
+
+	it('should let the later of equally specific globs win', () => {
+		const overrides: SeverityOverride[] = [
+			{ path: 'benches/**', rule: 'no-dbg-macro', severity: 'warning' },
+			{ path: 'benches/**', rule: 'no-dbg-macro', severity: 'off' },
+		]
+
+		expect(findSeverityOverride(overrides, 'no-dbg-macro', 'benches/a.rs')).toBe(overrides[1])
+	})
+
+	it('should only match the override rule', () => {
+		expect(findSeverityOverride(OVERRIDES, 'no-dbg-macro', 'benches/cold.rs')).toBeUndefined()
+		expect(findSeverityOverride(OVERRIDES, 'no-unwrap-in-lib', 'src/lib.rs')).toBeUndefined()
+	})
+})
+
+describe('applySeverityOverrides', () => {
+	const config = { rules: {}, overrides: OVERRIDES }
+
+	it('should adjust the severity of issues in matching files', () => {
+		const issues = [unwrap('src/lib.rs'), unwrap('benches/cold.rs'), unwrap('benches/hot/run.rs')]
+		const leveled = applySeverityOverrides(issues, config, '/repo')
+
+		expect(leveled.map((issue) => issue.severity)).toEqual(['error', 'warning', 'error'])
+	})
+
+	it('should drop issues an override turns off', () => {
+		expect(applySeverityOverrides([unwrap('src/generated/abi.rs')], config, '/repo')).toEqual([])
+	})
+
+	it('should match paths relative to the cwd', () => {
+		const [issue] = applySeverityOverrides([unwrap('/repo/benches/cold.rs')], config, '/repo')
+
+		expect(issue?.severity).toBe('warning')
+	})
+})
@@ -0,0 +1,95 @@
+import path from 'node:path'
+import { type LintConfig, RULE_LEVEL, type SeverityOverride } from './config.ts'
+import { compileIgnorePattern, type IgnorePattern } from './globs.ts'
+import { isCoveredPath } from './rule-options.ts'
+import type { LintIssue } from './shared.ts'
+
+// Glob characters that match more than themselves
+const WILDCARD = /[*?[{]/
+
+/**
+ * How specific an override's `path` glob is, for picking between overrides matching the same
+ * file: first the number of path segments without wildcards, then the number of other
+ * characters outside wildcards. `crates/node/benches/**` is more specific than `benches/**`,
+ * which is more specific than `**` alone.
+ */
+export function overrideSpecificity(glob: string): [number, number] {
+	const segments = glob.replace(/^\/|\/$/g, '').split('/')
+	const literalSegments = segments.filter((segment) => !WILDCARD.test(segment)).length
+	const literalChars = glob.replace(/\[[^\]]*\]|\{[^}]*\}|[*?/]/g, '').length
+	return [literalSegments, literalChars]
+}
+
+interface CompiledOverride {
+	override: SeverityOverride
+	pattern: IgnorePattern
+	specificity: [number, number]
+}
+
+function compileOverrides(overrides: SeverityOverride[]): CompiledOverride[] {
+	return overrides.flatMap((override) => {
+		const pattern = compileIgnorePattern(override.path)
+		return pattern === null
+			? []
+			: [{ override, pattern, specificity: overrideSpecificity(override.path) }]
+	})
+}
+
+/**
+ * The override deciding the severity of `ruleId` in the file at `relativePath`, relative to
+ * the cwd with `/` separators, or undefined when none matches. A glob matches a file when it
+ * matches the file or a directory above it, like `include` and `exclude`. The most specific
+ * glob wins, and of equally specific ones the last listed.
+ */
+export function findSeverityOverride(
+	overrides: SeverityOverride[],
+	ruleId: string,
+	relativePath: string,
+): SeverityOverride | undefined {
+	return pickOverride(compileOverrides(overrides), ruleId, relativePath)
+}
+
+function pickOverride(
+	compiled: CompiledOverride[],
+	ruleId: string,
+	relativePath: string,
+): SeverityOverride | undefined {
+	let best: CompiledOverride | undefined
+	for (const candidate of compiled) {
+		if (candidate.override.rule !== ruleId) continue
+		if (!isCoveredPath([candidate.pattern], relativePath)) continue
+		const [segments, chars] = candidate.specificity
+		if (
+			best === undefined ||
+			segments > best.specificity[0] ||
+			(segments === best.specificity[0] && chars >= best.specificity[1])
+		) {
+			best = candidate
+		}
+	}
+	return best?.override
+}
+
+/**
+ * Adjust the severity of issues in files matched by `overrides` entries, after the levels
+ * from `[rules]` have been applied. Issues an override turns `off` are dropped. Rules that are
+ * off for the whole project don't run, so an override can't turn them back on in one place.
+ */
+export function applySeverityOverrides(
+	issues: LintIssue[],
+	config: LintConfig,
+	cwd: string = process.cwd(),
+): LintIssue[] {
+	if (!config.overrides?.length) {
+		return issues
+	}
+	const compiled = compileOverrides(config.overrides)
+
+	return issues.flatMap((issue) => {
+		const relative = path.relative(cwd, path.resolve(cwd, issue.file)).split(path.sep).join('/')
+		const override = pickOverride(compiled, issue.ruleId, relative)
+		if (override === undefined) return [issue]
+		if (override.severity === RULE_LEVEL.OFF) return []
+		return [{ ...issue, severity: override.severity }]
+	})
+}
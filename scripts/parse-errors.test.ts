@@ -0,0 +1,52 @@
+import { describe, expect, it } from 'vitest'
+import { describeParseError, filesFailingToParse } from './parse-errors.ts'
+import type { LintIssue } from './shared.ts'
+
+function parseError(file: string, line: number, code?: string): LintIssue {
+	return {
+		ruleId: 'tempo-parse-error',
+		severity: 'hint',
+		message: 'File could not be parsed',
+		file,
+		line,
+		column: 5,
+		code,
+	}
+}
+
+describe('filesFailingToParse', () => {
+	it('should list each file with a parse error once', () => {
+		const issues = [
+			parseError('src/b.rs', 4),
+			parseError('src/a.rs', 2),
+			parseError('src/b.rs', 9),
+			{ ...parseError('src/c.rs', 1), ruleId: 'no-dbg-macro' },
+		]
+
+		expect(filesFailingToParse(issues)).toEqual(['src/a.rs', 'src/b.rs'])
+	})
+})
+
+describe('describeParseError', () => {
+	it('should quote the code the parser gave up on', () => {
+		expect(describeParseError(parseError('/repo/src/lib.rs', 2, 'let x = ;'), 'src/lib.rs')).toBe(
+			'src/lib.rs:2:5: failed to parse at `let x = ;`; findings for this file may be incomplete',
+		)
+	})
+
+	it('should shorten long or multi-line code to the start of its first line', () => {
+		const long = parseError('src/lib.rs', 4, `{{#each entries}} ${'{{key}} '.repeat(8)}`)
+		const multiLine = parseError('src/lib.rs', 4, '{{#each entries}}\n    {{key}}')
+
+		expect(describeParseError(long)).toContain(
+			'at `{{#each entries}} {{key}} {{key}} {{key} ...`;',
+		)
+		expect(describeParseError(multiLine)).toContain('at `{{#each entries}} ...`;')
+	})
+
+	it('should leave the code out when there is none', () => {
+		expect(describeParseError(parseError('src/lib.rs', 2))).toBe(
+			'src/lib.rs:2:5: failed to parse; findings for this file may be incomplete',
+		)
+	})
+})
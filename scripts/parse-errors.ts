@@ -0,0 +1,33 @@
+import { type LintIssue, PARSE_ERROR_RULE_ID } from './shared.ts'
+
+// Longest stretch of unparsed code quoted in a parse error
+const MAX_EXCERPT_LENGTH = 40
+
+export function isParseError(issue: LintIssue): boolean {
+	return issue.ruleId === PARSE_ERROR_RULE_ID
+}
+
+// Files with at least one parse error, sorted, each listed once
+export function filesFailingToParse(issues: LintIssue[]): string[] {
+	return [...new Set(issues.filter(isParseError).map((issue) => issue.file))].sort()
+}
+
+/**
+ * The parse error as printed: where it is and the code the parser gave up on, shortened to its
+ * first line. The parser only marks the region it couldn't make sense of, so there's no message
+ * saying what it expected instead.
+ */
+export function describeParseError(issue: LintIssue, file: string = issue.file): string {
+	const location = `${file}:${issue.line}:${issue.column}`
+	const excerpt = quoteExcerpt(issue.code ?? '')
+	const near = excerpt === null ? '' : ` at ${excerpt}`
+	return `${location}: failed to parse${near}; findings for this file may be incomplete`
+}
+
+function quoteExcerpt(code: string): string | null {
+	const lines = code.trim().split('\n')
+	const text = lines[0]!.trim().replace(/\s+/g, ' ')
+	if (text === '') return null
+	const shortened = text.length > MAX_EXCERPT_LENGTH || lines.length > 1
+	return `\`${shortened ? `${text.slice(0, MAX_EXCERPT_LENGTH).trimEnd()} ...` : text}\``
+}
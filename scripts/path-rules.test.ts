@@ -0,0 +1,71 @@
+import { describe, expect, it } from 'vitest'
+import { loadPathRules, parsePathRule } from './path-rules.ts'
+import { applyRules } from './plugins.ts'
+import { LANG } from './shared.ts'
+
+const MOD_RS_RULE = [
+	'id: no-mod-rs',
+	'message: "Rename this mod.rs to `$PARENT.rs`."',
+	'severity: off',
+	'language: rust',
+	'files:',
+	'  - mod.rs',
+	'ignores:',
+	'  # Test helpers',
+	'  - "**/tests/**/mod.rs"',
+	'',
+].join('\n')
+
+describe('parsePathRule', () => {
+	it('should read the metadata and globs', () => {
+		const rule = parsePathRule(MOD_RS_RULE, 'no-mod-rs.yml')
+
+		expect(rule).toMatchObject({ id: 'no-mod-rs', severity: 'off', language: 'rust' })
+		expect(rule.files).toHaveLength(1)
+		expect(rule.ignores).toHaveLength(1)
+	})
+
+	it('should reject rules without files', () => {
+		expect(() => parsePathRule('id: empty\nmessage: "Empty"\n', 'empty.yml')).toThrow(
+			"empty.yml: path rule 'empty' needs a non-empty 'files' list",
+		)
+	})
+
+	it('should reject rules without a known language', () => {
+		const content = MOD_RS_RULE.replace('language: rust', 'language: go')
+
+		expect(() => parsePathRule(content, 'no-mod-rs.yml')).toThrow(
+			"no-mod-rs.yml: path rule 'no-mod-rs' has no known 'language'",
+		)
+	})
+})
+
+describe('loadPathRules', () => {
+	it('should load the packaged rust path rules', () => {
+		expect(loadPathRules(LANG.RUST).map((rule) => rule.id)).toContain('no-mod-rs')
+		expect(loadPathRules(LANG.TYPESCRIPT)).toEqual([])
+	})
+})
+
+describe('path rules in applyRules', () => {
+	const rules = [parsePathRule(MOD_RS_RULE, 'no-mod-rs.yml')]
+	const files = ['src/lib.rs', 'src/net/mod.rs', 'crates/node/tests/common/mod.rs']
+	const enabled = { rules: { 'no-mod-rs': 'error' as const } }
+
+	it('should flag every selected file at its start', () => {
+		expect(applyRules(files, rules, enabled, { cwd: '/repo' })).toEqual([
+			{
+				ruleId: 'no-mod-rs',
+				severity: 'error',
+				message: 'Rename this mod.rs to `net.rs`.',
+				file: 'src/net/mod.rs',
+				line: 1,
+				column: 1,
+			},
+		])
+	})
+
+	it('should skip rules that are off', () => {
+		expect(applyRules(files, rules, { rules: {} }, { cwd: '/repo' })).toEqual([])
+	})
+})
@@ -0,0 +1,79 @@
+import fs from 'node:fs'
+import path from 'node:path'
+import { compileIgnorePattern, type IgnorePattern } from './globs.ts'
+import { isRuleLanguage, type Rule } from './plugins.ts'
+import { isCoveredPath } from './rule-options.ts'
+import {
+	getPathRuleDirs,
+	type Language,
+	PACKAGE_ROOT,
+	parseRuleMetadata,
+	readYamlList,
+} from './shared.ts'
+
+/**
+ * A rule about which files exist rather than what they contain, e.g. one flagging `mod.rs`
+ * files. Its YAML has the usual metadata and `files`/`ignores` globs, like an ast-grep rule,
+ * but no `rule`: every scanned file the globs select is a finding, at the start of the file.
+ * In the message `$FILE` is the file name and `$PARENT` the name of the directory holding it.
+ */
+export interface PathRule extends Rule {
+	files: IgnorePattern[]
+	ignores: IgnorePattern[]
+}
+
+/**
+ * Parse a path rule file, throwing with a message naming `source` when it has no `id`, selects
+ * no files or names no known language. Globs match paths relative to the cwd, like `include`
+ * and `exclude`.
+ */
+export function parsePathRule(content: string, source: string): PathRule {
+	const metadata = parseRuleMetadata(content)
+	if (!metadata) {
+		throw new Error(`${source}: path rule has no id`)
+	}
+	const files = compileGlobs(readYamlList(content, 'files'))
+	if (files.length === 0) {
+		throw new Error(`${source}: path rule '${metadata.id}' needs a non-empty 'files' list`)
+	}
+	const language = content.match(/^language:\s*(\w+)/m)?.[1]
+	if (!isRuleLanguage(language)) {
+		throw new Error(`${source}: path rule '${metadata.id}' has no known 'language'`)
+	}
+	const ignores = compileGlobs(readYamlList(content, 'ignores'))
+	return {
+		...metadata,
+		language,
+		files,
+		ignores,
+		check: (file) => {
+			if (!isCoveredPath(files, file.relativePath) || isCoveredPath(ignores, file.relativePath)) {
+				return []
+			}
+			const parent = path.basename(path.dirname(path.resolve(file.path)))
+			const message = metadata.message
+				.replaceAll('$FILE', path.basename(file.path))
+				.replaceAll('$PARENT', parent)
+			return [{ line: 1, column: 1, message }]
+		},
+	}
+}
+
+function compileGlobs(globs: string[] | undefined): IgnorePattern[] {
+	return (globs ?? [])
+		.map((glob) => compileIgnorePattern(glob))
+		.filter((pattern): pattern is IgnorePattern => pattern !== null)
+}
+
+export function loadPathRules(language: Language, packageRoot: string = PACKAGE_ROOT): PathRule[] {
+	const rules: PathRule[] = []
+	for (const dir of getPathRuleDirs(language, packageRoot)) {
+		if (!fs.existsSync(dir)) continue
+		for (const name of fs.readdirSync(dir).sort()) {
+			if (!name.endsWith('.yml')) continue
+			const file = path.join(dir, name)
+			rules.push(parsePathRule(fs.readFileSync(file, 'utf8'), file))
+		}
+	}
+	return rules
+}
@@ -0,0 +1,146 @@
+import path from 'node:path'
+import { describe, expect, it } from 'vitest'
+import { applyRules, loadPlugins, type Rule, validatePlugin } from './plugins.ts'
+import { PACKAGE_ROOT } from './shared.ts'
+
+const PLUGIN_DIR = path.join(PACKAGE_ROOT, 'test-fixtures/plugins')
+
+function rule(overrides: Partial<Rule> = {}): Rule {
+	return {
+		id: 'no-todo-macro',
+		message: 'Replace todo!() before merging.',
+		severity: 'warning',
+		language: 'rust',
+		check: (file) =>
+			file.source.split('\n').flatMap((line, i) => {
+				const column = line.indexOf('todo!(')
+				return column === -1 ? [] : [{ line: i + 1, column: column + 1 }]
+			}),
+		...overrides,
+	}
+}
+
+describe('validatePlugin', () => {
+	it('should return the exported rules', () => {
+		const rules = [rule()]
+
+		expect(validatePlugin({ rules }, 'house.ts')).toEqual(rules)
+	})
+
+	it('should reject modules without a rules array', () => {
+		expect(() => validatePlugin({ default: [rule()] }, 'house.ts')).toThrow(
+			"Plugin 'house.ts' must export a 'rules' array",
+		)
+	})
+
+	it('should name the rule and what is wrong with it', () => {
+		expect(() => validatePlugin({ rules: [rule({ id: 'No_Todo' })] }, 'house.ts')).toThrow(
+			"Plugin 'house.ts', rule 'No_Todo': 'id' must be a kebab-case string",
+		)
+		expect(() => validatePlugin({ rules: [rule({ severity: 'hint' })] }, 'house.ts')).toThrow(
+			"rule 'no-todo-macro': 'severity' must be one of: error, warning, off",
+		)
+		expect(() => validatePlugin({ rules: [{ ...rule(), check: undefined }] }, 'house.ts')).toThrow(
+			"rule 'no-todo-macro': 'check' must be a function",
+		)
+		expect(() => validatePlugin({ rules: [null] }, 'house.ts')).toThrow("rule #1: 'id'")
+	})
+})
+
+describe('loadPlugins', () => {
+	it('should load rules from modules relative to the base directory', async () => {
+		const rules = await loadPlugins(['house-rules.ts'], PLUGIN_DIR, new Set())
+
+		expect(rules.map((loaded) => loaded.id)).toEqual(['require-license-header'])
+	})
+
+	it('should reject rule ids that are already taken', async () => {
+		await expect(
+			loadPlugins(['house-rules.ts'], PLUGIN_DIR, new Set(['require-license-header'])),
+		).rejects.toThrow("Plugin 'house-rules.ts': rule id 'require-license-header' is already taken")
+	})
+
+	it('should reject missing modules', async () => {
+		await expect(loadPlugins(['missing.ts'], PLUGIN_DIR, new Set())).rejects.toThrow(
+			"Plugin 'missing.ts' not found",
+		)
+	})
+})
+
+describe('applyRules', () => {
+	const sources: Record<string, string> = {
+		'src/lib.rs': 'fn parse() {\n    todo!()\n}\n',
+		'src/main.rs': 'fn main() {}\n',
+		'web/index.ts': 'todo!()\n',
+	}
+	const readSource = (file: string) => sources[file]!
+	const files = Object.keys(sources)
+
+	it('should turn findings into issues at the configured level', () => {
+		const config = { rules: { 'no-todo-macro': 'error' as const } }
+
+		expect(applyRules(files, [rule()], config, { readSource })).toEqual([
+			{
+				ruleId: 'no-todo-macro',
+				severity: 'error',
+				message: 'Replace todo!() before merging.',
+				file: 'src/lib.rs',
+				line: 2,
+				column: 5,
+			},
+		])
+	})
+
+	it('should keep per-finding messages and end positions', () => {
+		const check = () => [{ line: 1, column: 1, endLine: 1, endColumn: 3, message: 'Custom.' }]
+		const [issue] = applyRules(['src/main.rs'], [rule({ check })], { rules: {} }, { readSource })
+
+		expect(issue).toMatchObject({ message: 'Custom.', endLine: 1, endColumn: 3 })
+	})
+
+	it('should skip rules that are off and files of other languages', () => {
+		const off = { rules: { 'no-todo-macro': 'off' as const } }
+		const checked: string[] = []
+		const check: Rule['check'] = (file) => {
+			checked.push(file.path)
+			return []
+		}
+
+		expect(applyRules(files, [rule()], off, { readSource })).toEqual([])
+		applyRules(files, [rule({ check })], { rules: {} }, { readSource })
+		expect(checked).toEqual(['src/lib.rs', 'src/main.rs'])
+	})
+
+	it('should only read sources that a rule asks for', () => {
+		const read: string[] = []
+		const trackedRead = (file: string) => {
+			read.push(file)
+			return ''
+		}
+		const check = () => []
+		applyRules(files, [rule({ check })], { rules: {} }, { readSource: trackedRead })
+
+		expect(read).toEqual([])
+	})
+
+	it('should report how long each rule took on each file', () => {
+		const timings: string[] = []
+		const onTiming = (ruleId: string, file: string, ms: number) => {
+			expect(ms).toBeGreaterThanOrEqual(0)
+			timings.push(`${ruleId} ${file}`)
+		}
+		applyRules(files, [rule()], { rules: {} }, { readSource, onTiming })
+
+		expect(timings).toEqual(['no-todo-macro src/lib.rs', 'no-todo-macro src/main.rs'])
+	})
+
+	it('should name the rule and file when a rule throws', () => {
+		const check = () => {
+			throw new Error('boom')
+		}
+
+		expect(() => applyRules(files, [rule({ check })], { rules: {} }, { readSource })).toThrow(
+			"Rule 'no-todo-macro' failed on src/lib.rs: boom",
+		)
+	})
+})
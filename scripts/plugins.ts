@@ -0,0 +1,212 @@
+import fs from 'node:fs'
+import path from 'node:path'
+import { pathToFileURL } from 'node:url'
+import { type LintConfig, RULE_LEVEL, VALID_RULE_LEVELS } from './config.ts'
+import { getLanguageExtensions } from './files.ts'
+import { LANG, type Language, type LintIssue, type RuleMetadata } from './shared.ts'
+
+export type RuleLanguage = Exclude<Language, typeof LANG.ALL>
+
+export interface SourceFile {
+	// Path as scanned, relative to the cwd unless the scan path was absolute
+	path: string
+	// The same file relative to the cwd with `/` separators, for matching globs
+	relativePath: string
+	// Contents of the file, read on first use
+	readonly source: string
+}
+
+export interface Finding {
+	// 1-based, like editors count
+	line: number
+	column: number
+	// Just past the last character of the finding
+	endLine?: number
+	endColumn?: number
+	// Replaces the rule's message for this finding only
+	message?: string
+}
+
+/**
+ * A lint rule written in TypeScript, run next to the ast-grep rules. Plugins are modules listed
+ * under `plugins` in tempo-lints.toml whose `rules` export is an array of these. Every scanned
+ * file of the rule's language is passed to `check`, and its findings then go through config
+ * levels, `--rules`, allow annotations, baselines and every output format like any other.
+ * Built-in path rules such as `no-mod-rs` are `Rule`s too.
+ *
+ * `Rule`, `SourceFile` and `Finding` are a stable interface for plugins: fields may be added,
+ * but existing ones keep their names and meaning.
+ */
+export interface Rule extends RuleMetadata {
+	// Files with one of this language's extensions are checked
+	language: RuleLanguage
+	check(file: SourceFile): Finding[]
+}
+
+// Same shape as built-in rule ids, so they can be used in `[rules]` and allow annotations
+const RULE_ID = /^[a-z][a-z0-9]*(?:-[a-z0-9]+)*$/
+const RULE_LANGUAGES = [LANG.RUST, LANG.TYPESCRIPT]
+
+/**
+ * Import the plugin modules at `specifiers`, paths relative to `baseDir`, and return their
+ * rules. Throws with a message naming the plugin when one can't be loaded, exports something
+ * other than rules, or reuses a rule id from `takenIds` or another plugin.
+ */
+export async function loadPlugins(
+	specifiers: string[],
+	baseDir: string,
+	takenIds: Set<string>,
+): Promise<Rule[]> {
+	const rules: Rule[] = []
+	const ids = new Set(takenIds)
+	for (const specifier of specifiers) {
+		const file = path.resolve(baseDir, specifier)
+		if (!fs.existsSync(file)) {
+			throw new Error(`Plugin '${specifier}' not found at ${file}`)
+		}
+		let module: Record<string, unknown>
+		try {
+			module = await import(pathToFileURL(file).href)
+		} catch (err) {
+			throw new Error(`Could not load plugin '${specifier}': ${(err as Error).message}`)
+		}
+		for (const rule of validatePlugin(module, specifier)) {
+			if (ids.has(rule.id)) {
+				throw new Error(`Plugin '${specifier}': rule id '${rule.id}' is already taken`)
+			}
+			ids.add(rule.id)
+			rules.push(rule)
+		}
+	}
+	return rules
+}
+
+/**
+ * Check that a plugin module exports `rules` correctly, since plugins are plain modules the
+ * type checker may never have seen.
+ */
+export function validatePlugin(module: Record<string, unknown>, source: string): Rule[] {
+	const { rules } = module
+	if (!Array.isArray(rules)) {
+		throw new Error(`Plugin '${source}' must export a 'rules' array`)
+	}
+	return rules.map((rule, i) => {
+		const problem = findRuleProblem(rule)
+		if (problem !== null) {
+			const name = typeof rule?.id === 'string' ? `rule '${rule.id}'` : `rule #${i + 1}`
+			throw new Error(`Plugin '${source}', ${name}: ${problem}`)
+		}
+		return rule
+	})
+}
+
+function findRuleProblem(rule: Partial<Rule> | null): string | null {
+	if (typeof rule?.id !== 'string' || !RULE_ID.test(rule.id)) {
+		return "'id' must be a kebab-case string"
+	}
+	if (typeof rule.message !== 'string' || rule.message.trim() === '') {
+		return "'message' must be a non-empty string"
+	}
+	if (!VALID_RULE_LEVELS.some((level) => level === rule.severity)) {
+		return `'severity' must be one of: ${VALID_RULE_LEVELS.join(', ')}`
+	}
+	if (!isRuleLanguage(rule.language)) {
+		return `'language' must be one of: ${RULE_LANGUAGES.join(', ')}`
+	}
+	if (typeof rule.check !== 'function') {
+		return "'check' must be a function"
+	}
+	return null
+}
+
+export function isRuleLanguage(value: unknown): value is RuleLanguage {
+	return RULE_LANGUAGES.some((language) => language === value)
+}
+
+// Rules that run when linting `language`
+export function getLanguageRules(rules: Rule[], language: Language): Rule[] {
+	return language === LANG.ALL ? rules : rules.filter((rule) => rule.language === language)
+}
+
+export interface ApplyRulesOptions {
+	// Reads a file's source; defaults to reading from disk
+	readSource?: (file: string) => string
+	// Directory that `relativePath` is relative to
+	cwd?: string
+	// Told how long each rule took on each file, for --stats
+	onTiming?: (ruleId: string, file: string, ms: number) => void
+}
+
+/**
+ * Run `rules` over the scanned `files`. Rules whose level is `off`, in the config or by
+ * default, are skipped, and each file only goes to the rules for its language. A rule that
+ * throws fails the run with the rule and file in the message.
+ */
+export function applyRules(
+	files: string[],
+	rules: Rule[],
+	config: LintConfig,
+	options: ApplyRulesOptions = {},
+): LintIssue[] {
+	const readSource = options.readSource ?? readFromDisk
+	const cwd = options.cwd ?? process.cwd()
+	const active = rules
+		.map((rule) => ({ rule, level: config.rules[rule.id] ?? rule.severity }))
+		.filter(({ level }) => level !== RULE_LEVEL.OFF)
+	const issues: LintIssue[] = []
+
+	for (const file of files) {
+		const extension = path.extname(file)
+		const checks = active.filter(({ rule }) =>
+			getLanguageExtensions(rule.language).includes(extension),
+		)
+		if (checks.length === 0) continue
+
+		let source: string | undefined
+		const sourceFile: SourceFile = {
+			path: file,
+			relativePath: path.relative(cwd, path.resolve(cwd, file)).split(path.sep).join('/'),
+			get source() {
+				source ??= readSource(file)
+				return source
+			},
+		}
+		for (const { rule, level } of checks) {
+			let findings: Finding[]
+			const started = performance.now()
+			try {
+				findings = rule.check(sourceFile)
+			} catch (err) {
+				throw new Error(`Rule '${rule.id}' failed on ${file}: ${(err as Error).message}`)
+			}
+			options.onTiming?.(rule.id, file, performance.now() - started)
+			for (const finding of findings) {
+				issues.push({
+					ruleId: rule.id,
+					severity: level,
+					message: finding.message ?? rule.message,
+					file,
+					line: finding.line,
+					column: finding.column,
+					...(finding.endLine === undefined ? {} : { endLine: finding.endLine }),
+					...(finding.endColumn === undefined ? {} : { endColumn: finding.endColumn }),
+				})
+			}
+		}
+	}
+	return issues
+}
+
+function readFromDisk(file: string): string {
+	return fs.readFileSync(file, 'utf8')
+}
+
+// Just the metadata, for formatters listing the rules that ran
+export function toRuleMetadata(rule: Rule): RuleMetadata {
+	return {
+		id: rule.id,
+		message: rule.message,
+		severity: rule.severity,
+		...(rule.note === undefined ? {} : { note: rule.note }),
+	}
+}
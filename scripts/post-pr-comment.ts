@@ -1,19 +1,17 @@
 #!/usr/bin/env tsx
 
 import fs from 'node:fs'
+import { parseJsonReport } from './formatters.ts'
 import {
 	COMMENT_SIGNATURE,
 	countBySeverity,
-	getValidRuleIds,
 	groupByFile,
 	groupByRule,
 	isValidLanguage,
-	type Language,
 	type LintIssue,
 	MAX_FILES_TO_DISPLAY,
 	MAX_ISSUES_PER_FILE,
 	MAX_ISSUES_PER_RULE,
-	parseLintIssues,
 	pluralize,
 	warn,
 } from './shared.ts'
@@ -192,19 +190,18 @@ async function main(
 	totalIssues: number,
 	repo: string,
 	prNumber: string,
-	language: Language,
 	githubToken: string,
 ): Promise<void> {
 	let issues: LintIssue[] = []
 	if (outputFile && fs.existsSync(outputFile)) {
 		try {
 			const content = fs.readFileSync(outputFile, 'utf8')
-			// Get valid rule IDs to filter out non-tempo lint entries
-			const validRuleIds = getValidRuleIds(language)
-			const result = parseLintIssues(content, validRuleIds)
+			const result = parseJsonReport(content)
 			if (result.error) {
 				warn(`Lint output: ${result.error}`)
 			}
+			// The report only holds findings of the rules the run used, plugin rules included, so
+			// every one of them is shown
 			issues = result.issues
 		} catch (err) {
 			warn(`Failed to read lint output file: ${(err as Error).message}`)
@@ -264,10 +261,7 @@ if (import.meta.url === `file://${process.argv[1]}`) {
 		process.exit(1)
 	}
 
-	// Type assertions safe after validation
-	const language: Language = languageArg as Language
-
-	main(outputFile ?? '', totalIssues, repo, prNumber, language, githubToken).catch((err: Error) => {
+	main(outputFile ?? '', totalIssues, repo, prNumber, githubToken).catch((err: Error) => {
 		warn(err.message)
 		process.exit(1)
 	})
@@ -0,0 +1,275 @@
+import fs from 'node:fs'
+import path from 'node:path'
+import { describe, expect, it } from 'vitest'
+import { CLI_FINISHED_RULE_IDS, type LintConfig } from './config.ts'
+import {
+	allowEntryToGlobs,
+	applyAllowedPaths,
+	BARE_RETURN_FILTER,
+	buildMarkersPattern,
+	buildRuleOverrides,
+	buildVendorOverrides,
+	DEFAULT_LOG_MACROS_PATTERN,
+	DEFAULT_MAX_LINES_PATTERN,
+	DEFAULT_MAX_WIDTH_PATTERN,
+	DEFAULT_TODO_MARKERS_PATTERN,
+	DEFAULT_UNWRAP_METHODS_PATTERN,
+	dropLibraryIgnores,
+} from './rule-options.ts'
+import { getRuleDirs, LANG, type LintIssue } from './shared.ts'
+
+describe('buildMarkersPattern', () => {
+	it('should escape regex syntax in markers', () => {
+		expect(buildMarkersPattern(['TODO', 'SAFETY-TODO', 'N.B.'])).toBe(
+			'(?:TODO|SAFETY\\-TODO|N\\.B\\.)',
+		)
+	})
+
+	it('should reproduce the default pattern for the default markers', () => {
+		expect(buildMarkersPattern(['TODO', 'FIXME', 'XXX', 'HACK'])).toBe(
+			DEFAULT_TODO_MARKERS_PATTERN,
+		)
+	})
+})
+
+describe('buildRuleOverrides', () => {
+	const ruleDirs = getRuleDirs(LANG.ALL)
+
+	it('should not override anything by default', () => {
+		expect(buildRuleOverrides(ruleDirs, { rules: {} }).size).toBe(0)
+	})
+
+	it('should rewrite the markers of every no-todo-comment rule', () => {
+		const overrides = buildRuleOverrides(ruleDirs, {
+			rules: {},
+			ruleOptions: { 'no-todo-comment': { markers: ['TODO', "DON'T-SHIP"] } },
+		})
+
+		expect([...overrides.keys()]).toEqual([
+			path.join(ruleDirs[0]!, 'no-todo-comment.yml'),
+			path.join(ruleDirs[2]!, 'no-todo-comment.yml'),
+		])
+		for (const [file, content] of overrides) {
+			const original = fs.readFileSync(file, 'utf8')
+			expect(content).toBe(
+				original.replace(DEFAULT_TODO_MARKERS_PATTERN, "(?:TODO|DON''T\\-SHIP)"),
+			)
+		}
+	})
+})
+
+describe('buildRuleOverrides for no-unwrap-in-lib', () => {
+	const ruleDirs = getRuleDirs(LANG.RUST)
+	const file = path.join(ruleDirs[1]!, 'no-unwrap-in-lib.yml')
+	const withMethods = (methods: string[]) =>
+		buildRuleOverrides(ruleDirs, { rules: {}, ruleOptions: { 'no-unwrap-in-lib': { methods } } })
+
+	it('should match the methods in the rule file by default', () => {
+		expect(fs.readFileSync(file, 'utf8')).toContain(DEFAULT_UNWRAP_METHODS_PATTERN)
+	})
+
+	it('should only report the configured methods', () => {
+		const overrides = withMethods(['unwrap', 'unwrap_unchecked'])
+
+		expect(overrides.get(file)).toBe(
+			fs
+				.readFileSync(file, 'utf8')
+				.replace(DEFAULT_UNWRAP_METHODS_PATTERN, "'^(unwrap|unwrap_unchecked)$'"),
+		)
+	})
+
+	it('should reject unknown methods and an empty list', () => {
+		expect(() => withMethods(['unwrap', 'expect'])).toThrow(
+			'Unknown no-unwrap-in-lib methods: expect. Must be some of: unwrap, unwrap_err, unwrap_unchecked',
+		)
+		expect(() => withMethods([])).toThrow('no-unwrap-in-lib needs at least one method')
+	})
+})
+
+describe('buildRuleOverrides for max-function-length', () => {
+	it('should rewrite the line-break count to the configured limit', () => {
+		const ruleDirs = getRuleDirs(LANG.RUST)
+		const overrides = buildRuleOverrides(ruleDirs, {
+			rules: {},
+			ruleOptions: { 'max-function-length': { maxLines: 80 } },
+		})
+		const file = path.join(ruleDirs[1]!, 'max-function-length.yml')
+
+		expect([...overrides.keys()]).toEqual([file])
+		expect(overrides.get(file)).toBe(
+			fs.readFileSync(file, 'utf8').replace(DEFAULT_MAX_LINES_PATTERN, "){80}'"),
+		)
+	})
+})
+
+describe('buildRuleOverrides for max-line-length', () => {
+	it('should rewrite the character count to one past the configured width', () => {
+		const ruleDirs = getRuleDirs(LANG.RUST)
+		const overrides = buildRuleOverrides(ruleDirs, {
+			rules: {},
+			ruleOptions: { 'max-line-length': { maxWidth: 120 } },
+		})
+		const file = path.join(ruleDirs[1]!, 'max-line-length.yml')
+
+		expect(overrides.get(file)).toBe(
+			fs.readFileSync(file, 'utf8').replace(DEFAULT_MAX_WIDTH_PATTERN, ']{121}|'),
+		)
+	})
+})
+
+describe('buildRuleOverrides for no-string-format-in-log', () => {
+	it('should reproduce the default pattern for the default macros', () => {
+		expect(buildMarkersPattern(['info', 'warn', 'error', 'debug', 'trace'])).toBe(
+			DEFAULT_LOG_MACROS_PATTERN,
+		)
+	})
+
+	it('should rewrite the macro names to the configured ones', () => {
+		const ruleDirs = getRuleDirs(LANG.RUST)
+		const overrides = buildRuleOverrides(ruleDirs, {
+			rules: {},
+			ruleOptions: { 'no-string-format-in-log': { macros: ['info', 'log_warn'] } },
+		})
+		const file = path.join(ruleDirs[1]!, 'no-string-format-in-log.yml')
+
+		expect([...overrides.keys()]).toEqual([file])
+		expect(overrides.get(file)).toBe(
+			fs.readFileSync(file, 'utf8').replace(DEFAULT_LOG_MACROS_PATTERN, '(?:info|log_warn)'),
+		)
+	})
+})
+
+describe('buildRuleOverrides for no-redundant-return', () => {
+	it('should drop the bare return filter with bare-returns', () => {
+		const ruleDirs = getRuleDirs(LANG.RUST)
+		const file = path.join(ruleDirs[1]!, 'no-redundant-return.yml')
+		const withBareReturns = (bareReturns: boolean) =>
+			buildRuleOverrides(ruleDirs, {
+				rules: {},
+				ruleOptions: { 'no-redundant-return': { bareReturns } },
+			})
+
+		expect(fs.readFileSync(file, 'utf8')).toContain(BARE_RETURN_FILTER)
+		expect(withBareReturns(false).size).toBe(0)
+		expect(withBareReturns(true).get(file)).toBe(
+			fs.readFileSync(file, 'utf8').replace(BARE_RETURN_FILTER, ''),
+		)
+	})
+})
+
+describe('buildRuleOverrides for opt-in rules', () => {
+	const ruleDirs = getRuleDirs(LANG.RUST)
+	const file = path.join(ruleDirs[1]!, 'no-shadowing.yml')
+
+	it('should turn the rule on at the configured level', () => {
+		const overrides = buildRuleOverrides(ruleDirs, { rules: { 'no-shadowing': 'error' } })
+
+		expect([...overrides.keys()]).toEqual([file])
+		expect(overrides.get(file)).toBe(
+			fs.readFileSync(file, 'utf8').replace(/^severity: off$/m, 'severity: error'),
+		)
+	})
+
+	it('should leave rules that are on by default alone', () => {
+		expect(buildRuleOverrides(ruleDirs, { rules: { 'no-dbg-macro': 'warning' } }).size).toBe(0)
+	})
+})
+
+describe('buildVendorOverrides', () => {
+	const ruleDirs = getRuleDirs(LANG.RUST)
+
+	it('should turn off every rule the CLI finishes', () => {
+		const overrides = buildVendorOverrides(ruleDirs)
+
+		expect([...overrides.keys()].map((file) => path.basename(file, '.yml'))).toEqual(
+			CLI_FINISHED_RULE_IDS,
+		)
+		for (const [file, content] of overrides) {
+			expect(content).toMatch(/^severity: off$/m)
+			expect(content.replace('severity: off', '')).toBe(
+				fs.readFileSync(file, 'utf8').replace(/^severity: \w+$/m, ''),
+			)
+		}
+	})
+
+	it('should leave other rules alone', () => {
+		expect(buildVendorOverrides(getRuleDirs(LANG.TYPESCRIPT)).size).toBe(0)
+	})
+})
+
+describe('dropLibraryIgnores', () => {
+	const ruleDirs = getRuleDirs(LANG.RUST)
+
+	it('should remove the ignores of library-scoped rules and return them', () => {
+		const overrides = new Map<string, string>()
+		const dropped = dropLibraryIgnores(overrides, ruleDirs)
+		const file = path.join(ruleDirs[1]!, 'no-unwrap-in-lib.yml')
+
+		expect(dropped.get('no-unwrap-in-lib')?.length).toBeGreaterThan(0)
+		expect(overrides.get(file)).not.toContain('ignores:')
+		expect(overrides.get(file)).toContain('files:')
+		expect(dropped.has('no-dbg-macro')).toBe(false)
+	})
+
+	it('should build on other rewrites of the rule', () => {
+		const file = path.join(ruleDirs[1]!, 'no-unwrap-in-lib.yml')
+		const overrides = new Map([[file, `${fs.readFileSync(file, 'utf8')}# rewritten\n`]])
+		dropLibraryIgnores(overrides, ruleDirs)
+
+		expect(overrides.get(file)).toContain('# rewritten')
+		expect(overrides.get(file)).not.toContain('ignores:')
+	})
+})
+
+describe('allowEntryToGlobs', () => {
+	it('should keep path globs as they are', () => {
+		expect(allowEntryToGlobs('crates/sys/**')).toEqual(['crates/sys/**'])
+	})
+
+	it('should map module paths to the module file and directory', () => {
+		expect(allowEntryToGlobs('ffi::raw')).toEqual(['**/src/ffi/raw.rs', '**/src/ffi/raw/**'])
+		expect(allowEntryToGlobs('crate::ffi')).toEqual(['**/src/ffi.rs', '**/src/ffi/**'])
+		expect(allowEntryToGlobs('crate::')).toEqual(['**/src/**'])
+	})
+})
+
+describe('applyAllowedPaths', () => {
+	const cwd = path.resolve('/repo')
+	const unsafeIssue = (file: string): LintIssue => ({
+		ruleId: 'no-unsafe-block',
+		severity: 'warning',
+		message: 'Unsafe block is not allowed outside approved modules.',
+		file,
+		line: 1,
+		column: 1,
+	})
+	const config: LintConfig = {
+		rules: {},
+		ruleOptions: { 'no-unsafe-block': { allow: ['crates/sys/', 'ffi::raw'] } },
+	}
+
+	it('should drop findings in allowed files and directories', () => {
+		const issues = [
+			unsafeIssue('crates/sys/src/lib.rs'),
+			unsafeIssue('crates/core/src/ffi/raw.rs'),
+			unsafeIssue(path.join(cwd, 'crates/core/src/ffi/raw/bindings.rs')),
+			unsafeIssue('crates/core/src/ffi.rs'),
+		]
+
+		expect(applyAllowedPaths(issues, config, cwd).map((issue) => issue.file)).toEqual([
+			'crates/core/src/ffi.rs',
+		])
+	})
+
+	it('should leave other rules alone', () => {
+		const issue = { ...unsafeIssue('crates/sys/src/lib.rs'), ruleId: 'no-dbg-macro' }
+
+		expect(applyAllowedPaths([issue], config, cwd)).toEqual([issue])
+	})
+
+	it('should return issues unchanged without an allowlist', () => {
+		const issues = [unsafeIssue('src/lib.rs')]
+
+		expect(applyAllowedPaths(issues, { rules: {} }, cwd)).toBe(issues)
+	})
+})
@@ -0,0 +1,259 @@
+import fs from 'node:fs'
+import path from 'node:path'
+import {
+	CLI_FINISHED_RULE_IDS,
+	FUNCTION_LENGTH_RULE_ID,
+	LINE_LENGTH_RULE_ID,
+	type LintConfig,
+	LOG_FORMAT_RULE_ID,
+	REDUNDANT_RETURN_RULE_ID,
+	RULE_LEVEL,
+	TODO_RULE_ID,
+	UNWRAP_RULE_ID,
+} from './config.ts'
+import { DEFAULT_MAX_FUNCTION_LINES } from './function-length.ts'
+import { compileIgnorePattern, type IgnorePattern, matchIgnorePatterns } from './globs.ts'
+import { DEFAULT_MAX_LINE_WIDTH } from './line-length.ts'
+import type { LintIssue } from './shared.ts'
+
+// Marker alternation in the no-todo-comment rule files, replaced when markers are configured
+export const DEFAULT_TODO_MARKERS_PATTERN = '(?:TODO|FIXME|XXX|HACK)'
+
+// Line-break count in the max-function-length candidate regex, replaced when max-lines is set
+export const DEFAULT_MAX_LINES_PATTERN = `){${DEFAULT_MAX_FUNCTION_LINES}}'`
+
+// Character count in the max-line-length candidate regex, replaced when max-width is set
+export const DEFAULT_MAX_WIDTH_PATTERN = `]{${DEFAULT_MAX_LINE_WIDTH + 1}}|`
+
+// Macro name alternation in the no-string-format-in-log rule, replaced when macros are configured
+export const DEFAULT_LOG_MACROS_PATTERN = '(?:info|warn|error|debug|trace)'
+
+// Methods no-unwrap-in-lib reports by default, in the order of its METHOD regex
+export const UNWRAP_METHODS = ['unwrap', 'unwrap_err', 'unwrap_unchecked']
+export const DEFAULT_UNWRAP_METHODS_PATTERN = `'^(${UNWRAP_METHODS.join('|')})$'`
+
+// Filter keeping a bare `return;` out of no-redundant-return, removed when bare-returns is set
+export const BARE_RETURN_FILTER = "  not:\n    regex: '^return\\s*;?$'\n"
+
+// How an opt-in rule file declares its severity; ast-grep skips the rule until it's replaced
+const OPT_IN_SEVERITY = /^severity: off$/m
+const RULE_SEVERITY = /^severity: \w+$/m
+
+// Library-scoped rules are named `*-in-lib` and tell other code apart by path with `ignores`
+const LIBRARY_RULE_SUFFIX = '-in-lib'
+const IGNORES_BLOCK = /^ignores:\n((?:[ \t]+-[ \t].*\n?)+)/m
+
+// Characters with a meaning in Rust regex syntax, which ast-grep uses for `regex:`
+const REGEX_META = /[\\.+*?()|[\]{}^$#&~-]/g
+
+/**
+ * Build the regex alternation matching any of `markers` literally.
+ */
+export function buildMarkersPattern(markers: string[]): string {
+	return `(?:${markers.map((marker) => marker.trim().replace(REGEX_META, '\\$&')).join('|')})`
+}
+
+/**
+ * Rule files whose contents depend on the config, keyed by path, mapped to the YAML to scan
+ * with instead. ast-grep can't parameterize rules, so options like custom TODO markers are
+ * applied by rewriting a copy of the rule file.
+ */
+export function buildRuleOverrides(ruleDirs: string[], config: LintConfig): Map<string, string> {
+	const overrides = new Map<string, string>()
+
+	const markers = config.ruleOptions?.[TODO_RULE_ID]?.markers
+	if (markers) {
+		// The pattern goes into a single-quoted YAML string, where `'` is escaped by doubling it
+		const pattern = buildMarkersPattern(markers).replaceAll("'", "''")
+		replaceInRule(overrides, ruleDirs, TODO_RULE_ID, DEFAULT_TODO_MARKERS_PATTERN, pattern)
+	}
+
+	const methods = config.ruleOptions?.[UNWRAP_RULE_ID]?.methods
+	if (methods) {
+		const unknown = methods.filter((method) => !UNWRAP_METHODS.includes(method))
+		if (unknown.length > 0) {
+			throw new Error(
+				`Unknown ${UNWRAP_RULE_ID} methods: ${unknown.join(', ')}. Must be some of: ${UNWRAP_METHODS.join(', ')}`,
+			)
+		}
+		if (methods.length === 0) {
+			throw new Error(`${UNWRAP_RULE_ID} needs at least one method; turn the rule off instead`)
+		}
+		const pattern = `'^(${methods.join('|')})$'`
+		replaceInRule(overrides, ruleDirs, UNWRAP_RULE_ID, DEFAULT_UNWRAP_METHODS_PATTERN, pattern)
+	}
+
+	const maxLines = config.ruleOptions?.[FUNCTION_LENGTH_RULE_ID]?.maxLines
+	if (maxLines !== undefined) {
+		const pattern = `){${maxLines}}'`
+		replaceInRule(overrides, ruleDirs, FUNCTION_LENGTH_RULE_ID, DEFAULT_MAX_LINES_PATTERN, pattern)
+	}
+
+	const maxWidth = config.ruleOptions?.[LINE_LENGTH_RULE_ID]?.maxWidth
+	if (maxWidth !== undefined) {
+		const pattern = `]{${maxWidth + 1}}|`
+		replaceInRule(overrides, ruleDirs, LINE_LENGTH_RULE_ID, DEFAULT_MAX_WIDTH_PATTERN, pattern)
+	}
+
+	const macros = config.ruleOptions?.[LOG_FORMAT_RULE_ID]?.macros
+	if (macros) {
+		const pattern = buildMarkersPattern(macros).replaceAll("'", "''")
+		replaceInRule(overrides, ruleDirs, LOG_FORMAT_RULE_ID, DEFAULT_LOG_MACROS_PATTERN, pattern)
+	}
+
+	if (config.ruleOptions?.[REDUNDANT_RETURN_RULE_ID]?.bareReturns) {
+		replaceInRule(overrides, ruleDirs, REDUNDANT_RETURN_RULE_ID, BARE_RETURN_FILTER, '')
+	}
+
+	// Opt-in rules are off in their rule file and run once the config gives them a level
+	for (const [ruleId, level] of Object.entries(config.rules)) {
+		if (level === RULE_LEVEL.OFF) continue
+		for (const file of findRuleFiles(ruleDirs, ruleId)) {
+			const content = readRule(overrides, file)
+			if (OPT_IN_SEVERITY.test(content)) {
+				overrides.set(file, content.replace(OPT_IN_SEVERITY, `severity: ${level}`))
+			}
+		}
+	}
+
+	return overrides
+}
+
+/**
+ * Rule files as `vendor` writes them, keyed by their path in `ruleDirs`. Plain ast-grep would
+ * report every candidate of a rule the CLI finishes, so those rules are vendored turned off.
+ */
+export function buildVendorOverrides(ruleDirs: string[]): Map<string, string> {
+	const overrides = new Map<string, string>()
+	for (const ruleId of CLI_FINISHED_RULE_IDS) {
+		for (const file of findRuleFiles(ruleDirs, ruleId)) {
+			overrides.set(file, readRule(overrides, file).replace(RULE_SEVERITY, 'severity: off'))
+		}
+	}
+	return overrides
+}
+
+/**
+ * Take the path-based `ignores` out of library-scoped rules, for runs where Cargo targets
+ * decide what's library code instead. Returns the removed globs by rule id, so files that no
+ * target reaches can still be judged by path.
+ */
+export function dropLibraryIgnores(
+	overrides: Map<string, string>,
+	ruleDirs: string[],
+): Map<string, IgnorePattern[]> {
+	const dropped = new Map<string, IgnorePattern[]>()
+	for (const dir of ruleDirs) {
+		if (!fs.existsSync(dir)) continue
+		for (const name of fs.readdirSync(dir).sort()) {
+			if (!name.endsWith(`${LIBRARY_RULE_SUFFIX}.yml`)) continue
+			const file = path.join(dir, name)
+			const content = readRule(overrides, file)
+			const block = IGNORES_BLOCK.exec(content)
+			if (!block) continue
+
+			const globs = block[1]!
+				.split('\n')
+				.map((line) => line.replace(/^\s*-\s*/, '').trim())
+				.filter((glob) => glob !== '')
+				.map((glob) => glob.replace(/^"(.*)"$/, '$1'))
+			dropped.set(
+				path.basename(name, '.yml'),
+				globs
+					.map((glob) => compileIgnorePattern(glob))
+					.filter((pattern): pattern is IgnorePattern => pattern !== null),
+			)
+			overrides.set(file, content.replace(IGNORES_BLOCK, ''))
+		}
+	}
+	return dropped
+}
+
+function replaceInRule(
+	overrides: Map<string, string>,
+	ruleDirs: string[],
+	ruleId: string,
+	search: string,
+	replacement: string,
+): void {
+	for (const file of findRuleFiles(ruleDirs, ruleId)) {
+		const content = readRule(overrides, file)
+		if (!content.includes(search)) {
+			throw new Error(`${file} does not contain the default pattern '${search}'`)
+		}
+		overrides.set(file, content.replace(search, replacement))
+	}
+}
+
+function findRuleFiles(ruleDirs: string[], ruleId: string): string[] {
+	return ruleDirs
+		.map((dir) => path.join(dir, `${ruleId}.yml`))
+		.filter((file) => fs.existsSync(file))
+}
+
+// Several settings may rewrite the same rule, so each builds on the previous rewrite
+function readRule(overrides: Map<string, string>, file: string): string {
+	return overrides.get(file) ?? fs.readFileSync(file, 'utf8')
+}
+
+/**
+ * Translate an `allow` entry to gitignore-style globs. Entries containing `::` are Rust module
+ * paths covering the module's file and everything beneath it, so `ffi::raw` becomes
+ * `src/ffi/raw.rs` and `src/ffi/raw/` in any crate. Anything else is already a glob.
+ */
+export function allowEntryToGlobs(entry: string): string[] {
+	const trimmed = entry.trim()
+	if (!trimmed.includes('::')) {
+		return [trimmed]
+	}
+
+	const segments = trimmed.split('::').filter((segment) => segment !== '')
+	if (segments[0] === 'crate') segments.shift()
+	if (segments.length === 0) {
+		return ['**/src/**']
+	}
+	const module = segments.join('/')
+	return [`**/src/${module}.rs`, `**/src/${module}/**`]
+}
+
+/**
+ * Drop findings of rules with an `allow` setting in files that setting covers.
+ * Globs are relative to `cwd`, like `include` and `exclude`.
+ */
+export function applyAllowedPaths(
+	issues: LintIssue[],
+	config: LintConfig,
+	cwd: string = process.cwd(),
+): LintIssue[] {
+	const allowed = new Map<string, IgnorePattern[]>()
+	for (const [ruleId, options] of Object.entries(config.ruleOptions ?? {})) {
+		if (!options.allow) continue
+		const patterns = options.allow
+			.flatMap(allowEntryToGlobs)
+			.map((glob) => compileIgnorePattern(glob))
+			.filter((pattern): pattern is IgnorePattern => pattern !== null)
+		allowed.set(ruleId, patterns)
+	}
+	if (allowed.size === 0) {
+		return issues
+	}
+
+	return issues.filter((issue) => {
+		const patterns = allowed.get(issue.ruleId)
+		if (!patterns) return true
+		const relative = path.relative(cwd, path.resolve(cwd, issue.file)).split(path.sep).join('/')
+		return !isCoveredPath(patterns, relative)
+	})
+}
+
+// A file is covered when it or any directory above it matches, like an ignored directory
+export function isCoveredPath(patterns: IgnorePattern[], relativePath: string): boolean {
+	const segments = relativePath.split('/')
+	for (let i = 1; i <= segments.length; i++) {
+		const isDir = i < segments.length
+		if (matchIgnorePatterns(patterns, segments.slice(0, i).join('/'), isDir) === 'ignore') {
+			return true
+		}
+	}
+	return false
+}
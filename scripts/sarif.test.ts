@@ -0,0 +1,142 @@
+import path from 'node:path'
+import { describe, expect, it } from 'vitest'
+import type { FormatContext } from './formatters.ts'
+import {
+	buildSarifLog,
+	SARIF_VERSION,
+	sarifFormatter,
+	toArtifactLocation,
+	toSarifLevel,
+} from './sarif.ts'
+import type { LintIssue } from './shared.ts'
+
+const rootDir = path.resolve('/repo')
+
+const context: FormatContext = {
+	rules: [
+		{
+			id: 'no-dbg-macro',
+			message: 'Remove dbg!() macro before committing.',
+			severity: 'error',
+			note: 'The dbg!() macro is for quick debugging only.',
+		},
+		{
+			id: 'no-unwrap-in-lib',
+			message: 'Avoid .$METHOD() in library code.',
+			severity: 'warning',
+		},
+	],
+	rootDir,
+	toolVersion: '1.2.3',
+}
+
+const issues: LintIssue[] = [
+	{
+		ruleId: 'no-unwrap-in-lib',
+		severity: 'warning',
+		message: 'Avoid .unwrap() in library code.',
+		file: path.join(rootDir, 'crates', 'core', 'src', 'lib.rs'),
+		line: 12,
+		column: 9,
+		endLine: 12,
+		endColumn: 18,
+		byteOffset: { start: 301, end: 310 },
+	},
+]
+
+describe('toSarifLevel', () => {
+	it('should map severities to SARIF levels', () => {
+		expect(toSarifLevel('error')).toBe('error')
+		expect(toSarifLevel('warning')).toBe('warning')
+		expect(toSarifLevel('hint')).toBe('note')
+		expect(toSarifLevel('info')).toBe('note')
+	})
+})
+
+describe('toArtifactLocation', () => {
+	it('should make paths inside the root relative to %SRCROOT%', () => {
+		const location = toArtifactLocation(path.join(rootDir, 'src', 'my file.rs'), rootDir)
+
+		expect(location).toEqual({ uri: 'src/my%20file.rs', uriBaseId: '%SRCROOT%' })
+	})
+
+	it('should read relative paths as relative to the root', () => {
+		const location = toArtifactLocation(path.join('crates', 'core', 'lib.rs'), rootDir)
+
+		expect(location).toEqual({ uri: 'crates/core/lib.rs', uriBaseId: '%SRCROOT%' })
+	})
+
+	it('should fall back to an absolute file URI outside the root', () => {
+		const location = toArtifactLocation(path.resolve('/elsewhere/lib.rs'), rootDir)
+
+		expect(location.uri).toMatch(/^file:\/\//)
+		expect(location.uriBaseId).toBeUndefined()
+	})
+})
+
+describe('buildSarifLog', () => {
+	it('should describe the tool and its rules', () => {
+		const log = buildSarifLog(issues, context)
+		const driver = log.runs[0]!.tool.driver
+
+		expect(log.version).toBe(SARIF_VERSION)
+		expect(driver.name).toBe('tempo-lints')
+		expect(driver.version).toBe('1.2.3')
+		expect(driver.rules.map((rule) => rule.id)).toEqual(['no-dbg-macro', 'no-unwrap-in-lib'])
+		expect(driver.rules[0]).toEqual({
+			id: 'no-dbg-macro',
+			shortDescription: { text: 'Remove dbg!() macro before committing.' },
+			help: { text: 'The dbg!() macro is for quick debugging only.' },
+			defaultConfiguration: { level: 'error' },
+		})
+	})
+
+	it('should map findings to results with a physical location', () => {
+		const log = buildSarifLog(issues, context)
+		const result = log.runs[0]!.results[0]!
+
+		expect(result.ruleId).toBe('no-unwrap-in-lib')
+		expect(result.ruleIndex).toBe(1)
+		expect(result.level).toBe('warning')
+		expect(result.message.text).toBe('Avoid .unwrap() in library code.')
+		expect(result.locations[0]!.physicalLocation).toEqual({
+			artifactLocation: { uri: 'crates/core/src/lib.rs', uriBaseId: '%SRCROOT%' },
+			region: {
+				startLine: 12,
+				startColumn: 9,
+				endLine: 12,
+				endColumn: 18,
+				byteOffset: 301,
+				byteLength: 9,
+			},
+		})
+	})
+
+	it('should omit the end of the region when it is unknown', () => {
+		const [issue] = issues
+		const log = buildSarifLog([{ ...issue!, endLine: undefined, endColumn: undefined }], context)
+		const parsed = JSON.parse(JSON.stringify(log))
+
+		expect(parsed.runs[0].results[0].locations[0].physicalLocation.region).toEqual({
+			startLine: 12,
+			startColumn: 9,
+			byteOffset: 301,
+			byteLength: 9,
+		})
+	})
+
+	it('should emit an empty results array when there are no issues', () => {
+		const log = buildSarifLog([], context)
+
+		expect(log.runs[0]!.results).toEqual([])
+	})
+})
+
+describe('sarifFormatter', () => {
+	it('should emit valid JSON', () => {
+		const parsed = JSON.parse(sarifFormatter.format(issues, context))
+
+		expect(parsed.version).toBe(SARIF_VERSION)
+		expect(parsed.runs).toHaveLength(1)
+	})
+})
@@ -0,0 +1,158 @@
+import path from 'node:path'
+import { pathToFileURL } from 'node:url'
+import type { FormatContext, Formatter } from './formatters.ts'
+import { type LintIssue, relativizePath, type RuleMetadata } from './shared.ts'
+
+export const SARIF_VERSION = '2.1.0'
+export const SARIF_SCHEMA = 'https://json.schemastore.org/sarif-2.1.0.json'
+
+const TOOL_NAME = 'tempo-lints'
+const TOOL_INFORMATION_URI = 'https://github.com/tempoxyz/lints'
+const SRCROOT = '%SRCROOT%'
+
+// Subset of the SARIF 2.1.0 object model that we emit
+type SarifLevel = 'error' | 'warning' | 'note'
+
+interface SarifRule {
+	id: string
+	shortDescription: { text: string }
+	help?: { text: string }
+	defaultConfiguration: { level: SarifLevel }
+}
+
+interface SarifResult {
+	ruleId: string
+	ruleIndex?: number
+	level: SarifLevel
+	message: { text: string }
+	locations: {
+		physicalLocation: {
+			artifactLocation: { uri: string; uriBaseId?: string }
+			region: SarifRegion
+		}
+	}[]
+}
+
+export interface SarifRegion {
+	startLine: number
+	startColumn: number
+	endLine?: number
+	endColumn?: number
+	byteOffset?: number
+	byteLength?: number
+}
+
+export interface SarifLog {
+	$schema: string
+	version: string
+	runs: {
+		tool: {
+			driver: {
+				name: string
+				informationUri: string
+				version: string
+				rules: SarifRule[]
+			}
+		}
+		originalUriBaseIds: Record<string, { uri: string }>
+		results: SarifResult[]
+	}[]
+}
+
+export function toSarifLevel(severity: string): SarifLevel {
+	if (severity === 'error') return 'error'
+	if (severity === 'warning') return 'warning'
+	return 'note'
+}
+
+/**
+ * Build the artifact location for a reported file, given relative to `rootDir` or absolute.
+ * Files inside `rootDir` get a URI relative to `%SRCROOT%` so GitHub code scanning can
+ * match them to the checked-out source; anything else falls back to an absolute file URI.
+ */
+export function toArtifactLocation(
+	file: string,
+	rootDir: string,
+): { uri: string; uriBaseId?: string } {
+	const relative = relativizePath(path.resolve(rootDir, file), rootDir)
+
+	if (path.isAbsolute(relative)) {
+		return { uri: pathToFileURL(relative).href }
+	}
+
+	const uri = relative.split(path.sep).map(encodeURIComponent).join('/')
+	return { uri, uriBaseId: SRCROOT }
+}
+
+/**
+ * Build the region for an issue. SARIF end columns are exclusive like ours, so the span
+ * carries over unchanged; byte offsets become an offset plus a length.
+ */
+export function toRegion(issue: LintIssue): SarifRegion {
+	return {
+		startLine: issue.line,
+		startColumn: issue.column,
+		endLine: issue.endLine,
+		endColumn: issue.endColumn,
+		byteOffset: issue.byteOffset?.start,
+		byteLength: issue.byteOffset ? issue.byteOffset.end - issue.byteOffset.start : undefined,
+	}
+}
+
+export function buildSarifLog(issues: LintIssue[], context: FormatContext): SarifLog {
+	const rules = context.rules.map(toSarifRule)
+	const ruleIndex = new Map(context.rules.map((rule, index) => [rule.id, index]))
+
+	const results = issues.map((issue): SarifResult => {
+		return {
+			ruleId: issue.ruleId,
+			ruleIndex: ruleIndex.get(issue.ruleId),
+			level: toSarifLevel(issue.severity),
+			message: { text: issue.message },
+			locations: [
+				{
+					physicalLocation: {
+						artifactLocation: toArtifactLocation(issue.file, context.rootDir),
+						region: toRegion(issue),
+					},
+				},
+			],
+		}
+	})
+
+	return {
+		$schema: SARIF_SCHEMA,
+		version: SARIF_VERSION,
+		runs: [
+			{
+				tool: {
+					driver: {
+						name: TOOL_NAME,
+						informationUri: TOOL_INFORMATION_URI,
+						version: context.toolVersion,
+						rules,
+					},
+				},
+				originalUriBaseIds: {
+					[SRCROOT]: { uri: pathToFileURL(`${context.rootDir}${path.sep}`).href },
+				},
+				results,
+			},
+		],
+	}
+}
+
+function toSarifRule(rule: RuleMetadata): SarifRule {
+	return {
+		id: rule.id,
+		shortDescription: { text: rule.message },
+		help: rule.note ? { text: rule.note } : undefined,
+		defaultConfiguration: { level: toSarifLevel(rule.severity) },
+	}
+}
+
+export const sarifFormatter: Formatter = {
+	format(issues, context) {
+		return JSON.stringify(buildSarifLog(issues, context), null, 2)
+	},
+}
@@ -0,0 +1,90 @@
+import { describe, expect, it } from 'vitest'
+import { applyShadowing, bindingShape, changesType } from './shadowing.ts'
+import type { LintIssue } from './shared.ts'
+
+function rebind(original: string, binding: string): LintIssue {
+	return {
+		ruleId: 'no-shadowing',
+		severity: 'warning',
+		message: '`x` shadows an earlier binding in the same block.',
+		file: 'src/lib.rs',
+		line: 5,
+		column: 5,
+		captures: {
+			NAME: { text: 'x', line: 5, column: 9 },
+			ORIGINAL: { text: original, line: 2, column: 5 },
+			BINDING: { text: binding, line: 5, column: 5 },
+		},
+	}
+}
+
+describe('bindingShape', () => {
+	it('should read the type annotation', () => {
+		expect(bindingShape('let mut x: Vec<u8> = Vec::new();')).toEqual({
+			annotation: 'Vec<u8>',
+			literal: null,
+		})
+	})
+
+	it('should not mistake an `=` inside the type for the initializer', () => {
+		expect(bindingShape('let it: Box<dyn Iterator<Item = u8>> = make();').annotation).toBe(
+			'Box<dynIterator<Item=u8>>',
+		)
+		expect(bindingShape('let f: fn() -> u8 = g;').annotation).toBe('fn()->u8')
+	})
+
+	it('should classify literal initializers', () => {
+		expect(bindingShape('let x = 1_000;').literal).toBe('integer')
+		expect(bindingShape('let x = 0xff_u8;').literal).toBe('integer')
+		expect(bindingShape('let x = 1.5e3;').literal).toBe('float')
+		expect(bindingShape('let x = 2f64;').literal).toBe('float')
+		expect(bindingShape('let x = "a";').literal).toBe('string')
+		expect(bindingShape('let x = r#"a"#;').literal).toBe('string')
+		expect(bindingShape('let x = b"a";').literal).toBe('byte string')
+		expect(bindingShape("let x = '\\n';").literal).toBe('char')
+		expect(bindingShape('let x = false;').literal).toBe('bool')
+		expect(bindingShape('let x = compute();').literal).toBeNull()
+	})
+})
+
+describe('changesType', () => {
+	it('should compare annotations when both bindings have one', () => {
+		const u32 = bindingShape('let x: u32 = 1;')
+		expect(changesType(u32, bindingShape('let x: String = x.to_string();'))).toBe(true)
+		expect(changesType(u32, bindingShape('let x: u32 = x + 1;'))).toBe(false)
+	})
+
+	it('should compare literal kinds', () => {
+		expect(changesType(bindingShape('let x = 1;'), bindingShape('let x = "a";'))).toBe(true)
+		expect(changesType(bindingShape('let x = 1;'), bindingShape('let x = 2;'))).toBe(false)
+	})
+
+	it('should not guess without annotations or literals', () => {
+		expect(changesType(bindingShape('let x = 1;'), bindingShape('let x = x.to_string();'))).toBe(
+			false,
+		)
+	})
+})
+
+describe('applyShadowing', () => {
+	it('should name the lines of both bindings', () => {
+		const [issue] = applyShadowing([rebind('let x = 1;', 'let x = x + 1;')], { rules: {} })
+
+		expect(issue?.message).toBe('`x` on line 5 shadows the binding on line 2 in the same block.')
+	})
+
+	it('should only keep rebinds that change the type with type-changes-only', () => {
+		const config = { rules: {}, ruleOptions: { 'no-shadowing': { typeChangesOnly: true } } }
+		const issues = [rebind('let x = 1;', 'let x = x + 1;'), rebind('let x = 1;', 'let x = "a";')]
+
+		expect(applyShadowing(issues, config).map((issue) => issue.captures?.BINDING?.text)).toEqual([
+			'let x = "a";',
+		])
+	})
+
+	it('should leave other rules alone', () => {
+		const issue = { ...rebind('let x = 1;', 'let x = 2;'), ruleId: 'no-dbg-macro' }
+
+		expect(applyShadowing([issue], { rules: {} })).toEqual([issue])
+	})
+})
@@ -0,0 +1,107 @@
+import { type LintConfig, SHADOWING_RULE_ID } from './config.ts'
+import type { LintIssue } from './shared.ts'
+
+// What can be told about a binding's type from its `let` declaration alone
+export interface BindingShape {
+	// The written type annotation, without whitespace
+	annotation: string | null
+	// The kind of literal the binding is initialized with
+	literal: LiteralKind | null
+}
+
+export type LiteralKind = 'bool' | 'byte' | 'byte string' | 'char' | 'float' | 'integer' | 'string'
+
+const LET_HEAD = /^let\s+(?:mut\s+)?(?:r#)?\w+\s*/
+const INTEGER_LITERAL = /^-?(?:0x[\da-fA-F_]+|0o[0-7_]+|0b[01_]+|\d[\d_]*)(?:[ui](?:8|16|32|64|128|size))?$/
+// Checked after INTEGER_LITERAL, so it doesn't need to rule out plain integers
+const FLOAT_LITERAL = /^-?\d[\d_]*(?:\.(?:\d[\d_]*)?)?(?:[eE][+-]?\d[\d_]*)?(?:f32|f64)?$/
+
+/**
+ * Read the type annotation and literal kind from the text of a `let` declaration. Either is
+ * null when the declaration doesn't spell it out, e.g. for `let x = compute();`.
+ */
+export function bindingShape(declaration: string): BindingShape {
+	const head = LET_HEAD.exec(declaration)
+	if (!head) {
+		return { annotation: null, literal: null }
+	}
+
+	const rest = declaration.slice(head[0].length).replace(/;\s*$/, '')
+	const equals = findAssignment(rest)
+	const typed = rest.startsWith(':')
+	const annotation = typed ? rest.slice(1, equals === -1 ? undefined : equals) : null
+	const initializer = equals === -1 ? null : rest.slice(equals + 1).trim()
+
+	return {
+		annotation: annotation === null ? null : annotation.replace(/\s+/g, ''),
+		literal: initializer === null ? null : literalKind(initializer),
+	}
+}
+
+// Index of the `=` starting the initializer, skipping ones inside the type (e.g. `Item = u8`)
+function findAssignment(text: string): number {
+	let depth = 0
+	for (let i = 0; i < text.length; i++) {
+		const ch = text[i]!
+		if ('([{<'.includes(ch)) {
+			depth++
+		} else if (')]}'.includes(ch) || (ch === '>' && text[i - 1] !== '-')) {
+			depth = Math.max(depth - 1, 0)
+		} else if (ch === '=' && depth === 0) {
+			return i
+		}
+	}
+	return -1
+}
+
+function literalKind(initializer: string): LiteralKind | null {
+	if (initializer === 'true' || initializer === 'false') return 'bool'
+	if (INTEGER_LITERAL.test(initializer)) return 'integer'
+	if (FLOAT_LITERAL.test(initializer)) return 'float'
+	if (/^b(?:r#*)?"/.test(initializer)) return 'byte string'
+	if (/^(?:r#*)?"/.test(initializer)) return 'string'
+	if (/^b'/.test(initializer)) return 'byte'
+	if (/^'(?:[^'\\]|\\.[^']*)'$/.test(initializer)) return 'char'
+	return null
+}
+
+/**
+ * Whether a rebind visibly changes the type: both bindings are annotated with different
+ * types, or both are initialized with different kinds of literals. Anything else would take
+ * type inference to tell, so it doesn't count.
+ */
+export function changesType(original: BindingShape, rebind: BindingShape): boolean {
+	if (original.annotation !== null && rebind.annotation !== null) {
+		return original.annotation !== rebind.annotation
+	}
+	if (original.literal !== null && rebind.literal !== null) {
+		return original.literal !== rebind.literal
+	}
+	return false
+}
+
+/**
+ * Finish the no-shadowing rule: name the lines of both bindings in the message and, with
+ * `type-changes-only`, drop rebinds that don't visibly change the type.
+ */
+export function applyShadowing(issues: LintIssue[], config: LintConfig): LintIssue[] {
+	const typeChangesOnly = config.ruleOptions?.[SHADOWING_RULE_ID]?.typeChangesOnly ?? false
+
+	return issues.flatMap((issue) => {
+		const name = issue.captures?.NAME
+		const original = issue.captures?.ORIGINAL
+		const binding = issue.captures?.BINDING
+		if (issue.ruleId !== SHADOWING_RULE_ID || !name || !original || !binding) {
+			return [issue]
+		}
+		if (typeChangesOnly && !changesType(bindingShape(original.text), bindingShape(binding.text))) {
+			return []
+		}
+		return [
+			{
+				...issue,
+				message: `\`${name.text}\` on line ${binding.line} shadows the binding on line ${original.line} in the same block.`,
+			},
+		]
+	})
+}
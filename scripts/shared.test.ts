@@ -1,18 +1,25 @@
 import { existsSync, readFileSync } from 'node:fs'
-import { join } from 'node:path'
+import { join, resolve, sep } from 'node:path'
 import { afterEach, describe, expect, it } from 'vitest'
 import {
 	countBySeverity,
 	createTempConfig,
 	filterExcludedRules,
 	generateConfigContent,
+	getDirectiveDirs,
 	getRuleDirs,
 	getRuleDirsRelative,
+	getRuleMetadata,
 	getValidRuleIds,
 	isValidLanguage,
 	LANG,
 	type LintIssue,
+	PARSE_ERROR_RULE_ID,
 	parseLintIssues,
+	parseRuleMetadata,
+	readYamlList,
+	relativizePath,
+	sortIssues,
 	VALID_LANGUAGES,
 } from './shared.ts'
 
@@ -67,6 +74,9 @@ describe('parseLintIssues', () => {
 			file: 'src/utils.ts',
 			line: 10,
 			column: 2,
+			endLine: 10,
+			endColumn: 22,
+			byteOffset: { start: 100, end: 120 },
 			code: 'console.log("hello")',
 		})
 	})
@@ -171,6 +181,54 @@ describe('parseLintIssues', () => {
 		})
 	})
 
+	it('should shift positions to 1-based with oneBased', () => {
+		const astGrepOutput = JSON.stringify([
+			{
+				range: {
+					byteOffset: { start: 100, end: 120 },
+					start: { line: 10, column: 2 },
+					end: { line: 12, column: 0 },
+				},
+				file: 'src/lib.rs',
+				ruleId: 'no-dbg-macro',
+			},
+		])
+
+		const result = parseLintIssues(astGrepOutput, undefined, { oneBased: true })
+
+		expect(result.issues[0]).toMatchObject({
+			line: 11,
+			column: 3,
+			endLine: 13,
+			endColumn: 1,
+			byteOffset: { start: 100, end: 120 },
+		})
+	})
+
+	it('should keep single metavariables with 1-based positions', () => {
+		const astGrepOutput = JSON.stringify([
+			{
+				file: 'src/lib.rs',
+				ruleId: 'no-shadowing',
+				metaVariables: {
+					single: {
+						NAME: { text: 'x', range: { start: { line: 4, column: 8 } } },
+						ORIGINAL: { text: 'let x = 1;', range: { start: { line: 2, column: 4 } } },
+					},
+					multi: {},
+					transformed: {},
+				},
+			},
+		])
+
+		const result = parseLintIssues(astGrepOutput, undefined, { oneBased: true })
+
+		expect(result.issues[0]!.captures).toEqual({
+			NAME: { text: 'x', line: 5, column: 9 },
+			ORIGINAL: { text: 'let x = 1;', line: 3, column: 5 },
+		})
+	})
+
 	it('should return error for invalid JSON', () => {
 		const result = parseLintIssues('not valid json')
 
@@ -265,6 +323,24 @@ describe('getRuleDirs', () => {
 	})
 })
 
+describe('getDirectiveDirs', () => {
+	const mockRoot = '/mock/package/root'
+
+	it('should return the directives dir of each language', () => {
+		expect(getDirectiveDirs(LANG.RUST, mockRoot)).toEqual([`${mockRoot}/src/rust/directives`])
+		expect(getDirectiveDirs(LANG.TYPESCRIPT, mockRoot)).toEqual([
+			`${mockRoot}/src/typescript/directives`,
+		])
+	})
+
+	it('should return both dirs for all', () => {
+		expect(getDirectiveDirs(LANG.ALL, mockRoot)).toEqual([
+			`${mockRoot}/src/rust/directives`,
+			`${mockRoot}/src/typescript/directives`,
+		])
+	})
+})
+
 describe('getRuleDirsRelative', () => {
 	it('should return relative rust dirs for rust language', () => {
 		const dirs = getRuleDirsRelative(LANG.RUST)
@@ -361,6 +437,22 @@ describe('createTempConfig', () => {
 
 		expect(existsSync(tmpDir)).toBe(false)
 	})
+
+	it('should copy rule dirs with overridden files', () => {
+		const rulesDir = getRuleDirs(LANG.RUST)[0]!
+		const overridden = join(rulesDir, 'no-todo-comment.yml')
+		const result = createTempConfig([rulesDir], new Map([[overridden, 'id: no-todo-comment\n']]))
+		tmpDir = result.tmpDir
+		configPath = result.configPath
+		cleanup = result.cleanup
+
+		const copy = join(tmpDir, 'rules-0')
+		expect(readFileSync(configPath, 'utf-8')).toBe(`ruleDirs:\n  - ${copy}\n`)
+		expect(readFileSync(join(copy, 'no-todo-comment.yml'), 'utf-8')).toBe('id: no-todo-comment\n')
+		expect(readFileSync(join(copy, 'no-emojis.yml'), 'utf-8')).toBe(
+			readFileSync(join(rulesDir, 'no-emojis.yml'), 'utf-8'),
+		)
+	})
 })
 
 describe('filterExcludedRules', () => {
@@ -496,6 +588,82 @@ describe('countBySeverity', () => {
 	})
 })
 
+describe('sortIssues', () => {
+	const issue = (file: string, line: number, column: number, ruleId: string): LintIssue => ({
+		ruleId,
+		severity: 'warning',
+		message: 'Lint issue',
+		file,
+		line,
+		column,
+	})
+
+	it('should sort by file, then line, then column, then rule id', () => {
+		const issues = [
+			issue('src/b.rs', 1, 1, 'no-dbg-macro'),
+			issue('src/a.rs', 10, 1, 'no-dbg-macro'),
+			issue('src/a.rs', 2, 9, 'no-unwrap-in-lib'),
+			issue('src/a.rs', 2, 9, 'no-expect-in-lib'),
+			issue('src/a.rs', 2, 3, 'no-unwrap-in-lib'),
+		]
+
+		expect(sortIssues(issues).map((i) => `${i.file}:${i.line}:${i.column}:${i.ruleId}`)).toEqual([
+			'src/a.rs:2:3:no-unwrap-in-lib',
+			'src/a.rs:2:9:no-expect-in-lib',
+			'src/a.rs:2:9:no-unwrap-in-lib',
+			'src/a.rs:10:1:no-dbg-macro',
+			'src/b.rs:1:1:no-dbg-macro',
+		])
+	})
+
+	it('should order findings of one rule at the same spot by their end and message', () => {
+		const at = (endColumn: number, message: string) => ({
+			...issue('src/a.rs', 1, 1, 'no-shadowing'),
+			endColumn,
+			message,
+		})
+		const issues = [at(9, 'b'), at(9, 'a'), at(5, 'c')]
+
+		expect(sortIssues(issues)).toEqual([issues[2], issues[1], issues[0]])
+	})
+
+	it('should not modify the input array', () => {
+		const issues = [
+			issue('src/b.rs', 1, 1, 'no-dbg-macro'),
+			issue('src/a.rs', 1, 1, 'no-dbg-macro'),
+		]
+
+		sortIssues(issues)
+
+		expect(issues[0]?.file).toBe('src/b.rs')
+	})
+})
+
+describe('relativizePath', () => {
+	const base = resolve('/workspace/repo')
+
+	it('should make paths inside the base relative', () => {
+		expect(relativizePath(join(base, 'crates', 'core', 'lib.rs'), base)).toBe(
+			join('crates', 'core', 'lib.rs'),
+		)
+	})
+
+	it('should resolve relative paths against the cwd', () => {
+		expect(relativizePath(join('src', 'lib.rs'), process.cwd())).toBe(join('src', 'lib.rs'))
+	})
+
+	it('should keep paths outside the base absolute', () => {
+		const outside = resolve('/workspace/other/lib.rs')
+
+		expect(relativizePath(outside, base)).toBe(outside)
+		expect(relativizePath(join(base, '..'), base)).toBe(resolve('/workspace'))
+	})
+
+	it('should not mistake names starting with dots for parent directories', () => {
+		expect(relativizePath(join(base, `..hidden${sep}lib.rs`), base)).toBe(`..hidden${sep}lib.rs`)
+	})
+})
+
 describe('VALID_LANGUAGES', () => {
 	it('should contain all expected languages', () => {
 		expect(VALID_LANGUAGES).toContain('rust')
@@ -521,8 +689,35 @@ describe('getValidRuleIds', () => {
 		expect(ids).toContain('no-dbg-macro')
 		expect(ids).toContain('no-unwrap-in-lib')
 		expect(ids).toContain('no-mem-transmute')
-		expect(ids).toContain('tracing-no-format')
+		expect(ids).toContain('no-string-format-in-log')
+		expect(ids).toContain('no-large-enum-variant')
+		expect(ids).toContain('no-redundant-return')
+		expect(ids).toContain('no-as-cast')
+		expect(ids).toContain('no-impl-trait-in-public-return')
+		expect(ids).toContain('require-must-use-on-result-returning-fn')
+		expect(ids).toContain('no-noop-assert')
+		expect(ids).toContain('no-ignored-test')
 		expect(ids).toContain('unsafe-needs-safety-comment')
+		expect(ids).toContain('no-panic-in-lib')
+		expect(ids).toContain('no-unreachable-in-lib')
+		expect(ids).toContain('no-expect-in-lib')
+		expect(ids).toContain('no-println-in-lib')
+		expect(ids).toContain('no-eprintln-in-lib')
+		expect(ids).toContain('no-unsafe-block')
+		expect(ids).toContain('max-function-length')
+		expect(ids).toContain('max-line-length')
+		expect(ids).toContain('no-clone-in-loop')
+		expect(ids).toContain('no-shadowing')
+		expect(ids).toContain('no-wildcard-import')
+		expect(ids).toContain('no-float-eq')
+		// Path rules are rules like any other
+		expect(ids).toContain('no-mod-rs')
+		expect(ids).toContain('no-todo-comment')
+	})
+
+	it('should not include internal directive rules', () => {
+		expect(getValidRuleIds(LANG.RUST).has('tempo-allow')).toBe(false)
+		expect(getValidRuleIds(LANG.ALL).has(PARSE_ERROR_RULE_ID)).toBe(false)
 	})
 
 	it('should return typescript rule IDs', () => {
@@ -541,6 +736,68 @@ describe('getValidRuleIds', () => {
 	})
 })
 
+describe('parseRuleMetadata', () => {
+	it('should read id, message, severity and note', () => {
+		const rule = parseRuleMetadata(
+			[
+				'id: no-dbg-macro',
+				'message: "Remove dbg!() macro before committing."',
+				'severity: error',
+				'language: rust',
+				'note: |',
+				'  The dbg!() macro is for quick debugging only.',
+				'',
+				'  Use tracing instead.',
+				'rule:',
+				'  pattern: dbg!($$$ARGS)',
+			].join('\n'),
+		)
+
+		expect(rule).toEqual({
+			id: 'no-dbg-macro',
+			message: 'Remove dbg!() macro before committing.',
+			severity: 'error',
+			note: 'The dbg!() macro is for quick debugging only.\n\nUse tracing instead.',
+		})
+	})
+
+	it('should default severity and leave note undefined when missing', () => {
+		const rule = parseRuleMetadata('id: some-rule\nmessage: plain message\n')
+
+		expect(rule).toEqual({
+			id: 'some-rule',
+			message: 'plain message',
+			severity: 'warning',
+			note: undefined,
+		})
+	})
+
+	it('should return null without an id', () => {
+		expect(parseRuleMetadata('message: "no id"\n')).toBeNull()
+	})
+})
+
+describe('readYamlList', () => {
+	it('should read a top-level block sequence', () => {
+		const content = 'files:\n  - mod.rs\n  # Generated code\n  - "**/gen/*.rs"\nrule:\n  - x\n'
+
+		expect(readYamlList(content, 'files')).toEqual(['mod.rs', '**/gen/*.rs'])
+		expect(readYamlList(content, 'ignores')).toBeUndefined()
+	})
+})
+
+describe('getRuleMetadata', () => {
+	it('should return metadata for every rust rule', () => {
+		const rules = getRuleMetadata(LANG.RUST)
+		const dbg = rules.find((rule) => rule.id === 'no-dbg-macro')
+
+		expect(rules.map((rule) => rule.id)).toEqual(Array.from(getValidRuleIds(LANG.RUST)))
+		expect(dbg?.severity).toBe('error')
+		expect(dbg?.message).toContain('dbg!()')
+		expect(dbg?.note).toContain('quick debugging')
+	})
+})
+
 describe('parseLintIssues with rule ID filtering', () => {
 	it('should filter out entries with invalid ruleId', () => {
 		const mixedOutput = JSON.stringify([
@@ -21,6 +21,22 @@ const SEVERITY = {
 	HINT: 'hint',
 } as const
 
+/**
+ * Process exit codes. Tool errors win over lint errors, so CI can tell "the lint run failed"
+ * apart from "the lint run found problems".
+ */
+export const EXIT_CODE = {
+	// No error-severity findings counted against the run
+	SUCCESS: 0,
+	// At least one error-severity finding, or a rule over its `max` budget
+	LINT_ERRORS: 1,
+	// Usage errors, unreadable files or config, ast-grep failures and unparseable source files
+	TOOL_ERROR: 2,
+} as const
+
+// Internal directive rule matching syntax errors; its matches are tool errors, not findings
+export const PARSE_ERROR_RULE_ID = 'tempo-parse-error'
+
 export const MAX_ISSUES_PER_RULE = 10
 export const MAX_ISSUES_PER_FILE = 5
 export const MAX_FILES_TO_DISPLAY = 10
@@ -37,6 +53,10 @@ export interface SeverityCounts {
 export interface AstGrepIssue {
 	text?: string
 	range?: {
+		byteOffset?: {
+			start?: number
+			end?: number
+		}
 		start?: {
 			line?: number
 			column?: number
@@ -53,9 +73,45 @@ export interface AstGrepIssue {
 	severity?: string
 	message?: string // From rule definition
 	note?: string // From rule definition
+	replacement?: string // Only present if the rule has a fix
+	replacementOffsets?: {
+		start?: number
+		end?: number
+	}
+	metaVariables?: {
+		single?: Record<string, AstGrepMetaVariable>
+	}
+}
+
+export interface AstGrepMetaVariable {
+	text?: string
+	range?: {
+		start?: {
+			line?: number
+			column?: number
+		}
+	}
 }
 
-// Normalized issue format for output
+// A metavariable a rule captured: the matched text and the position it starts at
+export interface IssueCapture {
+	text: string
+	line: number
+	column: number
+}
+
+// A rule's auto-fix for one issue, as a byte range to replace in the file
+export interface IssueFix {
+	start: number
+	end: number
+	replacement: string
+}
+
+/**
+ * Normalized issue format for output.
+ * Positions are 0-based as ast-grep reports them, unless parsed with `oneBased`; the CLI
+ * always reports them 1-based.
+ */
 export interface LintIssue {
 	ruleId: string
 	severity: string
@@ -63,7 +119,15 @@ export interface LintIssue {
 	file: string
 	line: number
 	column: number
+	// End of the finding, pointing just past its last character
+	endLine?: number
+	endColumn?: number
+	// Byte offsets into the file: 0-based, end exclusive
+	byteOffset?: { start: number; end: number }
 	code?: string
+	fix?: IssueFix
+	// Single metavariables keyed by name, for rules the CLI finishes based on what they matched
+	captures?: Record<string, IssueCapture>
 }
 
 export function isValidLanguage(value: unknown): value is Language {
@@ -87,21 +151,71 @@ function isAstGrepIssueArray(value: unknown): value is AstGrepIssue[] {
 	return true
 }
 
-function normalizeIssue(issue: AstGrepIssue): LintIssue {
+export interface ParseLintIssuesOptions {
+	// Shift lines and columns to 1-based, like editors and compilers report them
+	oneBased?: boolean
+}
+
+function normalizeIssue(issue: AstGrepIssue, base: number): LintIssue {
+	const start = issue.range?.start
 	return {
 		ruleId: issue.ruleId ?? 'unknown',
 		severity: issue.severity ?? 'warning',
 		message: issue.message ?? issue.note?.split('\n')[0] ?? 'Lint issue',
 		file: issue.file ?? 'unknown',
-		line: issue.range?.start?.line ?? 1,
-		column: issue.range?.start?.column ?? 1,
-		code: issue.lines?.trim(),
+		line: start?.line === undefined ? 1 : start.line + base,
+		column: start?.column === undefined ? 1 : start.column + base,
+		...normalizeSpan(issue, base),
+		// A parse error keeps the code the parser gave up on, for the printed error
+		code: issue.ruleId === PARSE_ERROR_RULE_ID ? issue.text : issue.lines?.trim(),
+		fix: normalizeFix(issue),
+		captures: normalizeCaptures(issue, base),
 	}
 }
 
+// ast-grep ends are already exclusive, so only the shift to `base` is needed
+function normalizeSpan(
+	issue: AstGrepIssue,
+	base: number,
+): Pick<LintIssue, 'endLine' | 'endColumn' | 'byteOffset'> {
+	const end = issue.range?.end
+	const bytes = issue.range?.byteOffset
+	return {
+		endLine: end?.line === undefined ? undefined : end.line + base,
+		endColumn: end?.column === undefined ? undefined : end.column + base,
+		byteOffset:
+			bytes?.start === undefined || bytes.end === undefined
+				? undefined
+				: { start: bytes.start, end: bytes.end },
+	}
+}
+
+function normalizeFix(issue: AstGrepIssue): IssueFix | undefined {
+	const start = issue.replacementOffsets?.start
+	const end = issue.replacementOffsets?.end
+	if (issue.replacement === undefined || start === undefined || end === undefined) {
+		return undefined
+	}
+	return { start, end, replacement: issue.replacement }
+}
+
+function normalizeCaptures(
+	issue: AstGrepIssue,
+	base: number,
+): Record<string, IssueCapture> | undefined {
+	const captures: Record<string, IssueCapture> = {}
+	for (const [name, variable] of Object.entries(issue.metaVariables?.single ?? {})) {
+		if (variable.text === undefined || variable.range?.start?.line === undefined) continue
+		const { line, column = 0 } = variable.range.start
+		captures[name] = { text: variable.text, line: line + base, column: column + base }
+	}
+	return Object.keys(captures).length > 0 ? captures : undefined
+}
+
 export function parseLintIssues(
 	input: string,
 	validRuleIds?: Set<string>,
+	options: ParseLintIssuesOptions = {},
 ): { issues: LintIssue[]; error: string | null } {
 	const { data, error } = safeParseJSON<unknown>(input)
 
@@ -114,7 +228,8 @@ export function parseLintIssues(
 	}
 
 	// Normalize ast-grep issues to our format
-	let issues = data.map(normalizeIssue)
+	const base = options.oneBased ? 1 : 0
+	let issues = data.map((issue) => normalizeIssue(issue, base))
 
 	// Filter by valid rule IDs if provided (to exclude non-tempo lint entries)
 	if (validRuleIds) {
@@ -152,6 +267,44 @@ export function getRuleDirs(language: Language, packageRoot: string = PACKAGE_RO
 	return dirs
 }
 
+/**
+ * Get directories of internal directive rules, whose matches are consumed by the CLI
+ * (e.g. to find inline allow annotations) instead of being reported.
+ * They're scanned alongside the lint rules but are not vendored.
+ */
+export function getDirectiveDirs(language: Language, packageRoot: string = PACKAGE_ROOT): string[] {
+	const dirs: string[] = []
+
+	if (language === LANG.RUST || language === LANG.ALL) {
+		dirs.push(path.join(packageRoot, 'src', LANG.RUST, 'directives'))
+	}
+
+	if (language === LANG.TYPESCRIPT || language === LANG.ALL) {
+		dirs.push(path.join(packageRoot, 'src', LANG.TYPESCRIPT, 'directives'))
+	}
+
+	return dirs
+}
+
+/**
+ * Get directories of path rules, which flag files by where they are rather than what they
+ * contain (e.g. `mod.rs` files). The CLI checks them against the files it discovers, so
+ * they're not scanned by ast-grep and not vendored.
+ */
+export function getPathRuleDirs(language: Language, packageRoot: string = PACKAGE_ROOT): string[] {
+	const dirs: string[] = []
+
+	if (language === LANG.RUST || language === LANG.ALL) {
+		dirs.push(path.join(packageRoot, 'src', LANG.RUST, 'path-rules'))
+	}
+
+	if (language === LANG.TYPESCRIPT || language === LANG.ALL) {
+		dirs.push(path.join(packageRoot, 'src', LANG.TYPESCRIPT, 'path-rules'))
+	}
+
+	return dirs
+}
+
 /**
  * Get rule directories as relative paths for vendored configs.
  * Used by the vendor command to generate portable sgconfig.yml files.
@@ -172,43 +325,176 @@ export function getRuleDirsRelative(language: Language): string[] {
 	return dirs
 }
 
+export interface RuleMetadata {
+	id: string
+	message: string
+	severity: string
+	note?: string
+}
+
 /**
- * Get all valid rule IDs for a given language by scanning rule directories.
- * This is used to filter out non-lint entries from the output.
+ * Read the metadata of every rule for a given language from its YAML file, path rules
+ * included.
+ * Only the top-level `id`, `message`, `severity` and `note` keys are read.
  */
-export function getValidRuleIds(
+export function getRuleMetadata(
 	language: Language,
 	packageRoot: string = PACKAGE_ROOT,
-): Set<string> {
-	const ruleDirs = getRuleDirs(language, packageRoot)
-	const ids = new Set<string>()
+): RuleMetadata[] {
+	const ruleDirs = [
+		...getRuleDirs(language, packageRoot),
+		...getPathRuleDirs(language, packageRoot),
+	]
+	const rules: RuleMetadata[] = []
 
 	for (const dir of ruleDirs) {
 		if (!fs.existsSync(dir)) {
 			continue
 		}
 
-		const files = fs.readdirSync(dir)
+		const files = fs.readdirSync(dir).sort()
 		for (const file of files) {
 			if (file.endsWith('.yml')) {
-				const filePath = path.join(dir, file)
-				const content = fs.readFileSync(filePath, 'utf8')
-				const match = content.match(/^id:\s*(.+)$/m)
-				if (match?.[1]) {
-					ids.add(match[1].trim())
+				const content = fs.readFileSync(path.join(dir, file), 'utf8')
+				const rule = parseRuleMetadata(content)
+				if (rule) {
+					rules.push(rule)
 				}
 			}
 		}
 	}
 
-	return ids
+	return rules
+}
+
+export function parseRuleMetadata(content: string): RuleMetadata | null {
+	const id = content.match(/^id:\s*(.+)$/m)?.[1]?.trim()
+	if (!id) return null
+
+	return {
+		id,
+		message: unquoteYamlScalar(content.match(/^message:\s*(.+)$/m)?.[1] ?? ''),
+		severity: content.match(/^severity:\s*(\w+)/m)?.[1] ?? SEVERITY.WARNING,
+		note: readYamlBlockScalar(content, 'note'),
+	}
+}
+
+function unquoteYamlScalar(value: string): string {
+	const trimmed = value.trim()
+	if (trimmed.startsWith('"')) {
+		const { data } = safeParseJSON<string>(trimmed)
+		return data ?? trimmed.slice(1, -1)
+	}
+	if (trimmed.startsWith("'")) {
+		return trimmed.slice(1, -1).replaceAll("''", "'")
+	}
+	return trimmed
+}
+
+// Reads a top-level `key: |` literal block, dropping its indentation
+function readYamlBlockScalar(content: string, key: string): string | undefined {
+	const lines = content.split('\n')
+	const start = lines.findIndex((line) => line.startsWith(`${key}: |`))
+	if (start === -1) return undefined
+
+	const block: string[] = []
+	for (const line of lines.slice(start + 1)) {
+		if (line.trim() !== '' && !line.startsWith(' ')) break
+		block.push(line)
+	}
+
+	const indent = Math.min(
+		...block.filter((line) => line.trim() !== '').map((line) => line.search(/\S/)),
+	)
+	return block
+		.map((line) => line.slice(indent).trimEnd())
+		.join('\n')
+		.trim()
+}
+
+/**
+ * Read a top-level `key:` block sequence of scalars, e.g. the `files` globs of a rule.
+ * Returns undefined when the key isn't there.
+ */
+export function readYamlList(content: string, key: string): string[] | undefined {
+	const lines = content.split('\n')
+	const start = lines.findIndex(
+		(line) => line.startsWith(`${key}:`) && line.slice(key.length + 1).trim() === '',
+	)
+	if (start === -1) return undefined
+
+	const items: string[] = []
+	for (const line of lines.slice(start + 1)) {
+		const item = /^\s+-\s+(.*)$/.exec(line)
+		if (item) {
+			items.push(unquoteYamlScalar(item[1]!))
+		} else if (line.trim() !== '' && !/^\s+#/.test(line)) {
+			break
+		}
+	}
+	return items
+}
+
+/**
+ * Get all valid rule IDs for a given language by scanning rule directories.
+ * This is used to filter out non-lint entries from the output.
+ */
+export function getValidRuleIds(
+	language: Language,
+	packageRoot: string = PACKAGE_ROOT,
+): Set<string> {
+	return new Set(getRuleMetadata(language, packageRoot).map((rule) => rule.id))
+}
+
+/**
+ * Find the root of the enclosing git repository, falling back to `startDir`.
+ * Used to make reported paths portable across machines.
+ */
+export function findRepoRoot(startDir: string): string {
+	let dir = path.resolve(startDir)
+	while (true) {
+		if (fs.existsSync(path.join(dir, '.git'))) {
+			return dir
+		}
+		const parent = path.dirname(dir)
+		if (parent === dir) {
+			return path.resolve(startDir)
+		}
+		dir = parent
+	}
+}
+
+/**
+ * The path to report for `file`: relative to `baseDir`, or absolute when it's outside of it, so
+ * reports never contain `../` chains. A relative `file` is resolved against the cwd.
+ */
+export function relativizePath(file: string, baseDir: string): string {
+	const absolute = path.resolve(file)
+	const relative = path.relative(path.resolve(baseDir), absolute)
+	if (
+		relative === '' ||
+		relative === '..' ||
+		relative.startsWith(`..${path.sep}`) ||
+		path.isAbsolute(relative)
+	) {
+		return absolute
+	}
+	return relative
 }
 
 export function generateConfigContent(ruleDirs: string[]): string {
 	return `ruleDirs:\n${ruleDirs.map((d) => `  - ${d}`).join('\n')}\n`
 }
 
-export function createTempConfig(ruleDirs: string[]): {
+/**
+ * Write a temporary sgconfig.yml for `ruleDirs`.
+ * `overrides` maps rule file paths to replacement YAML; a rule dir containing an overridden
+ * file is copied into the temp dir with the replacement applied.
+ */
+export function createTempConfig(
+	ruleDirs: string[],
+	overrides: Map<string, string> = new Map(),
+): {
 	tmpDir: string
 	configPath: string
 	cleanup: () => void
@@ -216,7 +502,21 @@ export function createTempConfig(ruleDirs: string[]): {
 	const tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-'))
 	const configPath = path.join(tmpDir, 'sgconfig.yml')
 
-	fs.writeFileSync(configPath, generateConfigContent(ruleDirs))
+	const dirs = ruleDirs.map((dir, index) => {
+		const files = [...overrides.keys()].filter((file) => path.dirname(file) === dir)
+		if (files.length === 0) {
+			return dir
+		}
+
+		const copy = path.join(tmpDir, `rules-${index}`)
+		fs.cpSync(dir, copy, { recursive: true })
+		for (const file of files) {
+			fs.writeFileSync(path.join(copy, path.basename(file)), overrides.get(file)!)
+		}
+		return copy
+	})
+
+	fs.writeFileSync(configPath, generateConfigContent(dirs))
 
 	const cleanup = () => {
 		try {
@@ -294,6 +594,31 @@ export function groupByRule(issues: LintIssue[]): Record<string, LintIssue[]> {
 	return groupBy(issues, (issue) => issue.ruleId)
 }
 
+/**
+ * Sort issues by file path, then line, then column, then rule id.
+ * ast-grep reports files in whatever order its worker threads finish, so output is sorted
+ * to stay stable between runs. Paths are compared by code unit, independent of locale.
+ * Findings of one rule at the same spot are ordered by where they end and then by message,
+ * so no two distinct issues are left in the order they happened to arrive in.
+ */
+export function sortIssues(issues: LintIssue[]): LintIssue[] {
+	return [...issues].sort(
+		(a, b) =>
+			compareStrings(a.file, b.file) ||
+			a.line - b.line ||
+			a.column - b.column ||
+			compareStrings(a.ruleId, b.ruleId) ||
+			(a.endLine ?? 0) - (b.endLine ?? 0) ||
+			(a.endColumn ?? 0) - (b.endColumn ?? 0) ||
+			compareStrings(a.message, b.message),
+	)
+}
+
+function compareStrings(a: string, b: string): number {
+	if (a === b) return 0
+	return a < b ? -1 : 1
+}
+
 /**
  * Returns a pluralized string based on the count.
  * @param count The count to check
@@ -305,8 +630,27 @@ export function pluralize(count: number, singular: string, plural?: string): str
 	return count === 1 ? singular : (plural ?? `${singular}s`)
 }
 
+// Set by --quiet: diagnostics are dropped and only the exit code reports the result
+let quiet = false
+
+export function setQuiet(value: boolean): void {
+	quiet = value
+}
+
+export function isQuiet(): boolean {
+	return quiet
+}
+
 export function warn(message: string): void {
-	console.error(`[tempo-lints] warning: ${message}`)
+	if (!quiet) {
+		console.error(`[tempo-lints] warning: ${message}`)
+	}
+}
+
+export function reportError(message: string): void {
+	if (!quiet) {
+		console.error(`[tempo-lints] error: ${message}`)
+	}
 }
 
 function safeParseJSON<T>(input: string): { data: T | null; error: Error | null } {
@@ -0,0 +1,94 @@
+import { describe, expect, it } from 'vitest'
+import {
+	createStats,
+	formatDuration,
+	formatStats,
+	recordRuleTime,
+	startPhase,
+	timePhase,
+} from './stats.ts'
+
+// Stats of a run that started at time 0, with fixed phase timings
+function finishedStats(slowest = 0) {
+	const stats = createStats(slowest)
+	stats.startedAt = 0
+	stats.phases = { discovery: 12, scan: 840, rules: 65, report: 3 }
+	return stats
+}
+
+describe('formatDuration', () => {
+	it('should print milliseconds under a second and seconds above', () => {
+		expect(formatDuration(0.4)).toBe('0ms')
+		expect(formatDuration(849.6)).toBe('850ms')
+		expect(formatDuration(1240)).toBe('1.24s')
+	})
+})
+
+describe('timePhase', () => {
+	it('should add the time a step took to its phase and return its result', () => {
+		const stats = createStats()
+
+		expect(timePhase(stats, 'scan', () => 42)).toBe(42)
+		expect(stats.phases.scan).toBeGreaterThanOrEqual(0)
+		expect(timePhase(null, 'scan', () => 'off')).toBe('off')
+	})
+
+	it('should count a step that throws', () => {
+		const stats = createStats()
+		stats.phases.rules = -1
+
+		expect(() =>
+			timePhase(stats, 'rules', () => {
+				throw new Error('boom')
+			}),
+		).toThrow('boom')
+		expect(stats.phases.rules).toBeGreaterThanOrEqual(-1)
+		expect(stats.phases.rules).not.toBe(-1)
+	})
+
+	it('should time steps that finish in a callback', () => {
+		const stats = createStats()
+		stats.phases.scan = -1
+		startPhase(stats, 'scan')()
+
+		expect(stats.phases.scan).not.toBe(-1)
+		expect(() => startPhase(null, 'scan')()).not.toThrow()
+	})
+})
+
+describe('formatStats', () => {
+	it('should print the total and each phase', () => {
+		expect(formatStats(finishedStats(), 3, undefined, 1500)).toBe(
+			[
+				'Stats: 1.50s total, 3 files scanned',
+				'  Finding files  12ms   walking the scan paths and checking the cache',
+				'  Scanning       840ms  ast-grep parses the files and runs the YAML rules in one pass',
+				'  CLI rules      65ms   path rules, plugins and finishing YAML rule findings',
+				'  Reporting      3ms    filtering, fixes and output',
+			].join('\n'),
+		)
+	})
+
+	it('should say how many files came from the cache', () => {
+		const stats = finishedStats()
+		stats.filesCached = 2
+
+		expect(formatStats(stats, 3, undefined, 10)).toContain(
+			'Stats: 10ms total, 3 files scanned (2 from the cache)',
+		)
+	})
+
+	it('should list the slowest rules and, when asked, the slowest files', () => {
+		const stats = finishedStats(1)
+		recordRuleTime(stats, 'no-todo-file', '/repo/src/lib.rs', 20)
+		recordRuleTime(stats, 'crate-name', '/repo/src/lib.rs', 5)
+		recordRuleTime(stats, 'no-todo-file', '/repo/src/main.rs', 40)
+		const output = formatStats(stats, 2, (file) => file.replace('/repo/', ''), 1000)
+
+		expect(output).toContain(
+			'Slowest CLI rules:\n  no-todo-file  60ms\n  crate-name    5ms\n' +
+				'Slowest files in CLI rules:\n  src/main.rs  40ms',
+		)
+		expect(formatStats(finishedStats(), 2, undefined, 1000)).not.toContain('Slowest')
+	})
+})
@@ -0,0 +1,123 @@
+import { pluralize } from './shared.ts'
+
+// Steps of a run timed by --stats, in the order they happen
+export const STATS_PHASES = ['discovery', 'scan', 'rules', 'report'] as const
+export type StatsPhase = (typeof STATS_PHASES)[number]
+
+// What each step covers, as printed
+const PHASE_LABELS: Record<StatsPhase, [string, string]> = {
+	discovery: ['Finding files', 'walking the scan paths and checking the cache'],
+	scan: ['Scanning', 'ast-grep parses the files and runs the YAML rules in one pass'],
+	rules: ['CLI rules', 'path rules, plugins and finishing YAML rule findings'],
+	report: ['Reporting', 'filtering, fixes and output'],
+}
+
+// How many of the slowest CLI rules are listed
+const SLOWEST_RULES = 5
+
+// Wall-clock timings of a single run, in milliseconds
+export interface RunStats {
+	startedAt: number
+	phases: Record<StatsPhase, number>
+	// Time the CLI's own rules took, by rule id and by file
+	rules: Map<string, number>
+	files: Map<string, number>
+	// Files whose findings came from the cache instead of a scan
+	filesCached: number
+	// How many of the slowest files to list, from --slowest
+	slowest: number
+}
+
+export function createStats(slowest = 0): RunStats {
+	return {
+		startedAt: performance.now(),
+		phases: { discovery: 0, scan: 0, rules: 0, report: 0 },
+		rules: new Map(),
+		files: new Map(),
+		filesCached: 0,
+		slowest,
+	}
+}
+
+// Run `step` and add the time it took to `phase`; just runs it when stats are off
+export function timePhase<T>(stats: RunStats | null, phase: StatsPhase, step: () => T): T {
+	if (stats === null) return step()
+	const started = performance.now()
+	try {
+		return step()
+	} finally {
+		stats.phases[phase] += performance.now() - started
+	}
+}
+
+// Start timing `phase` for a step that finishes in a callback; call the result when it's done
+export function startPhase(stats: RunStats | null, phase: StatsPhase): () => void {
+	if (stats === null) return () => {}
+	const started = performance.now()
+	return () => {
+		stats.phases[phase] += performance.now() - started
+	}
+}
+
+// Add how long the CLI rule `ruleId` took on `file`
+export function recordRuleTime(stats: RunStats, ruleId: string, file: string, ms: number): void {
+	stats.rules.set(ruleId, (stats.rules.get(ruleId) ?? 0) + ms)
+	stats.files.set(file, (stats.files.get(file) ?? 0) + ms)
+}
+
+// e.g. `850ms` or `1.24s`
+export function formatDuration(ms: number): string {
+	return ms < 1000 ? `${Math.round(ms)}ms` : `${(ms / 1000).toFixed(2)}s`
+}
+
+/**
+ * The --stats report: total wall time, each phase, the slowest CLI rules and, with `slowest`,
+ * the files those rules took longest on. ast-grep doesn't time its work per rule or file, so
+ * only the CLI's own work is broken down: path and plugin rules, and the passes finishing
+ * candidates of rules like max-function-length under the id of the rule they finish.
+ * `displayPath` turns a file into the path shown, like in the findings.
+ */
+export function formatStats(
+	stats: RunStats,
+	filesScanned: number,
+	displayPath: (file: string) => string = (file) => file,
+	now: number = performance.now(),
+): string {
+	const cached = stats.filesCached > 0 ? ` (${stats.filesCached} from the cache)` : ''
+	const files = `${filesScanned} ${pluralize(filesScanned, 'file')} scanned${cached}`
+	const lines = [`Stats: ${formatDuration(now - stats.startedAt)} total, ${files}`]
+	const rows = STATS_PHASES.map((phase): [string, string, string] => {
+		const [label, note] = PHASE_LABELS[phase]
+		return [label, formatDuration(stats.phases[phase]), note]
+	})
+	lines.push(...formatTable(rows))
+
+	const rules = slowestEntries(stats.rules, SLOWEST_RULES)
+	if (rules.length > 0) {
+		lines.push('Slowest CLI rules:')
+		lines.push(...formatTable(rules.map(([ruleId, ms]) => [ruleId, formatDuration(ms), ''])))
+	}
+	const slowFiles = slowestEntries(stats.files, stats.slowest)
+	if (slowFiles.length > 0) {
+		lines.push('Slowest files in CLI rules:')
+		lines.push(
+			...formatTable(slowFiles.map(([file, ms]) => [displayPath(file), formatDuration(ms), ''])),
+		)
+	}
+	return lines.join('\n')
+}
+
+// The `count` largest entries, largest first, ties by key
+function slowestEntries(times: Map<string, number>, count: number): [string, number][] {
+	return [...times]
+		.sort(([keyA, a], [keyB, b]) => b - a || (keyA < keyB ? -1 : 1))
+		.slice(0, count)
+}
+
+function formatTable(rows: [string, string, string][]): string[] {
+	const width = Math.max(...rows.map(([label]) => label.length))
+	const timeWidth = Math.max(...rows.map(([, time]) => time.length))
+	return rows.map(([label, time, note]) =>
+		`  ${label.padEnd(width)}  ${note === '' ? time : `${time.padEnd(timeWidth)}  ${note}`}`,
+	)
+}
@@ -0,0 +1,129 @@
+import { describe, expect, it } from 'vitest'
+import { parseToml, TomlError } from './toml.ts'
+
+describe('parseToml', () => {
+	it('should parse tables with string keys and values', () => {
+		const input = `
+# Project lint settings
+[rules]
+no-dbg-macro = "warning" # inline comment
+"no-unwrap-in-lib" = 'error'
+`
+
+		expect(parseToml(input)).toEqual({
+			rules: { 'no-dbg-macro': 'warning', 'no-unwrap-in-lib': 'error' },
+		})
+	})
+
+	it('should parse booleans, numbers and escapes', () => {
+		const input = 'a = true\nb = false\nc = 1_000\nd = -1.5\ne = "tab\\there \\u00e9"\n'
+
+		expect(parseToml(input)).toEqual({ a: true, b: false, c: 1000, d: -1.5, e: 'tab\there é' })
+	})
+
+	it('should parse multi-line arrays and inline tables', () => {
+		const input = `
+exclude = [
+	"target/**", # build output
+	"vendor/**",
+]
+limits = { max = 80, name = "fn" }
+`
+
+		expect(parseToml(input)).toEqual({
+			exclude: ['target/**', 'vendor/**'],
+			limits: { max: 80, name: 'fn' },
+		})
+	})
+
+	it('should parse dotted keys and nested tables', () => {
+		const input = '[rules.no-dbg-macro]\nlevel = "off"\n\n[a]\nb.c = 1\n'
+
+		expect(parseToml(input)).toEqual({
+			rules: { 'no-dbg-macro': { level: 'off' } },
+			a: { b: { c: 1 } },
+		})
+	})
+
+	it('should parse arrays of tables', () => {
+		const input = '[[overrides]]\npath = "src/**"\n\n[[overrides]]\npath = "tests/**"\n'
+
+		expect(parseToml(input)).toEqual({
+			overrides: [{ path: 'src/**' }, { path: 'tests/**' }],
+		})
+	})
+
+	it('should parse multi-line strings', () => {
+		const input = [
+			'description = """',
+			'Lints for \\',
+			'    Tempo "projects""""',
+			"pattern = '''",
+			'\\d+ # not a comment',
+			"'''",
+			'after = 1',
+			'',
+		].join('\n')
+
+		expect(parseToml(input)).toEqual({
+			description: 'Lints for Tempo "projects"',
+			pattern: '\\d+ # not a comment\n',
+			after: 1,
+		})
+		expect(() => parseToml('a = """open\n')).toThrow('unterminated string')
+		expect(() => parseToml('a = """\n\n"""\nb = 2 3\n')).toThrow('line 4:')
+	})
+
+	it('should report the line of a syntax error', () => {
+		expect(() => parseToml('[rules]\nno-dbg-macro = warning\n')).toThrow(
+			"line 2: unexpected value 'warning'",
+		)
+		expect(() => parseToml('a = "unterminated\n')).toThrow(TomlError)
+	})
+
+	it('should reject duplicate keys', () => {
+		expect(() => parseToml('a = 1\na = 2\n')).toThrow("line 2: duplicate key 'a'")
+	})
+
+	it('should reject trailing content after a value', () => {
+		expect(() => parseToml('a = 1 2\n')).toThrow('expected a new line after value')
+	})
+
+	it('should parse hex, octal and binary integers', () => {
+		const input = 'hex = 0xdead_BEEF\noctal = 0o755\nbinary = 0b1101\n'
+
+		expect(parseToml(input)).toEqual({ hex: 0xdeadbeef, octal: 0o755, binary: 0b1101 })
+	})
+
+	it('should parse inf and nan', () => {
+		const input = 'a = inf\nb = -inf\nc = [+nan, 1e3]\n'
+
+		expect(parseToml(input)).toEqual({
+			a: Number.POSITIVE_INFINITY,
+			b: Number.NEGATIVE_INFINITY,
+			c: [Number.NaN, 1000],
+		})
+	})
+
+	it('should reject values that only start like a boolean or number', () => {
+		expect(() => parseToml('a = truex\n')).toThrow("line 1: unexpected value 'truex'")
+		expect(() => parseToml('a = [false0]\n')).toThrow("unexpected value 'false0'")
+		expect(() => parseToml('a = 12abc\n')).toThrow("unexpected value '12abc'")
+		expect(() => parseToml('a = infinity\n')).toThrow("unexpected value 'infinity'")
+	})
+
+	it('should reject malformed numbers', () => {
+		expect(() => parseToml('a = 012\n')).toThrow("unexpected value '012'")
+		expect(() => parseToml('a = 1__000\n')).toThrow("unexpected value '1__000'")
+		expect(() => parseToml('a = 1.\n')).toThrow("unexpected value '1.'")
+		expect(() => parseToml('a = 0xg\n')).toThrow("unexpected value '0xg'")
+		expect(() => parseToml('a = -0x1\n')).toThrow("unexpected value '-0x1'")
+		expect(() => parseToml('a = 0x1_0000_0000_0000_0000\n')).toThrow('out of range')
+	})
+
+	it('should report dates as unsupported', () => {
+		expect(() => parseToml('released = 1979-05-27\n')).toThrow(
+			"dates and times are not supported: '1979-05-27'",
+		)
+	})
+})
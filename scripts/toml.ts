@@ -0,0 +1,402 @@
+/**
+ * Minimal TOML parser for `tempo-lints.toml`.
+ *
+ * Supports the subset of TOML 1.0 that the config file and Cargo manifests need: comments,
+ * `[tables]`, `[[arrays of tables]]`, bare/quoted/dotted keys, basic and literal strings
+ * (including multi-line), integers (including hex, octal and binary), floats (including `inf`
+ * and `nan`), booleans, arrays (including multi-line) and inline tables. Dates are not
+ * supported and are reported as errors.
+ */
+
+export type TomlValue = string | number | boolean | TomlValue[] | TomlTable
+export interface TomlTable {
+	[key: string]: TomlValue
+}
+
+export class TomlError extends Error {
+	constructor(
+		public line: number,
+		message: string,
+	) {
+		super(`line ${line}: ${message}`)
+		this.name = 'TomlError'
+	}
+}
+
+const BARE_KEY = /^[A-Za-z0-9_-]+/
+// Booleans, numbers and dates run up to the next delimiter, so `truex` or `1x` is one bad value
+const SCALAR = /^[^\s,\]}#]+/
+// Underscores are only allowed between digits, and decimal integers have no leading zeros
+const DECIMAL = /^[+-]?(?:0|[1-9](?:_?\d)*)$/
+const HEX = /^0x[0-9A-Fa-f](?:_?[0-9A-Fa-f])*$/
+const OCTAL = /^0o[0-7](?:_?[0-7])*$/
+const BINARY = /^0b[01](?:_?[01])*$/
+const FLOAT = /^[+-]?(?:0|[1-9](?:_?\d)*)(?:\.\d(?:_?\d)*)?(?:[eE][+-]?\d(?:_?\d)*)?$/
+const SPECIAL_FLOAT = /^[+-]?(inf|nan)$/
+const DATE_OR_TIME = /^\d{4}-\d{2}-\d{2}|^\d{2}:\d{2}/
+const RADIX_PREFIXES: Record<string, number> = { '0x': 16, '0o': 8, '0b': 2 }
+
+class Parser {
+	private pos = 0
+	private line = 1
+
+	constructor(private input: string) {}
+
+	parse(): TomlTable {
+		const root: TomlTable = {}
+		let current = root
+
+		while (true) {
+			this.skipWhitespaceAndNewlines()
+			if (this.done()) break
+
+			if (this.peek() === '[') {
+				current = this.parseTableHeader(root)
+			} else {
+				const keys = this.parseKey()
+				this.skipWhitespace()
+				this.expect('=')
+				this.skipWhitespace()
+				const value = this.parseValue()
+				this.assign(current, keys, value)
+			}
+
+			this.expectLineEnd()
+		}
+
+		return root
+	}
+
+	private parseTableHeader(root: TomlTable): TomlTable {
+		this.expect('[')
+		const isArray = this.peek() === '['
+		if (isArray) this.pos++
+
+		this.skipWhitespace()
+		const keys = this.parseKey()
+		this.skipWhitespace()
+		this.expect(']')
+		if (isArray) this.expect(']')
+
+		const parent = this.descend(root, keys.slice(0, -1))
+		const last = keys[keys.length - 1]!
+
+		if (isArray) {
+			const existing = parent[last]
+			const list = existing === undefined ? [] : existing
+			if (!Array.isArray(list)) {
+				throw this.error(`'${keys.join('.')}' is already defined as a non-array`)
+			}
+			const table: TomlTable = {}
+			list.push(table)
+			parent[last] = list
+			return table
+		}
+
+		const existing = parent[last]
+		if (existing === undefined) {
+			const table: TomlTable = {}
+			parent[last] = table
+			return table
+		}
+		if (!isTable(existing)) {
+			throw this.error(`'${keys.join('.')}' is already defined as a non-table`)
+		}
+		return existing
+	}
+
+	// Walks (and creates) the tables along a dotted key path
+	private descend(table: TomlTable, keys: string[]): TomlTable {
+		let current = table
+		for (const key of keys) {
+			let next = current[key]
+			if (next === undefined) {
+				next = {}
+				current[key] = next
+			}
+			if (Array.isArray(next)) {
+				next = next[next.length - 1]
+			}
+			if (!isTable(next)) {
+				throw this.error(`'${key}' is already defined as a non-table`)
+			}
+			current = next
+		}
+		return current
+	}
+
+	private assign(table: TomlTable, keys: string[], value: TomlValue): void {
+		const parent = this.descend(table, keys.slice(0, -1))
+		const last = keys[keys.length - 1]!
+		if (last in parent) {
+			throw this.error(`duplicate key '${keys.join('.')}'`)
+		}
+		parent[last] = value
+	}
+
+	private parseKey(): string[] {
+		const keys: string[] = []
+		while (true) {
+			this.skipWhitespace()
+			const ch = this.peek()
+			if (ch === '"') {
+				keys.push(this.parseBasicString())
+			} else if (ch === "'") {
+				keys.push(this.parseLiteralString())
+			} else {
+				const match = this.rest().match(BARE_KEY)
+				if (!match) throw this.error('expected a key')
+				keys.push(match[0])
+				this.pos += match[0].length
+			}
+			this.skipWhitespace()
+			if (this.peek() !== '.') return keys
+			this.pos++
+		}
+	}
+
+	private parseValue(): TomlValue {
+		const ch = this.peek()
+		if (ch === '"') {
+			const multiline = this.input.startsWith('"""', this.pos)
+			return multiline ? this.parseMultilineString('"') : this.parseBasicString()
+		}
+		if (ch === "'") {
+			const multiline = this.input.startsWith("'''", this.pos)
+			return multiline ? this.parseMultilineString("'") : this.parseLiteralString()
+		}
+		if (ch === '[') return this.parseArray()
+		if (ch === '{') return this.parseInlineTable()
+
+		const token = this.rest().match(SCALAR)?.[0]
+		if (!token) throw this.error('expected a value')
+		const value = this.parseScalar(token)
+		this.pos += token.length
+		return value
+	}
+
+	private parseScalar(token: string): TomlValue {
+		if (token === 'true') return true
+		if (token === 'false') return false
+
+		const digits = token.replaceAll('_', '')
+		if (HEX.test(token) || OCTAL.test(token) || BINARY.test(token)) {
+			const value = Number.parseInt(digits.slice(2), RADIX_PREFIXES[digits.slice(0, 2)])
+			return this.checkInteger(token, value)
+		}
+		if (DECIMAL.test(token)) return this.checkInteger(token, Number(digits))
+		if (FLOAT.test(token)) return Number(digits)
+
+		const special = token.match(SPECIAL_FLOAT)
+		if (special) {
+			if (special[1] === 'nan') return Number.NaN
+			return token.startsWith('-') ? Number.NEGATIVE_INFINITY : Number.POSITIVE_INFINITY
+		}
+
+		if (DATE_OR_TIME.test(token)) throw this.error(`dates and times are not supported: '${token}'`)
+		throw this.error(`unexpected value '${token}'`)
+	}
+
+	private checkInteger(token: string, value: number): number {
+		if (!Number.isSafeInteger(value)) throw this.error(`integer '${token}' is out of range`)
+		return value
+	}
+
+	private parseBasicString(): string {
+		this.expect('"')
+		let result = ''
+		while (true) {
+			const ch = this.peek()
+			if (ch === undefined || ch === '\n') throw this.error('unterminated string')
+			this.pos++
+			if (ch === '"') return result
+			if (ch !== '\\') {
+				result += ch
+				continue
+			}
+			result += this.parseEscape()
+		}
+	}
+
+	// `"""basic"""` or `'''literal'''`; a newline right after the opening quotes is dropped
+	private parseMultilineString(quote: '"' | "'"): string {
+		const delimiter = quote.repeat(3)
+		this.pos += 3
+		if (this.peek() === '\r' && this.input[this.pos + 1] === '\n') this.pos++
+		if (this.peek() === '\n') {
+			this.pos++
+			this.line++
+		}
+
+		let result = ''
+		while (true) {
+			if (this.done()) throw this.error('unterminated string')
+			if (this.input.startsWith(delimiter, this.pos)) {
+				this.pos += 3
+				// Up to two quotes may directly precede the closing delimiter
+				for (let i = 0; i < 2 && this.peek() === quote; i++) {
+					result += quote
+					this.pos++
+				}
+				return result
+			}
+
+			const ch = this.peek()!
+			this.pos++
+			if (ch === '\n') this.line++
+			if (quote === "'" || ch !== '\\') {
+				result += ch
+				continue
+			}
+			// A backslash ending the line trims the line break and leading whitespace after it
+			const lineEnd = this.input.indexOf('\n', this.pos)
+			if (lineEnd !== -1 && this.input.slice(this.pos, lineEnd).trim() === '') {
+				while (/[ \t\r\n]/.test(this.peek() ?? '')) {
+					if (this.peek() === '\n') this.line++
+					this.pos++
+				}
+				continue
+			}
+			result += this.parseEscape()
+		}
+	}
+
+	// The character a backslash escape stands for; `pos` is just past the backslash
+	private parseEscape(): string {
+		const escape = this.peek()
+		this.pos++
+		switch (escape) {
+			case 'n':
+				return '\n'
+			case 't':
+				return '\t'
+			case 'r':
+				return '\r'
+			case '"':
+				return '"'
+			case '\\':
+				return '\\'
+			case 'u':
+			case 'U': {
+				const length = escape === 'u' ? 4 : 8
+				const hex = this.input.slice(this.pos, this.pos + length)
+				if (!/^[0-9A-Fa-f]+$/.test(hex) || hex.length !== length) {
+					throw this.error(`invalid unicode escape '\\${escape}${hex}'`)
+				}
+				this.pos += length
+				return String.fromCodePoint(Number.parseInt(hex, 16))
+			}
+			default:
+				throw this.error(`invalid escape '\\${escape ?? ''}'`)
+		}
+	}
+
+	private parseLiteralString(): string {
+		this.expect("'")
+		const end = this.input.indexOf("'", this.pos)
+		const newline = this.input.indexOf('\n', this.pos)
+		if (end === -1 || (newline !== -1 && newline < end)) throw this.error('unterminated string')
+		const result = this.input.slice(this.pos, end)
+		this.pos = end + 1
+		return result
+	}
+
+	private parseArray(): TomlValue[] {
+		this.expect('[')
+		const items: TomlValue[] = []
+		while (true) {
+			this.skipWhitespaceAndNewlines()
+			if (this.peek() === ']') {
+				this.pos++
+				return items
+			}
+			items.push(this.parseValue())
+			this.skipWhitespaceAndNewlines()
+			if (this.peek() === ',') {
+				this.pos++
+			} else if (this.peek() !== ']') {
+				throw this.error("expected ',' or ']' in array")
+			}
+		}
+	}
+
+	private parseInlineTable(): TomlTable {
+		this.expect('{')
+		const table: TomlTable = {}
+		this.skipWhitespace()
+		if (this.peek() === '}') {
+			this.pos++
+			return table
+		}
+		while (true) {
+			const keys = this.parseKey()
+			this.skipWhitespace()
+			this.expect('=')
+			this.skipWhitespace()
+			this.assign(table, keys, this.parseValue())
+			this.skipWhitespace()
+			if (this.peek() === '}') {
+				this.pos++
+				return table
+			}
+			this.expect(',')
+		}
+	}
+
+	private skipWhitespace(): void {
+		while (this.peek() === ' ' || this.peek() === '\t') this.pos++
+	}
+
+	private skipComment(): void {
+		if (this.peek() !== '#') return
+		while (!this.done() && this.peek() !== '\n') this.pos++
+	}
+
+	private skipWhitespaceAndNewlines(): void {
+		while (true) {
+			this.skipWhitespace()
+			this.skipComment()
+			if (this.peek() === '\r' && this.input[this.pos + 1] === '\n') this.pos++
+			if (this.peek() !== '\n') return
+			this.pos++
+			this.line++
+		}
+	}
+
+	private expectLineEnd(): void {
+		this.skipWhitespace()
+		this.skipComment()
+		if (this.peek() === '\r') this.pos++
+		if (this.done()) return
+		if (this.peek() !== '\n') throw this.error('expected a new line after value')
+	}
+
+	private expect(ch: string): void {
+		if (this.peek() !== ch) throw this.error(`expected '${ch}'`)
+		this.pos++
+	}
+
+	private peek(): string | undefined {
+		return this.input[this.pos]
+	}
+
+	private rest(): string {
+		return this.input.slice(this.pos)
+	}
+
+	private done(): boolean {
+		return this.pos >= this.input.length
+	}
+
+	private error(message: string): TomlError {
+		return new TomlError(this.line, message)
+	}
+}
+
+export function isTable(value: TomlValue | undefined): value is TomlTable {
+	return typeof value === 'object' && value !== null && !Array.isArray(value)
+}
+
+export function parseToml(input: string): TomlTable {
+	return new Parser(input).parse()
+}
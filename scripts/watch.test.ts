@@ -0,0 +1,99 @@
+import fs from 'node:fs'
+import os from 'node:os'
+import path from 'node:path'
+import { afterEach, beforeEach, describe, expect, it } from 'vitest'
+import { debounce, isWatchedChange, watchPath } from './watch.ts'
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms))
+
+describe('debounce', () => {
+	it('should call once after a burst of triggers', async () => {
+		let calls = 0
+		const debounced = debounce(20, () => calls++)
+
+		debounced.trigger()
+		debounced.trigger()
+		debounced.trigger()
+		expect(calls).toBe(0)
+
+		await sleep(60)
+		expect(calls).toBe(1)
+	})
+
+	it('should call again for triggers after the previous call', async () => {
+		let calls = 0
+		const debounced = debounce(10, () => calls++)
+
+		debounced.trigger()
+		await sleep(40)
+		debounced.trigger()
+		await sleep(40)
+		expect(calls).toBe(2)
+	})
+
+	it('should not call after cancel', async () => {
+		let calls = 0
+		const debounced = debounce(10, () => calls++)
+
+		debounced.trigger()
+		debounced.cancel()
+		await sleep(40)
+		expect(calls).toBe(0)
+	})
+})
+
+describe('isWatchedChange', () => {
+	it('should accept source files of the language', () => {
+		expect(isWatchedChange('src/lib.rs', ['.rs'])).toBe(true)
+		expect(isWatchedChange('src/index.mts', ['.ts', '.mts'])).toBe(true)
+	})
+
+	it('should accept paths that may be directories', () => {
+		expect(isWatchedChange('src/parser', ['.rs'])).toBe(true)
+	})
+
+	it('should ignore other files', () => {
+		expect(isWatchedChange('target/tempo-lints-cache/rust.json', ['.rs'])).toBe(false)
+		expect(isWatchedChange('src/lib.rs.swp', ['.rs'])).toBe(false)
+	})
+})
+
+describe('watchPath', () => {
+	let tmpDir: string
+	let stop: (() => void) | null
+
+	beforeEach(() => {
+		tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'tempo-lints-watch-'))
+		stop = null
+	})
+
+	afterEach(() => {
+		stop?.()
+		fs.rmSync(tmpDir, { recursive: true, force: true })
+	})
+
+	it('should report files created in new directories', async () => {
+		const changes: string[] = []
+		stop = watchPath(tmpDir, (file) => changes.push(file))
+
+		fs.mkdirSync(path.join(tmpDir, 'src'))
+		await sleep(50)
+		fs.writeFileSync(path.join(tmpDir, 'src', 'lib.rs'), 'fn main() {}\n')
+		await sleep(200)
+
+		expect(changes).toContain(path.join(tmpDir, 'src', 'lib.rs'))
+	})
+
+	it('should report changes to a watched file as the file itself', async () => {
+		const file = path.join(tmpDir, 'lib.rs')
+		fs.writeFileSync(file, 'fn a() {}\n')
+		const changes: string[] = []
+		stop = watchPath(file, (changed) => changes.push(changed))
+
+		fs.appendFileSync(file, 'fn b() {}\n')
+		await sleep(200)
+
+		expect(changes.length).toBeGreaterThan(0)
+		expect(changes.every((changed) => changed === file)).toBe(true)
+	})
+})
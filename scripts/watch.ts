@@ -0,0 +1,71 @@
+import fs from 'node:fs'
+import path from 'node:path'
+
+// How long to wait after the last change before re-linting, so a burst of saves is one run
+export const WATCH_DEBOUNCE_MS = 100
+
+// Clears the screen and the scrollback, then moves the cursor to the top
+export const CLEAR_SCREEN = '\x1b[2J\x1b[3J\x1b[H'
+
+export interface Debounced {
+	trigger: () => void
+	cancel: () => void
+}
+
+/**
+ * Call `fn` once `delayMs` passed without another `trigger()`, so a burst of triggers
+ * results in a single call.
+ */
+export function debounce(delayMs: number, fn: () => void): Debounced {
+	let timer: NodeJS.Timeout | null = null
+
+	const cancel = () => {
+		if (timer !== null) clearTimeout(timer)
+		timer = null
+	}
+
+	return {
+		trigger: () => {
+			cancel()
+			timer = setTimeout(() => {
+				timer = null
+				fn()
+			}, delayMs)
+		},
+		cancel,
+	}
+}
+
+/**
+ * Whether a change to `file` may affect the results: a source file with one of `extensions`,
+ * or a path without an extension, which may be a directory that was created, renamed or
+ * removed along with the files in it.
+ */
+export function isWatchedChange(file: string, extensions: string[]): boolean {
+	const extension = path.extname(file)
+	return extension === '' || extensions.includes(extension)
+}
+
+/**
+ * Watch `target` (a directory, recursively, or a single file) and call `onChange` with the
+ * path of every entry that's created, changed or removed, starting with `target`. Returns a
+ * function that stops watching.
+ */
+export function watchPath(target: string, onChange: (file: string) => void): () => void {
+	const isDirectory = fs.statSync(target).isDirectory()
+
+	let watcher: fs.FSWatcher
+	try {
+		watcher = fs.watch(target, { recursive: isDirectory }, (_event, filename) => {
+			// Some platforms don't say which entry changed, so anything under `target` may have
+			onChange(isDirectory && filename !== null ? path.join(target, filename) : target)
+		})
+	} catch (err) {
+		if ((err as NodeJS.ErrnoException).code === 'ERR_FEATURE_UNAVAILABLE_ON_PLATFORM') {
+			throw new Error('Watching a directory on this platform needs Node.js 20 or later')
+		}
+		throw err
+	}
+
+	return () => watcher.close()
+}
@@ -0,0 +1,101 @@
+import { describe, expect, it } from 'vitest'
+import type { LintIssue } from './shared.ts'
+import { applyWildcardImports, globImportPath, isAllowedGlob } from './wildcard-imports.ts'
+
+function globImport(declaration: string, glob: string, line = 1): LintIssue {
+	const declarationLines = declaration.split('\n')
+	const globLine = declarationLines.findIndex((text) => text.includes(glob))
+	// The declaration starts at column 1, so every line's columns line up with the file's
+	const column = declarationLines[globLine]!.indexOf(glob) + 1
+	return {
+		ruleId: 'no-wildcard-import',
+		severity: 'warning',
+		message: `Glob import \`${glob}\` hides where the names it brings in come from.`,
+		file: 'src/lib.rs',
+		line: line + globLine,
+		column,
+		captures: {
+			GLOB: { text: glob, line: line + globLine, column },
+			DECLARATION: { text: declaration, line, column: 1 },
+		},
+	}
+}
+
+describe('globImportPath', () => {
+	it('should return a top-level glob as written', () => {
+		expect(globImportPath('use foo::*;', 4, 'foo::*')).toBe('foo::*')
+	})
+
+	it('should prefix a glob nested in groups with their paths', () => {
+		expect(globImportPath('use foo::{bar::*, baz};', 10, 'bar::*')).toBe('foo::bar::*')
+		const nested = 'pub(crate) use a::{b::{c}, d::{e::*}};'
+		expect(globImportPath(nested, nested.indexOf('e::*'), 'e::*')).toBe('a::d::e::*')
+	})
+
+	it('should ignore whitespace and line breaks in the declaration', () => {
+		const declaration = 'use foo::{\n    bar,\n    baz :: *,\n};'
+		expect(globImportPath(declaration, declaration.indexOf('baz'), 'baz :: *')).toBe(
+			'foo::baz::*',
+		)
+	})
+})
+
+describe('isAllowedGlob', () => {
+	it('should allow modules ending in an allowed entry', () => {
+		expect(isAllowedGlob('std::io::prelude::*', ['prelude'])).toBe(true)
+		expect(isAllowedGlob('::diesel::dsl::*', ['diesel::dsl::*'])).toBe(true)
+		expect(isAllowedGlob('crate::prelude::*', ['crate::prelude'])).toBe(true)
+	})
+
+	it('should not allow other modules', () => {
+		expect(isAllowedGlob('crate::preludes::*', ['prelude'])).toBe(false)
+		expect(isAllowedGlob('prelude::extra::*', ['prelude'])).toBe(false)
+		expect(isAllowedGlob('foo::*', ['*'])).toBe(false)
+	})
+})
+
+describe('applyWildcardImports', () => {
+	it('should name the full path of a nested glob', () => {
+		const [issue] = applyWildcardImports([globImport('use foo::{bar::*, baz};', 'bar::*')], {
+			rules: {},
+		})
+
+		expect(issue?.message).toBe(
+			'Glob import `foo::bar::*` hides where the names it brings in come from.',
+		)
+	})
+
+	it('should find globs on later lines of the declaration', () => {
+		const declaration = 'use foo::{\n    bar::{self, *},\n    baz::*,\n};'
+		const [issue] = applyWildcardImports([globImport(declaration, 'baz::*', 3)], { rules: {} })
+
+		expect(issue?.message).toContain('`foo::baz::*`')
+	})
+
+	it('should drop preludes by default', () => {
+		const issues = [globImport('use std::io::prelude::*;', 'std::io::prelude::*')]
+
+		expect(applyWildcardImports(issues, { rules: {} })).toEqual([])
+	})
+
+	it('should replace the allowed modules with allow-modules', () => {
+		const config = {
+			rules: {},
+			ruleOptions: { 'no-wildcard-import': { allowModules: ['diesel::dsl'] } },
+		}
+		const issues = [
+			globImport('use diesel::dsl::*;', 'diesel::dsl::*'),
+			globImport('use std::io::prelude::*;', 'std::io::prelude::*'),
+		]
+
+		expect(applyWildcardImports(issues, config).map((issue) => issue.captures?.GLOB?.text)).toEqual(
+			['std::io::prelude::*'],
+		)
+	})
+
+	it('should leave other rules alone', () => {
+		const issue = { ...globImport('use foo::*;', 'foo::*'), ruleId: 'no-dbg-macro' }
+
+		expect(applyWildcardImports([issue], { rules: {} })).toEqual([issue])
+	})
+})
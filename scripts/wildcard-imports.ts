@@ -0,0 +1,93 @@
+import { type LintConfig, WILDCARD_IMPORT_RULE_ID } from './config.ts'
+import type { IssueCapture, LintIssue } from './shared.ts'
+
+// Modules whose glob imports are allowed when `allow-modules` isn't set
+export const DEFAULT_ALLOWED_GLOB_MODULES = ['prelude']
+
+/**
+ * Spell out the full path a glob in a `use` declaration imports. `offset` is where `glob`
+ * starts in `declaration`, so the glob `bar::*` in `use foo::{bar::*, baz};` is `foo::bar::*`.
+ */
+export function globImportPath(declaration: string, offset: number, glob: string): string {
+	const head = declaration.slice(0, offset)
+	const keyword = /\buse\b/.exec(head)
+	// Path prefixes of the `{...}` groups the glob is nested in
+	const prefixes: string[] = []
+	let current = ''
+	for (const ch of head.slice(keyword ? keyword.index + 'use'.length : 0)) {
+		if (ch === '{') {
+			prefixes.push(current)
+			current = ''
+		} else if (ch === '}') {
+			prefixes.pop()
+			current = ''
+		} else if (ch === ',') {
+			current = ''
+		} else if (!/\s/.test(ch)) {
+			current += ch
+		}
+	}
+	return prefixes.join('') + glob.replace(/\s+/g, '')
+}
+
+/**
+ * Whether a glob import of `path` is covered by one of the `allowed` modules: the module is
+ * the entry itself or a path ending in it, so `prelude` covers `std::io::prelude::*`.
+ */
+export function isAllowedGlob(path: string, allowed: string[]): boolean {
+	const module = normalizeModule(path)
+	return allowed
+		.map(normalizeModule)
+		.some((entry) => entry !== '' && (module === entry || module.endsWith(`::${entry}`)))
+}
+
+// Entries may be written with a leading `::` or a trailing `::*`, like the import itself
+function normalizeModule(path: string): string {
+	return path
+		.trim()
+		.replace(/^::/, '')
+		.replace(/(?:^|::)\*$/, '')
+}
+
+// Where `position` falls in `text`, which starts at `start` in the same file
+export function offsetWithin(
+	text: string,
+	start: IssueCapture,
+	position: Pick<IssueCapture, 'line' | 'column'>,
+): number {
+	const lines = text.split('\n')
+	const lineIndex = position.line - start.line
+	let offset = 0
+	for (let i = 0; i < lineIndex; i++) {
+		offset += (lines[i]?.length ?? 0) + 1
+	}
+	return offset + position.column - (lineIndex === 0 ? start.column : 1)
+}
+
+/**
+ * Finish the no-wildcard-import rule: name the full imported path in the message and drop
+ * glob imports of allowed modules.
+ */
+export function applyWildcardImports(issues: LintIssue[], config: LintConfig): LintIssue[] {
+	const allowed =
+		config.ruleOptions?.[WILDCARD_IMPORT_RULE_ID]?.allowModules ?? DEFAULT_ALLOWED_GLOB_MODULES
+
+	return issues.flatMap((issue) => {
+		const glob = issue.captures?.GLOB
+		const declaration = issue.captures?.DECLARATION
+		if (issue.ruleId !== WILDCARD_IMPORT_RULE_ID || !glob || !declaration) {
+			return [issue]
+		}
+		const offset = offsetWithin(declaration.text, declaration, glob)
+		const path = globImportPath(declaration.text, offset, glob.text)
+		if (isAllowedGlob(path, allowed)) {
+			return []
+		}
+		return [
+			{
+				...issue,
+				message: `Glob import \`${path}\` hides where the names it brings in come from.`,
+			},
+		]
+	})
+}
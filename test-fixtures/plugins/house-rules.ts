@@ -0,0 +1,16 @@
+import type { Rule } from '../../scripts/plugins.ts'
+
+const LICENSE_HEADER = '// SPDX-License-Identifier:'
+
+// A house rule of the kind a plugin adds: every source file starts with a license header
+const requireLicenseHeader: Rule = {
+	id: 'require-license-header',
+	message: `Start the file with a \`${LICENSE_HEADER}\` comment.`,
+	severity: 'error',
+	language: 'rust',
+	check(file) {
+		return file.source.startsWith(LICENSE_HEADER) ? [] : [{ line: 1, column: 1 }]
+	},
+}
+
+export const rules = [requireLicenseHeader]
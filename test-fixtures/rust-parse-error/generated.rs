@@ -0,0 +1,7 @@
+// Generated from a template with its placeholders left in
+pub fn lookup(key: u32) -> u32 {
+    match key {
+        {{#each entries}} {{key}} => {{value}}, {{/each}}
+        _ => 0,
+    }
+}
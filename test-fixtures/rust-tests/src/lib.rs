@@ -0,0 +1,19 @@
+pub fn parse(input: &str) -> u32 {
+    input.parse().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_numbers() {
+        let value = dbg!(parse("42"));
+        assert_eq!(value, 42);
+    }
+
+    #[test]
+    fn placeholder() {
+        assert!(true);
+    }
+}
@@ -0,0 +1,6 @@
+#[test]
+#[ignore]
+fn fetches_over_the_network() {
+    let body = fetch("https://example.com").unwrap();
+    assert!(!body.is_empty());
+}
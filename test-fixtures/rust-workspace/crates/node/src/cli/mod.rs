@@ -0,0 +1,4 @@
+// Library code, even though it sits under a `cli/` directory
+pub fn parse_port(value: &str) -> u16 {
+    value.parse().unwrap()
+}
@@ -0,0 +1,5 @@
+// The `launcher` binary: panicking on bad input is fine here
+fn main() {
+    let port: u16 = std::env::args().nth(1).unwrap().parse().unwrap();
+    println!("listening on {port}");
+}
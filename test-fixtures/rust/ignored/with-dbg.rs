@@ -0,0 +1,5 @@
+// Listed in ../.ignore; only scanned with --no-ignore
+fn main() {
+    let y = 7;
+    dbg!(y);
+}
@@ -0,0 +1,7 @@
+// Findings that follow-up checks add, like long lines, still come out in line order
+pub fn parse(input: &str) -> u32 {
+    let value = input.trim().parse::<u32>().unwrap();
+    let message = format!("parsed {value} from an input that is long enough to run past the limit");
+    dbg!(message);
+    value
+}
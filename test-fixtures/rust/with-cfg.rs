@@ -0,0 +1,19 @@
+#[cfg(target_os = "windows")]
+mod windows {
+    pub fn open(path: &str) -> std::fs::File {
+        std::fs::File::open(path).unwrap()
+    }
+}
+
+#[cfg(feature = "metrics")]
+pub fn record(value: u64) -> u64 {
+    dbg!(value)
+}
+
+pub fn run(value: u64) -> u64 {
+    #[cfg(not(unix))]
+    {
+        dbg!(value);
+    }
+    value
+}